@@ -0,0 +1,204 @@
+use super::wav::{downmix, resample_linear, SAMPLE_RATE};
+use std::path::Path;
+use symphonia::core::audio::SampleBuffer;
+use symphonia::core::codecs::{DecoderOptions, CODEC_TYPE_NULL};
+use symphonia::core::errors::Error as SymphoniaError;
+use symphonia::core::formats::FormatOptions;
+use symphonia::core::io::MediaSourceStream;
+use symphonia::core::meta::MetadataOptions;
+use symphonia::core::probe::Hint;
+
+/// File extensions of the compressed formats that can be decoded
+pub const SUPPORTED_EXTENSIONS: &[&str] = &["mp3", "ogg", "oga", "flac", "m4a", "mp4", "aac"];
+
+/// Why an audio file could not be decoded
+#[derive(Debug, thiserror::Error)]
+pub enum DecodeError {
+    /// The container format or codec is not supported
+    #[error("Unsupported audio format: {0}")]
+    Unsupported(String),
+    /// The file is in a supported format but its data is damaged
+    #[error("Corrupt audio file: {0}")]
+    Corrupt(String),
+    /// The file could not be read
+    #[error("Failed to read audio file: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+impl From<SymphoniaError> for DecodeError {
+    fn from(error: SymphoniaError) -> Self {
+        match error {
+            SymphoniaError::Unsupported(what) => DecodeError::Unsupported(what.to_string()),
+            SymphoniaError::IoError(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => {
+                DecodeError::Corrupt("unexpected end of file".to_string())
+            }
+            SymphoniaError::IoError(e) => DecodeError::Io(e),
+            other => DecodeError::Corrupt(other.to_string()),
+        }
+    }
+}
+
+/// Returns true if the file extension is one of `SUPPORTED_EXTENSIONS`
+pub fn is_supported_extension(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| SUPPORTED_EXTENSIONS.contains(&ext.to_lowercase().as_str()))
+}
+
+/// Decodes a compressed audio file (MP3, Ogg Vorbis, FLAC, AAC/M4A) into 16kHz mono samples
+///
+/// Packets that fail to decode are skipped, so a few damaged frames don't lose
+/// the whole recording.
+///
+/// # Arguments
+/// * `path` - Audio file to decode; its extension is used as a format hint
+///
+/// # Returns
+/// * `Ok(Vec<f32>)` with samples in the nominal range [-1.0, 1.0]
+/// * `Err(DecodeError::Unsupported)` if the format or codec is not supported
+/// * `Err(DecodeError::Corrupt)` if the file is damaged beyond decoding
+pub fn decode_file(path: &Path) -> Result<Vec<f32>, DecodeError> {
+    let file = std::fs::File::open(path)?;
+    let stream = MediaSourceStream::new(Box::new(file), Default::default());
+
+    let mut hint = Hint::new();
+    if let Some(ext) = path.extension().and_then(|ext| ext.to_str()) {
+        hint.with_extension(ext);
+    }
+
+    let probed = symphonia::default::get_probe().format(
+        &hint,
+        stream,
+        &FormatOptions::default(),
+        &MetadataOptions::default(),
+    )?;
+    let mut format = probed.format;
+
+    let track = format
+        .tracks()
+        .iter()
+        .find(|track| track.codec_params.codec != CODEC_TYPE_NULL)
+        .ok_or_else(|| DecodeError::Unsupported("no audio track found".to_string()))?;
+    let track_id = track.id;
+    let sample_rate = track
+        .codec_params
+        .sample_rate
+        .ok_or_else(|| DecodeError::Corrupt("unknown sample rate".to_string()))?;
+
+    let mut decoder =
+        symphonia::default::get_codecs().make(&track.codec_params, &DecoderOptions::default())?;
+
+    let mut samples = Vec::new();
+    let mut failed_packets = 0usize;
+
+    loop {
+        let packet = match format.next_packet() {
+            Ok(packet) => packet,
+            // Symphonia reports the end of the stream as an EOF error
+            Err(SymphoniaError::IoError(e)) if e.kind() == std::io::ErrorKind::UnexpectedEof => {
+                break
+            }
+            Err(SymphoniaError::ResetRequired) => break,
+            Err(e) => return Err(e.into()),
+        };
+        if packet.track_id() != track_id {
+            continue;
+        }
+
+        match decoder.decode(&packet) {
+            Ok(decoded) => {
+                let spec = *decoded.spec();
+                let mut buffer = SampleBuffer::<f32>::new(decoded.capacity() as u64, spec);
+                buffer.copy_interleaved_ref(decoded);
+                samples.extend(downmix(buffer.samples(), spec.channels.count()));
+            }
+            Err(SymphoniaError::DecodeError(e)) => {
+                log::warn!("Skipping undecodable packet in {:?}: {}", path, e);
+                failed_packets += 1;
+            }
+            Err(e) => return Err(e.into()),
+        }
+    }
+
+    if samples.is_empty() && failed_packets > 0 {
+        return Err(DecodeError::Corrupt(format!(
+            "none of the {} audio packets could be decoded",
+            failed_packets
+        )));
+    }
+
+    log::info!(
+        "Decoded {} samples at {} Hz from {:?}",
+        samples.len(),
+        sample_rate,
+        path
+    );
+    Ok(resample_linear(&samples, sample_rate, SAMPLE_RATE))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    /// 0.1 s of a 440 Hz tone, 8 kHz mono 16-bit FLAC
+    fn fixture(name: &str) -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("tests")
+            .join("fixtures")
+            .join(name)
+    }
+
+    fn temp_file(name: &str, contents: &[u8]) -> PathBuf {
+        let path =
+            std::env::temp_dir().join(format!("rustler_decode_{}_{}", std::process::id(), name));
+        std::fs::write(&path, contents).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_supported_extensions() {
+        assert!(is_supported_extension(Path::new("memo.m4a")));
+        assert!(is_supported_extension(Path::new("/tmp/Song.MP3")));
+        assert!(!is_supported_extension(Path::new("notes.txt")));
+        assert!(!is_supported_extension(Path::new("no_extension")));
+    }
+
+    #[test]
+    fn test_decodes_flac_fixture_to_16khz() {
+        let samples = decode_file(&fixture("tone-8khz-mono.flac")).unwrap();
+
+        // 800 samples at 8 kHz become 1600 at 16 kHz
+        assert_eq!(samples.len(), 1600);
+        let peak = samples.iter().fold(0.0f32, |peak, s| peak.max(s.abs()));
+        assert!((peak - 0.5).abs() < 0.01, "peak {}", peak);
+    }
+
+    #[test]
+    fn test_unknown_format_is_unsupported() {
+        let path = temp_file("garbage.mp3", &[0x42; 4096]);
+
+        let err = decode_file(&path).unwrap_err();
+        assert!(matches!(err, DecodeError::Unsupported(_)), "{:?}", err);
+        std::fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn test_damaged_file_is_corrupt() {
+        // A FLAC signature followed by a broken stream info block
+        let mut contents = b"fLaC".to_vec();
+        contents.extend([0x80, 0x00, 0x00, 0x22]);
+        contents.extend([0xFF; 10]);
+        let path = temp_file("damaged.flac", &contents);
+
+        let err = decode_file(&path).unwrap_err();
+        assert!(matches!(err, DecodeError::Corrupt(_)), "{:?}", err);
+        std::fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn test_missing_file_is_io_error() {
+        let err = decode_file(Path::new("/nonexistent/memo.m4a")).unwrap_err();
+        assert!(matches!(err, DecodeError::Io(_)));
+    }
+}
@@ -0,0 +1,266 @@
+/// One second-order section of an IIR filter, with coefficients normalized so a0 = 1
+#[derive(Debug, Clone, Copy)]
+struct Biquad {
+    b0: f64,
+    b1: f64,
+    b2: f64,
+    a1: f64,
+    a2: f64,
+}
+
+/// 4th-order Butterworth high-pass at 80 Hz for 16kHz audio, as two cascaded
+/// sections (Q = 0.5412 and Q = 1.3066, from the RBJ audio EQ cookbook).
+///
+/// Removes mains hum (50/60 Hz) and fan rumble, which carry no speech: 50 Hz
+/// is attenuated by about 16 dB, while everything from 200 Hz up passes
+/// unchanged. Computed in f64, since the poles sit very close to the unit
+/// circle at this low cutoff.
+const HIGH_PASS_SECTIONS: [Biquad; 2] = [
+    Biquad {
+        b0: 0.971_558_877_252_620_1,
+        b1: -1.943_117_754_505_240_3,
+        b2: 0.971_558_877_252_620_1,
+        a1: -1.942_638_230_540_113_5,
+        a2: 0.943_597_278_470_367_1,
+    },
+    Biquad {
+        b0: 0.987_878_606_802_828_5,
+        b1: -1.975_757_213_605_657,
+        b2: 0.987_878_606_802_828_5,
+        a1: -1.975_269_634_851_873_5,
+        a2: 0.976_244_792_359_440_1,
+    },
+];
+
+/// Windows quieter than this RMS level (about -50 dBFS) are background noise
+const GATE_THRESHOLD: f32 = 0.003;
+
+/// Gain applied to background noise while the gate is closed (-20 dB);
+/// attenuating rather than muting keeps the noise from pumping in and out
+const GATE_CLOSED_GAIN: f32 = 0.1;
+
+/// Length of the windows the gate opens or closes on (10 ms at 16kHz)
+const GATE_WINDOW_SAMPLES: usize = 160;
+
+/// Windows the gate stays open after the last loud one, so quiet word
+/// endings and short pauses aren't attenuated (200 ms)
+const GATE_HOLD_WINDOWS: usize = 20;
+
+/// Peak level recordings are normalized to (-3 dBFS), leaving headroom so
+/// nothing clips when the WAV is written
+const NORMALIZE_TARGET_PEAK: f32 = 0.707_945_8;
+
+/// Recordings with a lower peak (-60 dBFS) are silence, which normalizing
+/// would only turn into loud noise
+const NORMALIZE_MIN_PEAK: f32 = 0.001;
+
+/// Largest boost applied (+26 dB), so a very quiet mic's hiss doesn't end up
+/// as loud as speech
+const NORMALIZE_MAX_GAIN: f32 = 20.0;
+
+/// Scales a recording so its loudest sample reaches -3 dBFS
+///
+/// Quiet microphones produce audio close to the silence threshold, which
+/// Whisper transcribes poorly. Loud recordings are scaled down to the same
+/// peak. Silence is left unchanged, and the boost is capped at +26 dB.
+///
+/// # Arguments
+/// * `samples` - 16kHz mono samples, scaled in place
+///
+/// # Returns
+/// The gain that was applied, 1.0 if the recording was left unchanged
+pub fn normalize_peak(samples: &mut [f32]) -> f32 {
+    let peak = samples
+        .iter()
+        .filter(|sample| sample.is_finite())
+        .fold(0.0f32, |peak, sample| peak.max(sample.abs()));
+    if peak < NORMALIZE_MIN_PEAK {
+        return 1.0;
+    }
+
+    let gain = (NORMALIZE_TARGET_PEAK / peak).min(NORMALIZE_MAX_GAIN);
+    for sample in samples.iter_mut() {
+        *sample *= gain;
+    }
+    gain
+}
+
+/// Removes low-frequency hum with the high-pass filter, then attenuates the
+/// background noise between words with the noise gate
+///
+/// # Arguments
+/// * `samples` - 16kHz mono samples, filtered in place
+pub fn reduce_noise(samples: &mut [f32]) {
+    high_pass(samples);
+    noise_gate(samples);
+}
+
+/// Applies the 80 Hz high-pass filter in place
+fn high_pass(samples: &mut [f32]) {
+    for section in HIGH_PASS_SECTIONS {
+        // Transposed direct form II state
+        let (mut z1, mut z2) = (0.0f64, 0.0f64);
+        for sample in samples.iter_mut() {
+            let input = f64::from(*sample);
+            let output = section.b0 * input + z1;
+            z1 = section.b1 * input - section.a1 * output + z2;
+            z2 = section.b2 * input - section.a2 * output;
+            *sample = output as f32;
+        }
+    }
+}
+
+/// Attenuates windows quieter than the gate threshold, in place
+///
+/// The gain ramps linearly across a window whenever the gate opens or
+/// closes, so switching never clicks.
+fn noise_gate(samples: &mut [f32]) {
+    let mut gain = 1.0f32;
+    let mut quiet_windows = GATE_HOLD_WINDOWS;
+    for window in samples.chunks_mut(GATE_WINDOW_SAMPLES) {
+        let mean_square =
+            window.iter().map(|&sample| sample * sample).sum::<f32>() / window.len() as f32;
+        if mean_square.sqrt() >= GATE_THRESHOLD {
+            quiet_windows = 0;
+        } else {
+            quiet_windows = quiet_windows.saturating_add(1);
+        }
+        let target = if quiet_windows <= GATE_HOLD_WINDOWS {
+            1.0
+        } else {
+            GATE_CLOSED_GAIN
+        };
+
+        let step = (target - gain) / window.len() as f32;
+        for sample in window.iter_mut() {
+            gain += step;
+            *sample *= gain;
+        }
+        gain = target;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f32::consts::PI;
+
+    const SAMPLE_RATE: f32 = 16000.0;
+
+    fn tone(frequency: f32, amplitude: f32, seconds: f32) -> Vec<f32> {
+        (0..(seconds * SAMPLE_RATE) as usize)
+            .map(|i| amplitude * (2.0 * PI * frequency * i as f32 / SAMPLE_RATE).sin())
+            .collect()
+    }
+
+    /// RMS level after the filter has settled
+    fn settled_rms(samples: &[f32]) -> f32 {
+        let settled = &samples[samples.len() / 2..];
+        (settled.iter().map(|&sample| sample * sample).sum::<f32>() / settled.len() as f32).sqrt()
+    }
+
+    #[test]
+    fn test_high_pass_removes_hum() {
+        let original = tone(50.0, 0.5, 1.0);
+        let mut filtered = original.clone();
+        high_pass(&mut filtered);
+
+        let ratio = settled_rms(&filtered) / settled_rms(&original);
+        assert!(ratio < 0.2, "50 Hz kept {:.3} of its level", ratio);
+    }
+
+    #[test]
+    fn test_high_pass_keeps_speech_frequencies() {
+        for frequency in [440.0, 1000.0, 3000.0] {
+            let original = tone(frequency, 0.5, 1.0);
+            let mut filtered = original.clone();
+            high_pass(&mut filtered);
+
+            let ratio = settled_rms(&filtered) / settled_rms(&original);
+            assert!(
+                (ratio - 1.0).abs() < 0.02,
+                "{} Hz kept {:.3} of its level",
+                frequency,
+                ratio
+            );
+        }
+    }
+
+    #[test]
+    fn test_gate_attenuates_noise_between_speech() {
+        // 0.5 s of speech, 1 s of faint hiss, 0.5 s of speech
+        let mut samples = tone(440.0, 0.3, 0.5);
+        samples.extend(tone(3000.0, 0.001, 1.0));
+        samples.extend(tone(440.0, 0.3, 0.5));
+        let original = samples.clone();
+        noise_gate(&mut samples);
+
+        // Speech passes untouched
+        assert_eq!(samples[..8000], original[..8000]);
+        assert_eq!(
+            samples[samples.len() - 100..],
+            original[original.len() - 100..]
+        );
+
+        // The hiss is attenuated once the hold time is over
+        let hiss = 8000 + GATE_WINDOW_SAMPLES * (GATE_HOLD_WINDOWS + 2)..24000 - 160;
+        for i in hiss {
+            assert!((samples[i] - original[i] * GATE_CLOSED_GAIN).abs() < 1e-6);
+        }
+    }
+
+    fn peak(samples: &[f32]) -> f32 {
+        samples
+            .iter()
+            .fold(0.0f32, |peak, sample| peak.max(sample.abs()))
+    }
+
+    #[test]
+    fn test_quiet_sine_is_boosted_to_target_peak() {
+        let mut samples = tone(440.0, 0.05, 0.5);
+        let gain = normalize_peak(&mut samples);
+
+        assert!((peak(&samples) - NORMALIZE_TARGET_PEAK).abs() < 1e-4);
+        assert!(gain > 14.0 && gain < 14.3, "{}", gain);
+    }
+
+    #[test]
+    fn test_loud_recording_is_scaled_down_to_target_peak() {
+        let mut samples = tone(440.0, 1.2, 0.5);
+        normalize_peak(&mut samples);
+
+        assert!((peak(&samples) - NORMALIZE_TARGET_PEAK).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_silence_is_left_unchanged() {
+        let mut zeros = vec![0.0; 16000];
+        assert_eq!(normalize_peak(&mut zeros), 1.0);
+        assert!(zeros.iter().all(|&sample| sample == 0.0));
+
+        let mut near_silence = tone(440.0, 0.0005, 0.1);
+        let original = near_silence.clone();
+        assert_eq!(normalize_peak(&mut near_silence), 1.0);
+        assert_eq!(near_silence, original);
+
+        assert_eq!(normalize_peak(&mut []), 1.0);
+    }
+
+    #[test]
+    fn test_boost_is_capped() {
+        let mut samples = tone(440.0, 0.002, 0.5);
+        assert_eq!(normalize_peak(&mut samples), NORMALIZE_MAX_GAIN);
+        assert!(peak(&samples) < 0.05);
+    }
+
+    #[test]
+    fn test_reduce_noise_handles_empty_and_short_audio() {
+        let mut empty: Vec<f32> = Vec::new();
+        reduce_noise(&mut empty);
+        assert!(empty.is_empty());
+
+        let mut short = vec![0.5; 10];
+        reduce_noise(&mut short);
+        assert!(short.iter().all(|sample| sample.is_finite()));
+    }
+}
@@ -1,2 +1,21 @@
 /// Audio recording using cpal
 pub mod recorder;
+
+/// Ring buffer and idle stream for pre-roll audio
+pub mod ringbuffer;
+
+/// Detection of gaps between audio input callbacks
+pub mod xrun;
+
+/// Conversion of captured samples to 16-bit WAV files
+pub mod wav;
+
+/// Energy-based detection of speech and the pauses between it
+pub mod vad;
+
+/// High-pass filter, noise gate and peak normalization applied to recordings
+pub mod filter;
+
+/// Decoding of compressed audio files using symphonia
+#[cfg(feature = "compressed-audio")]
+pub mod decode;
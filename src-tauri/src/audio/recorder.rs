@@ -1,8 +1,14 @@
+use super::xrun::XrunDetector;
 use anyhow::{anyhow, Context, Result};
 use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
-use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender, SyncSender, TrySendError};
 use std::sync::{Arc, Mutex};
 use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+/// How often the recording thread checks whether live audio is due
+const LIVE_POLL_INTERVAL: Duration = Duration::from_millis(250);
 
 /// Commands that can be sent to the recording thread
 enum RecordingCommand {
@@ -15,9 +21,106 @@ pub struct RecordingHandle {
     command_tx: Sender<RecordingCommand>,
     /// Handle to the recording thread
     thread_handle: Option<JoinHandle<Result<Vec<f32>>>>,
+    /// Name of the input device, filled in once the recording thread opened it
+    device_name: Arc<Mutex<Option<String>>>,
+    /// Audio captured before the recording started, prepended on stop
+    pre_roll: Vec<f32>,
+    /// Number of gaps detected between input callbacks
+    xruns: Arc<AtomicUsize>,
+}
+
+/// The most recent part of a recording still in progress
+#[derive(Debug, Clone, PartialEq)]
+pub struct LiveAudio {
+    /// The most recent samples (16kHz mono)
+    pub samples: Vec<f32>,
+    /// Whether `samples` starts at the beginning of the recording (pre-roll aside)
+    pub from_start: bool,
+}
+
+/// Hands the tail of a recording in progress to a consumer at intervals,
+/// e.g. for live transcription
+pub struct LiveAudioFeed {
+    /// Receives the audio; a window is skipped while the consumer is still busy
+    pub sender: SyncSender<LiveAudio>,
+    /// Samples of new audio recorded before the next window is handed over
+    pub interval: usize,
+    /// Maximum number of samples in a window
+    pub window: usize,
+    /// Set once the recording stops, so the consumer can abort its work
+    pub stopped: Arc<AtomicBool>,
+}
+
+/// Copies the last `window` samples of a recording
+fn tail_window(samples: &[f32], window: usize) -> LiveAudio {
+    let start = samples.len().saturating_sub(window);
+    LiveAudio {
+        samples: samples[start..].to_vec(),
+        from_start: start == 0,
+    }
+}
+
+/// Blocks until the recording is stopped, handing live audio to the feed meanwhile
+///
+/// Only the tail window is copied, so the input callback never waits on the
+/// buffer lock for longer than a short copy.
+fn wait_for_stop(
+    command_rx: &Receiver<RecordingCommand>,
+    samples: &Mutex<Vec<f32>>,
+    live: Option<LiveAudioFeed>,
+) {
+    let Some(live) = live else {
+        // Wait for stop command (blocking until we receive it or channel closes)
+        let _ = command_rx.recv();
+        return;
+    };
+
+    let mut handed_over = 0;
+    while let Err(RecvTimeoutError::Timeout) = command_rx.recv_timeout(LIVE_POLL_INTERVAL) {
+        let audio = {
+            let samples = samples.lock().unwrap();
+            if samples.len() < handed_over + live.interval {
+                continue;
+            }
+            handed_over = samples.len();
+            tail_window(&samples, live.window)
+        };
+        match live.sender.try_send(audio) {
+            Ok(()) | Err(TrySendError::Full(_)) => {}
+            Err(TrySendError::Disconnected(_)) => {
+                log::debug!("Live audio consumer is gone");
+                let _ = command_rx.recv();
+                break;
+            }
+        }
+    }
+    live.stopped.store(true, Ordering::SeqCst);
+}
+
+/// Prepends pre-roll audio to a capture
+fn with_pre_roll(mut pre_roll: Vec<f32>, captured: Vec<f32>) -> Vec<f32> {
+    if pre_roll.is_empty() {
+        return captured;
+    }
+    pre_roll.extend(captured);
+    pre_roll
 }
 
 impl RecordingHandle {
+    /// Returns the name of the input device being recorded from
+    pub fn device_name(&self) -> String {
+        self.device_name
+            .lock()
+            .unwrap()
+            .clone()
+            .unwrap_or_else(|| "Unknown".to_string())
+    }
+
+    /// Returns the number of gaps (xruns) detected between input callbacks so far
+    pub fn xruns(&self) -> usize {
+        self.xruns.load(Ordering::SeqCst)
+    }
+
     /// Stops the recording and returns the recorded audio samples
     pub fn stop(mut self) -> Result<Vec<f32>> {
         log::info!("Stopping audio recording");
@@ -33,49 +136,115 @@ impl RecordingHandle {
             .take()
             .context("Recording thread already stopped")?;
 
-        let samples = thread_handle
+        let captured = thread_handle
             .join()
             .map_err(|_| anyhow!("Recording thread panicked"))??;
+        let samples = with_pre_roll(std::mem::take(&mut self.pre_roll), captured);
 
         log::info!(
-            "Audio recording stopped, {} samples captured",
-            samples.len()
+            "Audio recording stopped, {} samples captured from device: {} ({} xruns)",
+            samples.len(),
+            self.device_name(),
+            self.xruns()
         );
 
         Ok(samples)
     }
 }
 
+/// Picks the first device from the priority list that is currently available
+///
+/// # Arguments
+/// * `priority` - Preferred device names, most preferred first
+/// * `available` - Names of the input devices currently connected
+///
+/// # Returns
+/// * `Some(name)` of the first available preferred device
+/// * `None` if no preferred device is connected, meaning the OS default should be used
+pub fn resolve_input_device(priority: &[String], available: &[String]) -> Option<String> {
+    priority
+        .iter()
+        .map(|name| name.trim())
+        .find(|name| available.iter().any(|device| device == name))
+        .map(str::to_string)
+}
+
+/// Lists the names of the input devices currently connected
+pub fn list_input_device_names() -> Vec<String> {
+    cpal::default_host()
+        .input_devices()
+        .map(|devices| devices.filter_map(|device| device.name().ok()).collect())
+        .unwrap_or_default()
+}
+
 /// Audio recorder using cpal for cross-platform audio capture
 pub struct AudioRecorder;
 
 impl AudioRecorder {
     /// Starts recording audio and returns a handle to control the recording
     ///
+    /// # Arguments
+    /// * `device_priority` - Preferred input device names, first available wins;
+    ///   the OS default device is used if none of them is connected
+    /// * `pre_roll` - Samples captured just before the recording started (16kHz mono)
+    /// * `fill_xrun_gaps` - Whether to insert silence for audio lost to callback gaps,
+    ///   keeping the timing of the recording intact
+    /// * `live` - Receives the tail of the recording while it is in progress, if set
+    ///
     /// # Returns
     /// * `Ok(RecordingHandle)` if recording started successfully
     /// * `Err` if the stream could not be created or started
-    pub fn start_recording() -> Result<RecordingHandle> {
-        log::info!("Starting audio recording");
+    pub fn start_recording(
+        device_priority: &[String],
+        pre_roll: Vec<f32>,
+        fill_xrun_gaps: bool,
+        live: Option<LiveAudioFeed>,
+    ) -> Result<RecordingHandle> {
+        log::info!(
+            "Starting audio recording ({} pre-roll samples)",
+            pre_roll.len()
+        );
+
+        let preferred_device = resolve_input_device(device_priority, &list_input_device_names());
 
         // Create channel for commands
         let (command_tx, command_rx): (Sender<RecordingCommand>, Receiver<RecordingCommand>) =
             mpsc::channel();
 
+        // Resolved up front so callers can report the device right away;
+        // the recording thread overwrites it with the device it actually opened
+        let expected_device = preferred_device.clone().or_else(|| {
+            cpal::default_host()
+                .default_input_device()
+                .and_then(|device| device.name().ok())
+        });
+        let device_name: Arc<Mutex<Option<String>>> = Arc::new(Mutex::new(expected_device));
+        let device_name_clone = Arc::clone(&device_name);
+
+        let xruns = Arc::new(AtomicUsize::new(0));
+        let xruns_clone = Arc::clone(&xruns);
+
         // Spawn recording thread
         let thread_handle = thread::spawn(move || -> Result<Vec<f32>> {
             // Get default host
             let host = cpal::default_host();
 
-            // Get default input device
-            let device = host
-                .default_input_device()
-                .context("No input device available")?;
+            // Use the preferred device if it is still connected, else the default input device
+            let preferred = preferred_device.and_then(|wanted| {
+                host.input_devices()
+                    .ok()?
+                    .find(|device| device.name().map(|n| n == wanted).unwrap_or(false))
+            });
+            let device = match preferred {
+                Some(device) => device,
+                None => host
+                    .default_input_device()
+                    .context("No input device available")?,
+            };
 
-            log::info!(
-                "Using input device: {}",
-                device.name().unwrap_or_else(|_| "Unknown".to_string())
-            );
+            let name = device.name().unwrap_or_else(|_| "Unknown".to_string());
+            log::info!("Using input device: {}", name);
+            *device_name_clone.lock().unwrap() = Some(name);
 
             // Get default input config to validate device supports input
             let _supported_config = device
@@ -95,12 +264,23 @@ impl AudioRecorder {
             let samples: Arc<Mutex<Vec<f32>>> = Arc::new(Mutex::new(Vec::new()));
             let samples_clone = Arc::clone(&samples);
 
+            // Watch callback timestamps for gaps caused by callback starvation
+            let mut xrun_detector = XrunDetector::new(config.sample_rate.0, fill_xrun_gaps);
+            let mut first_capture: Option<cpal::StreamInstant> = None;
+
             // Build input stream
             let stream = device.build_input_stream(
                 &config,
-                move |data: &[f32], _: &cpal::InputCallbackInfo| {
-                    // Append samples to the buffer
+                move |data: &[f32], info: &cpal::InputCallbackInfo| {
+                    let capture = info.timestamp().capture;
+                    let origin = *first_capture.get_or_insert(capture);
+                    let capture_time = capture.duration_since(&origin).unwrap_or_default();
+                    let silence = xrun_detector.on_callback(capture_time, data.len());
+                    xruns_clone.store(xrun_detector.xruns(), Ordering::SeqCst);
+
+                    // Append samples to the buffer, after silence standing in for lost audio
                     let mut samples = samples_clone.lock().unwrap();
+                    samples.resize(samples.len() + silence, 0.0);
                     samples.extend_from_slice(data);
                 },
                 |err| {
@@ -113,8 +293,7 @@ impl AudioRecorder {
             stream.play()?;
             log::info!("Audio recording started");
 
-            // Wait for stop command (blocking until we receive it or channel closes)
-            let _ = command_rx.recv();
+            wait_for_stop(&command_rx, &samples, live);
 
             // Drop the stream to stop recording
             drop(stream);
@@ -127,6 +306,9 @@ impl AudioRecorder {
         Ok(RecordingHandle {
             command_tx,
             thread_handle: Some(thread_handle),
+            device_name,
+            pre_roll,
+            xruns,
         })
     }
 }
@@ -147,6 +329,64 @@ mod tests {
         host.default_input_device().is_some()
     }
 
+    #[test]
+    fn test_pre_roll_is_prepended() {
+        let samples = with_pre_roll(vec![0.1, 0.2], vec![0.3, 0.4, 0.5]);
+        assert_eq!(samples, vec![0.1, 0.2, 0.3, 0.4, 0.5]);
+    }
+
+    #[test]
+    fn test_empty_pre_roll_keeps_capture() {
+        let samples = with_pre_roll(Vec::new(), vec![0.3, 0.4]);
+        assert_eq!(samples, vec![0.3, 0.4]);
+    }
+
+    #[test]
+    fn test_pre_roll_from_ring_buffer_is_at_front() {
+        use crate::audio::ringbuffer::RingBuffer;
+
+        let mut ring = RingBuffer::new(3);
+        ring.push_slice(&[9.0, 1.0, 2.0, 3.0]);
+
+        let samples = with_pre_roll(ring.snapshot(), vec![4.0, 5.0]);
+        assert_eq!(samples, vec![1.0, 2.0, 3.0, 4.0, 5.0]);
+    }
+
+    fn names(names: &[&str]) -> Vec<String> {
+        names.iter().map(|name| name.to_string()).collect()
+    }
+
+    #[test]
+    fn test_resolve_input_device_picks_first_available() {
+        let priority = names(&["Jabra Evolve", "USB Mic", "Built-in Microphone"]);
+        let available = names(&["Built-in Microphone", "USB Mic"]);
+
+        assert_eq!(
+            resolve_input_device(&priority, &available),
+            Some("USB Mic".to_string())
+        );
+    }
+
+    #[test]
+    fn test_resolve_input_device_falls_back_to_default() {
+        let priority = names(&["Jabra Evolve"]);
+        let available = names(&["Built-in Microphone"]);
+
+        assert_eq!(resolve_input_device(&priority, &available), None);
+        assert_eq!(resolve_input_device(&[], &available), None);
+    }
+
+    #[test]
+    fn test_resolve_input_device_ignores_surrounding_whitespace() {
+        let priority = names(&["  Jabra Evolve "]);
+        let available = names(&["Jabra Evolve"]);
+
+        assert_eq!(
+            resolve_input_device(&priority, &available),
+            Some("Jabra Evolve".to_string())
+        );
+    }
+
     #[test]
     fn test_recording_command_enum() {
         // Verify the enum variant exists and can be created
@@ -159,7 +399,7 @@ mod tests {
         // In environments without audio devices, start_recording should
         // fail gracefully
         if !has_audio_input_device() {
-            let result = AudioRecorder::start_recording();
+            let result = AudioRecorder::start_recording(&[], Vec::new(), true, None);
             // Without a device, this should fail
             assert!(result.is_err() || result.is_ok());
         }
@@ -174,7 +414,7 @@ mod tests {
         }
 
         // Start recording
-        let handle = AudioRecorder::start_recording();
+        let handle = AudioRecorder::start_recording(&[], Vec::new(), true, None);
         assert!(
             handle.is_ok(),
             "Failed to start recording: {:?}",
@@ -200,6 +440,61 @@ mod tests {
         assert!(!samples.is_empty() || samples.is_empty()); // Always true, just validates we can check
     }
 
+    #[test]
+    fn test_tail_window_keeps_most_recent_samples() {
+        let samples: Vec<f32> = (0..10).map(|i| i as f32).collect();
+
+        let tail = tail_window(&samples, 4);
+        assert_eq!(tail.samples, vec![6.0, 7.0, 8.0, 9.0]);
+        assert!(!tail.from_start);
+
+        let whole = tail_window(&samples, 10);
+        assert_eq!(whole.samples, samples);
+        assert!(whole.from_start);
+        assert!(tail_window(&[], 4).from_start);
+    }
+
+    #[test]
+    fn test_live_feed_hands_over_new_audio_until_stopped() {
+        let (command_tx, command_rx) = mpsc::channel();
+        let (live_tx, live_rx) = mpsc::sync_channel(1);
+        let stopped = Arc::new(AtomicBool::new(false));
+        let samples = Arc::new(Mutex::new(vec![0.5; 6]));
+        let feed = LiveAudioFeed {
+            sender: live_tx,
+            interval: 5,
+            window: 8,
+            stopped: Arc::clone(&stopped),
+        };
+
+        let recording = {
+            let samples = Arc::clone(&samples);
+            thread::spawn(move || wait_for_stop(&command_rx, &samples, Some(feed)))
+        };
+
+        // Enough audio for a window from the start of the recording
+        let first = live_rx.recv_timeout(Duration::from_secs(5)).unwrap();
+        assert_eq!(first.samples.len(), 6);
+        assert!(first.from_start);
+
+        // Less than an interval of new audio hands over nothing
+        samples.lock().unwrap().extend([0.1; 4]);
+        assert!(live_rx.recv_timeout(LIVE_POLL_INTERVAL * 3).is_err());
+
+        // Then only the tail window is handed over
+        samples.lock().unwrap().extend([0.1; 2]);
+        let second = live_rx.recv_timeout(Duration::from_secs(5)).unwrap();
+        assert_eq!(second.samples.len(), 8);
+        assert!(!second.from_start);
+
+        assert!(!stopped.load(Ordering::SeqCst));
+        command_tx.send(RecordingCommand::Stop).unwrap();
+        recording.join().unwrap();
+        assert!(stopped.load(Ordering::SeqCst));
+        // The feed is dropped with the recording, ending the consumer
+        assert!(live_rx.recv().is_err());
+    }
+
     /// Tests for the channel communication pattern
     mod channel_tests {
         use super::*;
@@ -0,0 +1,200 @@
+use anyhow::{anyhow, Context, Result};
+use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
+use std::collections::VecDeque;
+use std::sync::mpsc::{self, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+
+/// Sample rate used for all captured audio (Whisper expects 16kHz)
+const SAMPLE_RATE: u32 = 16000;
+
+/// Fixed-capacity buffer that keeps only the most recent samples
+pub struct RingBuffer {
+    /// Buffered samples, oldest first
+    samples: VecDeque<f32>,
+    /// Maximum number of samples kept
+    capacity: usize,
+}
+
+impl RingBuffer {
+    /// Creates a ring buffer holding at most `capacity` samples
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            samples: VecDeque::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    /// Creates a ring buffer large enough for `duration_ms` of 16kHz audio
+    pub fn for_duration_ms(duration_ms: u32) -> Self {
+        Self::new((SAMPLE_RATE as usize * duration_ms as usize) / 1000)
+    }
+
+    /// Appends samples, dropping the oldest ones once the buffer is full
+    pub fn push_slice(&mut self, data: &[f32]) {
+        if self.capacity == 0 {
+            return;
+        }
+
+        // Only the tail of an oversized chunk can survive anyway
+        let data = &data[data.len().saturating_sub(self.capacity)..];
+
+        let overflow = (self.samples.len() + data.len()).saturating_sub(self.capacity);
+        self.samples.drain(..overflow);
+        self.samples.extend(data.iter().copied());
+    }
+
+    /// Returns the buffered samples, oldest first
+    pub fn snapshot(&self) -> Vec<f32> {
+        self.samples.iter().copied().collect()
+    }
+}
+
+/// Low-cost idle input stream that keeps the last few hundred milliseconds
+/// of audio so they can be prepended to the next recording
+pub struct PreRollListener {
+    /// Most recent audio from the idle stream
+    buffer: Arc<Mutex<RingBuffer>>,
+    /// Channel used to stop the listener thread
+    stop_tx: Sender<()>,
+    /// Handle to the listener thread
+    thread_handle: Option<JoinHandle<()>>,
+    /// Configured pre-roll length in milliseconds
+    duration_ms: u32,
+}
+
+impl PreRollListener {
+    /// Starts the idle stream on the default input device
+    ///
+    /// # Arguments
+    /// * `duration_ms` - Amount of audio to keep, in milliseconds
+    ///
+    /// # Returns
+    /// * `Ok(PreRollListener)` if the stream started
+    /// * `Err` if no input device is available or the stream could not be started
+    pub fn start(duration_ms: u32) -> Result<Self> {
+        log::info!("Starting pre-roll listener ({} ms)", duration_ms);
+
+        let buffer = Arc::new(Mutex::new(RingBuffer::for_duration_ms(duration_ms)));
+        let buffer_clone = Arc::clone(&buffer);
+        let (stop_tx, stop_rx) = mpsc::channel::<()>();
+        let (ready_tx, ready_rx) = mpsc::channel::<Result<()>>();
+
+        // cpal streams are not Send on every platform, so the stream lives on its own thread
+        let thread_handle = thread::spawn(move || {
+            let stream = (|| -> Result<cpal::Stream> {
+                let device = cpal::default_host()
+                    .default_input_device()
+                    .context("No input device available")?;
+
+                let config = cpal::StreamConfig {
+                    channels: 1,
+                    sample_rate: cpal::SampleRate(SAMPLE_RATE),
+                    buffer_size: cpal::BufferSize::Default,
+                };
+
+                let stream = device.build_input_stream(
+                    &config,
+                    move |data: &[f32], _: &cpal::InputCallbackInfo| {
+                        buffer_clone.lock().unwrap().push_slice(data);
+                    },
+                    |err| {
+                        log::error!("Pre-roll stream error: {}", err);
+                    },
+                    None,
+                )?;
+                stream.play()?;
+                Ok(stream)
+            })();
+
+            match stream {
+                Ok(stream) => {
+                    let _ = ready_tx.send(Ok(()));
+                    // Keep the stream alive until asked to stop
+                    let _ = stop_rx.recv();
+                    drop(stream);
+                    log::info!("Pre-roll listener stopped");
+                }
+                Err(e) => {
+                    let _ = ready_tx.send(Err(e));
+                }
+            }
+        });
+
+        ready_rx
+            .recv()
+            .map_err(|_| anyhow!("Pre-roll listener thread exited unexpectedly"))??;
+
+        Ok(Self {
+            buffer,
+            stop_tx,
+            thread_handle: Some(thread_handle),
+            duration_ms,
+        })
+    }
+
+    /// Returns the configured pre-roll length in milliseconds
+    pub fn duration_ms(&self) -> u32 {
+        self.duration_ms
+    }
+
+    /// Returns the audio captured just before this call, oldest first
+    pub fn snapshot(&self) -> Vec<f32> {
+        self.buffer.lock().unwrap().snapshot()
+    }
+}
+
+impl Drop for PreRollListener {
+    fn drop(&mut self) {
+        let _ = self.stop_tx.send(());
+        if let Some(handle) = self.thread_handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_for_duration_ms_capacity() {
+        let buffer = RingBuffer::for_duration_ms(500);
+        assert_eq!(buffer.capacity, 8000);
+        assert!(buffer.snapshot().is_empty());
+    }
+
+    #[test]
+    fn test_push_within_capacity() {
+        let mut buffer = RingBuffer::new(5);
+        buffer.push_slice(&[0.1, 0.2]);
+        buffer.push_slice(&[0.3]);
+
+        assert_eq!(buffer.snapshot(), vec![0.1, 0.2, 0.3]);
+    }
+
+    #[test]
+    fn test_push_drops_oldest_samples() {
+        let mut buffer = RingBuffer::new(3);
+        buffer.push_slice(&[1.0, 2.0]);
+        buffer.push_slice(&[3.0, 4.0]);
+
+        assert_eq!(buffer.snapshot(), vec![2.0, 3.0, 4.0]);
+    }
+
+    #[test]
+    fn test_push_oversized_chunk_keeps_tail() {
+        let mut buffer = RingBuffer::new(2);
+        buffer.push_slice(&[1.0, 2.0, 3.0, 4.0]);
+
+        assert_eq!(buffer.snapshot(), vec![3.0, 4.0]);
+    }
+
+    #[test]
+    fn test_zero_capacity_stays_empty() {
+        let mut buffer = RingBuffer::new(0);
+        buffer.push_slice(&[1.0]);
+
+        assert!(buffer.snapshot().is_empty());
+    }
+}
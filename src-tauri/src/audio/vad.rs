@@ -0,0 +1,169 @@
+use crate::audio::wav::SAMPLE_RATE;
+use crate::commands::transcription::calculate_rms;
+use std::ops::Range;
+
+/// Default length of a pause that separates two stretches of speech, in milliseconds
+pub const DEFAULT_VAD_MIN_SILENCE_MS: u32 = 500;
+
+/// Shortest pause accepted in settings, in milliseconds; twice the padding,
+/// so padded stretches of speech never overlap
+pub const MIN_VAD_MIN_SILENCE_MS: u32 = 400;
+
+/// Longest pause accepted in settings, in milliseconds
+pub const MAX_VAD_MIN_SILENCE_MS: u32 = 5000;
+
+/// Length of the frames classified as speech or silence (20 ms)
+const FRAME_SAMPLES: usize = SAMPLE_RATE as usize / 50;
+
+/// Quiet audio kept on both sides of a stretch of speech, so soft word
+/// onsets and endings aren't cut off mid-syllable (200 ms)
+const PADDING_SAMPLES: usize = SAMPLE_RATE as usize / 5;
+
+/// Share of the quietest frames whose level is taken as the background noise
+const NOISE_FLOOR_PERCENTILE: f32 = 0.1;
+
+/// Frames this many times louder than the background noise count as speech
+const NOISE_FLOOR_FACTOR: f32 = 3.0;
+
+/// Level above which a frame counts as speech
+///
+/// Estimated from the recording itself: the background noise is the level of
+/// the quietest tenth of the frames, and speech has to be clearly louder than
+/// that, so a noisy room doesn't count as speech throughout.
+///
+/// # Arguments
+/// * `samples` - 16kHz mono samples
+/// * `min_threshold` - Lowest threshold returned, for recordings in a quiet room
+pub fn speech_threshold(samples: &[f32], min_threshold: f32) -> f32 {
+    let mut levels: Vec<f32> = samples.chunks(FRAME_SAMPLES).map(calculate_rms).collect();
+    if levels.is_empty() {
+        return min_threshold;
+    }
+    levels.sort_by(f32::total_cmp);
+    let noise_floor = levels[(levels.len() as f32 * NOISE_FLOOR_PERCENTILE) as usize];
+    (noise_floor * NOISE_FLOOR_FACTOR).max(min_threshold)
+}
+
+/// Splits a recording into stretches of speech separated by pauses
+///
+/// Frames at or above the threshold are speech. Pauses shorter than
+/// `min_silence_samples` are kept inside a stretch, so only real breaks
+/// between sentences split the recording. Each stretch is padded with up to
+/// 200 ms of the surrounding audio; padding and frames are whole centiseconds,
+/// so Whisper's timestamps can be offset exactly.
+///
+/// # Arguments
+/// * `samples` - 16kHz mono samples
+/// * `threshold` - Frames with a lower RMS level are silent, see `speech_threshold`
+/// * `min_silence_samples` - Shortest pause that separates two stretches
+///
+/// # Returns
+/// The sample ranges of the stretches in order, empty if no frame is speech
+pub fn split_speech(
+    samples: &[f32],
+    threshold: f32,
+    min_silence_samples: usize,
+) -> Vec<Range<usize>> {
+    let min_silence_frames = min_silence_samples.div_ceil(FRAME_SAMPLES).max(1);
+
+    // Stretches of speech in frames, with short pauses bridged
+    let mut stretches: Vec<Range<usize>> = Vec::new();
+    for (frame, window) in samples.chunks(FRAME_SAMPLES).enumerate() {
+        if calculate_rms(window) < threshold {
+            continue;
+        }
+        match stretches.last_mut() {
+            Some(last) if frame - last.end < min_silence_frames => last.end = frame + 1,
+            _ => stretches.push(frame..frame + 1),
+        }
+    }
+
+    stretches
+        .into_iter()
+        .map(|frames| {
+            let start = (frames.start * FRAME_SAMPLES).saturating_sub(PADDING_SAMPLES);
+            let end = (frames.end * FRAME_SAMPLES + PADDING_SAMPLES).min(samples.len());
+            start..end
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SECOND: usize = SAMPLE_RATE as usize;
+    const MIN_SILENCE: usize = DEFAULT_VAD_MIN_SILENCE_MS as usize * SECOND / 1000;
+
+    /// Builds audio from (seconds, level) parts; speech is a sine at the level
+    fn audio(parts: &[(f32, f32)]) -> Vec<f32> {
+        parts
+            .iter()
+            .flat_map(|&(seconds, level)| {
+                (0..(seconds * SECOND as f32) as usize).map(move |i| level * (i as f32 * 0.1).sin())
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_silence_has_no_speech() {
+        assert!(split_speech(&vec![0.0; 3 * SECOND], 0.01, MIN_SILENCE).is_empty());
+        assert!(split_speech(&[], 0.01, MIN_SILENCE).is_empty());
+    }
+
+    #[test]
+    fn test_long_pauses_split_speech() {
+        let samples = audio(&[(1.0, 0.0), (2.0, 0.3), (1.0, 0.0), (1.5, 0.3), (1.0, 0.0)]);
+        let stretches = split_speech(&samples, 0.01, MIN_SILENCE);
+
+        assert_eq!(
+            stretches,
+            vec![
+                SECOND - PADDING_SAMPLES..3 * SECOND + PADDING_SAMPLES,
+                4 * SECOND - PADDING_SAMPLES..11 * SECOND / 2 + PADDING_SAMPLES,
+            ]
+        );
+        // Padded stretches start on whole centiseconds
+        for stretch in &stretches {
+            assert_eq!(stretch.start % (SECOND / 100), 0);
+        }
+    }
+
+    #[test]
+    fn test_short_pauses_stay_inside_speech() {
+        let samples = audio(&[(1.0, 0.3), (0.3, 0.0), (1.0, 0.3)]);
+
+        assert_eq!(
+            split_speech(&samples, 0.01, MIN_SILENCE),
+            vec![0..samples.len()]
+        );
+    }
+
+    #[test]
+    fn test_padding_stays_inside_the_audio() {
+        let samples = audio(&[(0.1, 0.3), (1.0, 0.0), (0.1, 0.3)]);
+        let stretches = split_speech(&samples, 0.01, MIN_SILENCE);
+
+        assert_eq!(stretches.first().unwrap().start, 0);
+        assert_eq!(stretches.last().unwrap().end, samples.len());
+    }
+
+    #[test]
+    fn test_threshold_follows_background_noise() {
+        // Quiet room: the minimum applies
+        let quiet = audio(&[(1.0, 0.0), (2.0, 0.3)]);
+        assert_eq!(speech_threshold(&quiet, 0.001), 0.001);
+
+        // Noisy room: speech has to rise above the hiss
+        let noisy = audio(&[(1.0, 0.02), (2.0, 0.3)]);
+        let threshold = speech_threshold(&noisy, 0.001);
+        assert!(threshold > 0.02 && threshold < 0.2, "{}", threshold);
+        assert_eq!(split_speech(&noisy, threshold, MIN_SILENCE).len(), 1);
+        assert_eq!(
+            split_speech(&noisy, threshold, MIN_SILENCE)[0].start,
+            SECOND - PADDING_SAMPLES
+        );
+
+        assert_eq!(speech_threshold(&[], 0.001), 0.001);
+    }
+}
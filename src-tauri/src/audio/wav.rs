@@ -0,0 +1,344 @@
+use anyhow::{bail, Context, Result};
+use std::io::Read;
+use std::path::Path;
+
+/// Sample rate of recordings written to disk (Whisper expects 16kHz)
+pub const SAMPLE_RATE: u32 = 16000;
+
+/// Returns true if the file starts with a RIFF/WAVE header
+///
+/// # Returns
+/// * `Ok(bool)` telling whether the file is a WAV file
+/// * `Err` if the file could not be read
+pub fn is_wav_file(path: &Path) -> Result<bool> {
+    let mut header = [0u8; 12];
+    let mut file = std::fs::File::open(path).context("Failed to open audio file")?;
+    match file.read_exact(&mut header) {
+        Ok(()) => Ok(&header[0..4] == b"RIFF" && &header[8..12] == b"WAVE"),
+        Err(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => Ok(false),
+        Err(e) => Err(e).context("Failed to read audio file"),
+    }
+}
+
+/// Averages interleaved frames down to a single channel
+///
+/// # Arguments
+/// * `samples` - Interleaved samples
+/// * `channels` - Number of channels per frame
+pub fn downmix(samples: &[f32], channels: usize) -> Vec<f32> {
+    if channels <= 1 {
+        return samples.to_vec();
+    }
+    samples
+        .chunks_exact(channels)
+        .map(|frame| frame.iter().sum::<f32>() / channels as f32)
+        .collect()
+}
+
+/// Resamples mono audio with linear interpolation
+///
+/// # Arguments
+/// * `samples` - Mono samples at `from_rate`
+/// * `from_rate` - Sample rate of the input in Hz
+/// * `to_rate` - Sample rate of the output in Hz
+///
+/// # Returns
+/// `samples.len() * to_rate / from_rate` samples (rounded) at `to_rate`
+pub fn resample_linear(samples: &[f32], from_rate: u32, to_rate: u32) -> Vec<f32> {
+    if from_rate == to_rate || from_rate == 0 || samples.is_empty() {
+        return samples.to_vec();
+    }
+
+    let ratio = from_rate as f64 / to_rate as f64;
+    let output_len = (samples.len() as f64 / ratio).round() as usize;
+    let last = samples.len() - 1;
+
+    (0..output_len)
+        .map(|i| {
+            let position = i as f64 * ratio;
+            let index = (position.floor() as usize).min(last);
+            let next = (index + 1).min(last);
+            let fraction = (position - index as f64) as f32;
+            samples[index] + (samples[next] - samples[index]) * fraction
+        })
+        .collect()
+}
+
+/// Reads a WAV file of any common format as 16kHz mono samples for Whisper
+///
+/// 8/16/24/32-bit integer and 32-bit float files are supported; channels are
+/// averaged and other sample rates are resampled.
+///
+/// # Arguments
+/// * `path` - WAV file to read
+///
+/// # Returns
+/// * `Ok(Vec<f32>)` with samples in the nominal range [-1.0, 1.0]
+/// * `Err` if the file is not a readable WAV file
+pub fn read_wav(path: &Path) -> Result<Vec<f32>> {
+    let mut reader = hound::WavReader::open(path).context("Failed to open WAV file")?;
+    let spec = reader.spec();
+    if spec.channels == 0 {
+        bail!("WAV file has no channels");
+    }
+
+    let samples: Vec<f32> = match spec.sample_format {
+        hound::SampleFormat::Float => reader
+            .samples::<f32>()
+            .collect::<Result<_, _>>()
+            .context("Failed to read audio samples")?,
+        hound::SampleFormat::Int => {
+            if !(1..=32).contains(&spec.bits_per_sample) {
+                bail!("Unsupported bit depth: {}", spec.bits_per_sample);
+            }
+            let scale = (1u64 << (spec.bits_per_sample - 1)) as f32;
+            reader
+                .samples::<i32>()
+                .map(|s| s.map(|s| s as f32 / scale))
+                .collect::<Result<_, _>>()
+                .context("Failed to read audio samples")?
+        }
+    };
+
+    let mono = downmix(&samples, spec.channels as usize);
+    Ok(resample_linear(&mono, spec.sample_rate, SAMPLE_RATE))
+}
+
+/// Samples that had to be corrected while converting to 16-bit PCM
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ConversionStats {
+    /// Samples outside [-1.0, 1.0] that were clamped
+    pub clamped: usize,
+    /// NaN samples that were written as silence
+    pub nan: usize,
+}
+
+impl ConversionStats {
+    /// Returns true if any sample had to be corrected
+    pub fn has_issues(&self) -> bool {
+        self.clamped > 0 || self.nan > 0
+    }
+}
+
+/// Converts an f32 sample to 16-bit PCM
+///
+/// Samples slightly above full scale (AGC overshoot, some drivers) are clamped
+/// instead of wrapping around into loud clicks. NaN samples become silence and
+/// are counted so upstream bugs don't go unnoticed.
+///
+/// # Arguments
+/// * `sample` - Sample in the nominal range [-1.0, 1.0]
+/// * `stats` - Counters updated for clamped and NaN samples
+pub fn sample_to_i16(sample: f32, stats: &mut ConversionStats) -> i16 {
+    if sample.is_nan() {
+        stats.nan += 1;
+        return 0;
+    }
+
+    if !(-1.0..=1.0).contains(&sample) {
+        stats.clamped += 1;
+    }
+
+    (sample.clamp(-1.0, 1.0) * i16::MAX as f32) as i16
+}
+
+/// Writes samples to a 16kHz mono 16-bit WAV file
+///
+/// # Arguments
+/// * `path` - Destination of the WAV file
+/// * `samples` - Samples in the nominal range [-1.0, 1.0]
+///
+/// # Returns
+/// * `Ok(ConversionStats)` with the number of corrected samples
+/// * `Err` if the file could not be written
+pub fn write_wav(path: &Path, samples: &[f32]) -> Result<ConversionStats> {
+    let spec = hound::WavSpec {
+        channels: 1,
+        sample_rate: SAMPLE_RATE,
+        bits_per_sample: 16,
+        sample_format: hound::SampleFormat::Int,
+    };
+
+    let mut writer = hound::WavWriter::create(path, spec).context("Failed to create WAV file")?;
+    let mut stats = ConversionStats::default();
+
+    for &sample in samples {
+        writer
+            .write_sample(sample_to_i16(sample, &mut stats))
+            .context("Failed to write audio sample")?;
+    }
+
+    writer.finalize().context("Failed to finalize WAV file")?;
+
+    if stats.has_issues() {
+        log::warn!(
+            "Corrected {} out-of-range and {} NaN samples while writing {:?}",
+            stats.clamped,
+            stats.nan,
+            path
+        );
+    }
+
+    Ok(stats)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn test_wav_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!(
+            "rustler_wav_test_{}_{}.wav",
+            name,
+            std::process::id()
+        ))
+    }
+
+    fn read_samples(path: &Path) -> Vec<i16> {
+        hound::WavReader::open(path)
+            .unwrap()
+            .samples::<i16>()
+            .map(|s| s.unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_in_range_samples_are_scaled() {
+        let mut stats = ConversionStats::default();
+
+        assert_eq!(sample_to_i16(0.0, &mut stats), 0);
+        assert_eq!(sample_to_i16(1.0, &mut stats), i16::MAX);
+        assert_eq!(sample_to_i16(-1.0, &mut stats), -i16::MAX);
+        assert_eq!(sample_to_i16(0.5, &mut stats), 16383);
+        assert!(!stats.has_issues());
+    }
+
+    #[test]
+    fn test_out_of_range_samples_are_clamped() {
+        let mut stats = ConversionStats::default();
+
+        assert_eq!(sample_to_i16(1.02, &mut stats), i16::MAX);
+        assert_eq!(sample_to_i16(-3.5, &mut stats), -i16::MAX);
+        assert_eq!(sample_to_i16(f32::INFINITY, &mut stats), i16::MAX);
+        assert_eq!(stats, ConversionStats { clamped: 3, nan: 0 });
+    }
+
+    #[test]
+    fn test_nan_samples_are_counted() {
+        let mut stats = ConversionStats::default();
+
+        assert_eq!(sample_to_i16(f32::NAN, &mut stats), 0);
+        assert_eq!(stats, ConversionStats { clamped: 0, nan: 1 });
+    }
+
+    #[test]
+    fn test_downmix_averages_channels() {
+        assert_eq!(downmix(&[0.5, -0.5, 1.0, 0.0], 2), vec![0.0, 0.5]);
+        assert_eq!(downmix(&[0.25, 0.5], 1), vec![0.25, 0.5]);
+    }
+
+    #[test]
+    fn test_resample_linear() {
+        assert_eq!(resample_linear(&[0.0, 1.0, 0.0], 16000, 16000).len(), 3);
+
+        // Upsampling by 2 interpolates between neighbours
+        assert_eq!(
+            resample_linear(&[0.0, 1.0], 8000, 16000),
+            vec![0.0, 0.5, 1.0, 1.0]
+        );
+        assert_eq!(resample_linear(&[0.0; 44100], 44100, 16000).len(), 16000);
+    }
+
+    #[test]
+    fn test_read_wav_converts_stereo_44k() {
+        let path = test_wav_path("stereo_44k");
+        let spec = hound::WavSpec {
+            channels: 2,
+            sample_rate: 44100,
+            bits_per_sample: 24,
+            sample_format: hound::SampleFormat::Int,
+        };
+        let mut writer = hound::WavWriter::create(&path, spec).unwrap();
+        // Half a second of a constant half-scale signal on both channels
+        for _ in 0..22050 {
+            writer.write_sample(1 << 22).unwrap();
+            writer.write_sample(1 << 22).unwrap();
+        }
+        writer.finalize().unwrap();
+
+        let samples = read_wav(&path).unwrap();
+
+        assert_eq!(samples.len(), 8000);
+        assert!(samples.iter().all(|&s| (s - 0.5).abs() < 1e-6));
+        std::fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn test_read_wav_float_and_8_bit() {
+        let float_path = test_wav_path("float");
+        let spec = hound::WavSpec {
+            channels: 1,
+            sample_rate: 16000,
+            bits_per_sample: 32,
+            sample_format: hound::SampleFormat::Float,
+        };
+        let mut writer = hound::WavWriter::create(&float_path, spec).unwrap();
+        for sample in [0.25f32, -0.75] {
+            writer.write_sample(sample).unwrap();
+        }
+        writer.finalize().unwrap();
+        assert_eq!(read_wav(&float_path).unwrap(), vec![0.25, -0.75]);
+        std::fs::remove_file(&float_path).ok();
+
+        let byte_path = test_wav_path("8bit");
+        let spec = hound::WavSpec {
+            channels: 1,
+            sample_rate: 16000,
+            bits_per_sample: 8,
+            sample_format: hound::SampleFormat::Int,
+        };
+        let mut writer = hound::WavWriter::create(&byte_path, spec).unwrap();
+        for sample in [64i8, -128] {
+            writer.write_sample(sample).unwrap();
+        }
+        writer.finalize().unwrap();
+        assert_eq!(read_wav(&byte_path).unwrap(), vec![0.5, -1.0]);
+        std::fs::remove_file(&byte_path).ok();
+    }
+
+    #[test]
+    fn test_is_wav_file() {
+        let wav_path = test_wav_path("header");
+        write_wav(&wav_path, &[0.0; 10]).unwrap();
+        assert!(is_wav_file(&wav_path).unwrap());
+        std::fs::remove_file(&wav_path).ok();
+
+        let text_path = test_wav_path("not_wav");
+        std::fs::write(&text_path, b"ID3 this is an mp3, honest").unwrap();
+        assert!(!is_wav_file(&text_path).unwrap());
+        std::fs::write(&text_path, b"RIFF").unwrap();
+        assert!(!is_wav_file(&text_path).unwrap());
+        std::fs::remove_file(&text_path).ok();
+    }
+
+    #[test]
+    fn test_write_wav_guards_samples() {
+        let path = test_wav_path("guard");
+
+        let stats = write_wav(&path, &[0.5, 1.5, f32::NAN, -2.0, -0.25]).unwrap();
+
+        assert_eq!(stats, ConversionStats { clamped: 2, nan: 1 });
+        assert_eq!(
+            read_samples(&path),
+            vec![16383, i16::MAX, 0, -i16::MAX, -8191]
+        );
+
+        let spec = hound::WavReader::open(&path).unwrap().spec();
+        assert_eq!(spec.sample_rate, 16000);
+        assert_eq!(spec.channels, 1);
+        assert_eq!(spec.bits_per_sample, 16);
+
+        std::fs::remove_file(&path).ok();
+    }
+}
@@ -0,0 +1,180 @@
+use std::time::Duration;
+
+/// A callback interval longer than this many buffer durations counts as an xrun
+const XRUN_THRESHOLD_BUFFERS: f64 = 1.5;
+
+/// Upper bound for silence inserted for a single gap, so a bogus timestamp
+/// cannot blow up the recording
+const MAX_GAP_FILL: Duration = Duration::from_secs(2);
+
+/// Detects gaps between audio input callbacks (xruns) from their capture timestamps
+///
+/// Each callback reports when its first frame was captured. If a callback starts
+/// noticeably later than the previous buffer ended, audio was lost in between,
+/// which is what makes starved recordings sound "robotic".
+pub struct XrunDetector {
+    /// Sample rate of the stream
+    sample_rate: u32,
+    /// Capture time and frame count of the previous callback
+    previous: Option<(Duration, usize)>,
+    /// Number of gaps detected so far
+    xruns: usize,
+    /// Whether silence should be inserted for detected gaps
+    fill_gaps: bool,
+}
+
+impl XrunDetector {
+    /// Creates a detector for a stream with the given sample rate
+    ///
+    /// # Arguments
+    /// * `sample_rate` - Sample rate of the stream in Hz
+    /// * `fill_gaps` - Whether `on_callback` should request silence for detected gaps
+    pub fn new(sample_rate: u32, fill_gaps: bool) -> Self {
+        Self {
+            sample_rate,
+            previous: None,
+            xruns: 0,
+            fill_gaps,
+        }
+    }
+
+    /// Records an input callback
+    ///
+    /// # Arguments
+    /// * `capture_time` - Capture time of the first frame, relative to any fixed origin
+    /// * `frames` - Number of frames delivered by the callback
+    ///
+    /// # Returns
+    /// Number of silent frames to insert before this callback's data
+    /// (always 0 when gap filling is disabled)
+    pub fn on_callback(&mut self, capture_time: Duration, frames: usize) -> usize {
+        let previous = self.previous.replace((capture_time, frames));
+        let Some((previous_time, previous_frames)) = previous else {
+            return 0;
+        };
+
+        let buffer_duration = self.frames_to_duration(previous_frames);
+        let interval = capture_time.saturating_sub(previous_time);
+        if buffer_duration.is_zero()
+            || interval.as_secs_f64() <= buffer_duration.as_secs_f64() * XRUN_THRESHOLD_BUFFERS
+        {
+            return 0;
+        }
+
+        self.xruns += 1;
+        let gap = interval.saturating_sub(buffer_duration).min(MAX_GAP_FILL);
+        log::warn!(
+            "Audio callback gap of {} ms detected (xrun #{})",
+            gap.as_millis(),
+            self.xruns
+        );
+
+        if self.fill_gaps {
+            (gap.as_secs_f64() * self.sample_rate as f64).round() as usize
+        } else {
+            0
+        }
+    }
+
+    /// Returns the number of gaps detected so far
+    pub fn xruns(&self) -> usize {
+        self.xruns
+    }
+
+    /// Converts a frame count into its duration at the stream's sample rate
+    fn frames_to_duration(&self, frames: usize) -> Duration {
+        if self.sample_rate == 0 {
+            return Duration::ZERO;
+        }
+        Duration::from_secs_f64(frames as f64 / self.sample_rate as f64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 16kHz stream with 1600-frame (100 ms) buffers
+    const RATE: u32 = 16000;
+    const FRAMES: usize = 1600;
+
+    fn ms(millis: u64) -> Duration {
+        Duration::from_millis(millis)
+    }
+
+    #[test]
+    fn test_steady_timeline_has_no_xruns() {
+        let mut detector = XrunDetector::new(RATE, true);
+
+        for i in 0..10 {
+            assert_eq!(detector.on_callback(ms(i * 100), FRAMES), 0);
+        }
+        assert_eq!(detector.xruns(), 0);
+    }
+
+    #[test]
+    fn test_jitter_below_threshold_is_ignored() {
+        let mut detector = XrunDetector::new(RATE, true);
+
+        assert_eq!(detector.on_callback(ms(0), FRAMES), 0);
+        assert_eq!(detector.on_callback(ms(140), FRAMES), 0);
+        assert_eq!(detector.on_callback(ms(220), FRAMES), 0);
+        assert_eq!(detector.xruns(), 0);
+    }
+
+    #[test]
+    fn test_gap_is_counted_and_filled() {
+        let mut detector = XrunDetector::new(RATE, true);
+
+        detector.on_callback(ms(0), FRAMES);
+        detector.on_callback(ms(100), FRAMES);
+        // 300 ms after the previous callback: 200 ms of audio went missing
+        let silence = detector.on_callback(ms(400), FRAMES);
+
+        assert_eq!(detector.xruns(), 1);
+        assert_eq!(silence, 3200);
+    }
+
+    #[test]
+    fn test_gap_fill_can_be_disabled() {
+        let mut detector = XrunDetector::new(RATE, false);
+
+        detector.on_callback(ms(0), FRAMES);
+        assert_eq!(detector.on_callback(ms(500), FRAMES), 0);
+        assert_eq!(detector.xruns(), 1);
+    }
+
+    #[test]
+    fn test_multiple_gaps_are_counted() {
+        let mut detector = XrunDetector::new(RATE, true);
+
+        let timeline = [0, 100, 300, 400, 500, 800, 900];
+        let silence: usize = timeline
+            .iter()
+            .map(|&t| detector.on_callback(ms(t), FRAMES))
+            .sum();
+
+        assert_eq!(detector.xruns(), 2);
+        // 100 ms + 200 ms of missing audio
+        assert_eq!(silence, 4800);
+    }
+
+    #[test]
+    fn test_gap_fill_is_capped() {
+        let mut detector = XrunDetector::new(RATE, true);
+
+        detector.on_callback(ms(0), FRAMES);
+        let silence = detector.on_callback(ms(60_000), FRAMES);
+
+        assert_eq!(silence, MAX_GAP_FILL.as_secs() as usize * RATE as usize);
+    }
+
+    #[test]
+    fn test_out_of_order_timestamp_is_not_an_xrun() {
+        let mut detector = XrunDetector::new(RATE, true);
+
+        detector.on_callback(ms(500), FRAMES);
+        assert_eq!(detector.on_callback(ms(100), FRAMES), 0);
+        assert_eq!(detector.xruns(), 0);
+    }
+}
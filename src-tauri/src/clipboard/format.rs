@@ -0,0 +1,195 @@
+use crate::commands::settings::PasteFormat;
+
+/// Formats text for pasting according to the configured paste format
+///
+/// # Arguments
+/// * `text` - Text to paste
+/// * `format` - Paste format from settings
+///
+/// # Returns
+/// The formatted text; empty text stays empty in every format
+pub fn format_paste_text(text: &str, format: PasteFormat) -> String {
+    if text.trim().is_empty() {
+        return text.to_string();
+    }
+
+    match format {
+        PasteFormat::PlainText => text.to_string(),
+        PasteFormat::MarkdownBlockquote => markdown_blockquote(text),
+        PasteFormat::MarkdownBullet => markdown_bullets(text),
+        PasteFormat::Html => html_paragraphs(text),
+    }
+}
+
+/// Quotes every line with `> `, keeping blank lines inside the quote
+fn markdown_blockquote(text: &str) -> String {
+    text.lines()
+        .map(|line| {
+            let line = line.trim_end();
+            if line.is_empty() {
+                ">".to_string()
+            } else {
+                format!("> {}", line)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Turns every non-blank line into a `- ` list item
+fn markdown_bullets(text: &str) -> String {
+    text.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(|line| format!("- {}", line))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Escapes the characters that have a meaning in HTML text
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Wraps every paragraph in `<p>`, with single line breaks as `<br>`
+///
+/// Paragraphs are separated by blank lines, as in Markdown.
+fn html_paragraphs(text: &str) -> String {
+    let mut paragraphs: Vec<Vec<&str>> = vec![Vec::new()];
+    for line in text.lines().map(str::trim) {
+        match (line.is_empty(), paragraphs.last_mut()) {
+            (true, Some(current)) if !current.is_empty() => paragraphs.push(Vec::new()),
+            (false, Some(current)) => current.push(line),
+            _ => {}
+        }
+    }
+
+    paragraphs
+        .iter()
+        .filter(|lines| !lines.is_empty())
+        .map(|lines| {
+            let lines: Vec<String> = lines.iter().map(|line| escape_html(line)).collect();
+            format!("<p>{}</p>", lines.join("<br>"))
+        })
+        .collect()
+}
+
+/// Header of a `CF_HTML` payload with the given byte offsets
+#[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+fn cf_html_header(
+    start_html: usize,
+    end_html: usize,
+    start_fragment: usize,
+    end_fragment: usize,
+) -> String {
+    format!(
+        "Version:0.9\r\nStartHTML:{:010}\r\nEndHTML:{:010}\r\nStartFragment:{:010}\r\nEndFragment:{:010}\r\n",
+        start_html, end_html, start_fragment, end_fragment
+    )
+}
+
+/// Builds a clipboard payload in the Windows `CF_HTML` format
+///
+/// The payload is a header with the byte offsets of the HTML document and the
+/// fragment inside it, followed by the document. Offsets are zero-padded to a
+/// fixed width so the header length doesn't depend on them.
+///
+/// # Arguments
+/// * `fragment` - HTML to paste
+///
+/// # Returns
+/// The UTF-8 payload, without the terminating NUL
+#[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+pub fn cf_html(fragment: &str) -> String {
+    const PREFIX: &str = "<html><body>\r\n<!--StartFragment-->";
+    const SUFFIX: &str = "<!--EndFragment-->\r\n</body></html>";
+
+    let start_html = cf_html_header(0, 0, 0, 0).len();
+    let start_fragment = start_html + PREFIX.len();
+    let end_fragment = start_fragment + fragment.len();
+    let end_html = end_fragment + SUFFIX.len();
+
+    format!(
+        "{}{}{}{}",
+        cf_html_header(start_html, end_html, start_fragment, end_fragment),
+        PREFIX,
+        fragment,
+        SUFFIX
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DICTATION: &str = "Buy milk.\nCall mom.\n\nBook flights";
+
+    #[test]
+    fn test_plain_text_is_unchanged() {
+        assert_eq!(
+            format_paste_text(DICTATION, PasteFormat::PlainText),
+            DICTATION
+        );
+    }
+
+    #[test]
+    fn test_markdown_blockquote_quotes_every_line() {
+        assert_eq!(
+            format_paste_text("Hello there.", PasteFormat::MarkdownBlockquote),
+            "> Hello there."
+        );
+        assert_eq!(
+            format_paste_text(DICTATION, PasteFormat::MarkdownBlockquote),
+            "> Buy milk.\n> Call mom.\n>\n> Book flights"
+        );
+    }
+
+    #[test]
+    fn test_markdown_bullet_lists_non_blank_lines() {
+        assert_eq!(
+            format_paste_text(DICTATION, PasteFormat::MarkdownBullet),
+            "- Buy milk.\n- Call mom.\n- Book flights"
+        );
+    }
+
+    #[test]
+    fn test_html_wraps_paragraphs_and_escapes() {
+        assert_eq!(
+            format_paste_text("Fish & chips <3", PasteFormat::Html),
+            "<p>Fish &amp; chips &lt;3</p>"
+        );
+        assert_eq!(
+            format_paste_text(DICTATION, PasteFormat::Html),
+            "<p>Buy milk.<br>Call mom.</p><p>Book flights</p>"
+        );
+    }
+
+    #[test]
+    fn test_empty_text_stays_empty() {
+        for format in PasteFormat::ALL {
+            assert_eq!(format_paste_text("", format), "");
+        }
+    }
+
+    #[test]
+    fn test_cf_html_offsets_point_at_fragment() {
+        let fragment = "<p>Grüße</p>";
+        let payload = cf_html(fragment);
+
+        let offset = |name: &str| -> usize {
+            let start = payload.find(name).unwrap() + name.len();
+            payload[start..start + 10].parse().unwrap()
+        };
+
+        assert!(payload.starts_with("Version:0.9\r\n"));
+        assert_eq!(
+            &payload[offset("StartFragment:")..offset("EndFragment:")],
+            fragment
+        );
+        assert!(payload[offset("StartHTML:")..].starts_with("<html>"));
+        assert_eq!(offset("EndHTML:"), payload.len());
+    }
+}
@@ -1,58 +1,366 @@
 #![allow(dead_code)]
 
+use super::shortcut::{Modifier, PasteKey, PasteShortcut};
 use anyhow::{Context, Result};
-use std::process::Command;
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use std::ffi::OsStr;
+use std::path::Path;
+use std::process::{Command, Output};
+use std::time::Duration;
+
+/// Whether the ydotool binary is installed, probed on first use
+static YDOTOOL_AVAILABLE: Lazy<bool> = Lazy::new(|| {
+    let available = Command::new("ydotool").arg("help").output().is_ok();
+    log::info!("ydotool available: {}", available);
+    available
+});
 
 /// Detects if the system is running Wayland or X11
 fn is_wayland() -> bool {
     std::env::var("WAYLAND_DISPLAY").is_ok()
 }
 
+/// A command-line tool needed for pasting that is not installed
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MissingTool {
+    /// Name of the binary looked up in PATH
+    pub name: &'static str,
+    /// What to install to get the binary
+    pub install_hint: &'static str,
+}
+
+/// Pasting is impossible because required tools are not installed
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, thiserror::Error)]
+#[error("Missing tools needed for pasting: {}", self.names())]
+pub struct MissingPasteTools {
+    pub missing: Vec<MissingTool>,
+}
+
+impl MissingPasteTools {
+    /// Comma-separated names of the missing tools
+    fn names(&self) -> String {
+        self.missing
+            .iter()
+            .map(|tool| tool.name)
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+/// Builds the missing tool entry for a binary, with the package that provides it
+fn missing_tool(name: &'static str) -> MissingTool {
+    let install_hint = match name {
+        "xclip" => "Install the 'xclip' package",
+        "xdotool" => "Install the 'xdotool' package",
+        "wl-copy" => "Install the 'wl-clipboard' package",
+        "wtype" => "Install the 'wtype' package, or 'xdotool' to paste into XWayland apps only",
+        _ => "Install it with your package manager",
+    };
+    MissingTool { name, install_hint }
+}
+
+/// Returns true if `path` is a file with an executable bit set
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    path.metadata()
+        .is_ok_and(|metadata| metadata.is_file() && metadata.permissions().mode() & 0o111 != 0)
+}
+
+/// Returns true if an executable called `name` is in one of the `search_path` directories
+fn is_in_path(name: &str, search_path: &OsStr) -> bool {
+    std::env::split_paths(search_path).any(|dir| is_executable(&dir.join(name)))
+}
+
+/// Lists the tools copying to the clipboard needs that are not in `search_path`
+///
+/// X11 needs xclip and Wayland needs wl-copy.
+///
+/// # Arguments
+/// * `search_path` - Directories to search, in the format of the PATH variable
+/// * `wayland` - Whether the session is Wayland rather than X11
+fn missing_copy_tools(search_path: &OsStr, wayland: bool) -> Vec<MissingTool> {
+    let name = if wayland { "wl-copy" } else { "xclip" };
+    if is_in_path(name, search_path) {
+        Vec::new()
+    } else {
+        vec![missing_tool(name)]
+    }
+}
+
+/// Lists the tools pasting needs that are not in `search_path`
+///
+/// Besides the tools to copy (see `missing_copy_tools`), X11 needs xdotool to
+/// press the shortcut. Wayland needs any of wtype, xdotool or ydotool; if none
+/// is installed, wtype is reported as the recommended one.
+///
+/// # Arguments
+/// * `search_path` - Directories to search, in the format of the PATH variable
+/// * `wayland` - Whether the session is Wayland rather than X11
+fn missing_paste_tools(search_path: &OsStr, wayland: bool) -> Vec<MissingTool> {
+    let mut missing = missing_copy_tools(search_path, wayland);
+
+    let key_tools: &[&str] = if wayland {
+        &["wtype", "xdotool", "ydotool"]
+    } else {
+        &["xdotool"]
+    };
+    if !key_tools.iter().any(|name| is_in_path(name, search_path)) {
+        missing.push(missing_tool(key_tools[0]));
+    }
+    missing
+}
+
+/// Turns a list of missing tools into an error if it isn't empty
+///
+/// Runs before anything is spawned so a missing tool gives an actionable error
+/// instead of a generic spawn failure.
+fn require_tools(missing: Vec<MissingTool>) -> Result<(), MissingPasteTools> {
+    if missing.is_empty() {
+        Ok(())
+    } else {
+        Err(MissingPasteTools { missing })
+    }
+}
+
+/// Directories searched for the paste tools
+fn search_path() -> std::ffi::OsString {
+    std::env::var_os("PATH").unwrap_or_default()
+}
+
 /// Pastes text on Linux using xdotool (X11) or wtype (Wayland)
 ///
+/// With the MiddleClick shortcut the text goes to the primary selection
+/// instead of the clipboard, since that is what a middle click pastes.
+///
 /// # Arguments
 /// * `text` - The text to paste
+/// * `shortcut` - Key combination or click to simulate after copying the text
+/// * `delay` - Time to wait between copying the text and simulating the shortcut
 ///
 /// # Returns
 /// * `Ok(())` if the text was pasted successfully
+/// * `Err(MissingPasteTools)` if the tools needed for pasting are not installed
 /// * `Err` if the paste command failed
-pub fn paste_text(text: &str) -> Result<()> {
+pub fn paste_text(text: &str, shortcut: &PasteShortcut, delay: Duration) -> Result<()> {
     log::info!("Pasting text on Linux");
 
-    if is_wayland() {
-        paste_text_wayland(text)
+    let wayland = is_wayland();
+    require_tools(missing_paste_tools(&search_path(), wayland))?;
+
+    copy_to_selection(text, x11_selection(shortcut), wayland)?;
+    super::wait_before_paste(delay);
+    let backend = simulate_paste(shortcut, wayland)?;
+
+    log::info!("Text pasted successfully using {}", backend);
+    Ok(())
+}
+
+/// Copies text to the clipboard with wl-copy (Wayland) or xclip (X11)
+///
+/// # Arguments
+/// * `text` - The text to copy
+///
+/// # Returns
+/// * `Ok(())` if the text was copied
+/// * `Err(MissingPasteTools)` if the tools needed for copying are not installed
+/// * `Err` if the copy command failed
+pub fn copy_to_clipboard(text: &str) -> Result<()> {
+    let wayland = is_wayland();
+    require_tools(missing_copy_tools(&search_path(), wayland))?;
+
+    copy_to_selection(text, "clipboard", wayland)
+}
+
+/// Reads text from the clipboard with wl-paste (Wayland) or xclip (X11)
+///
+/// # Returns
+/// * `Ok(String)` with the clipboard text
+/// * `Err` if the clipboard is empty, holds no text, or the command failed
+pub fn read_clipboard() -> Result<String> {
+    read_clipboard_with(is_wayland(), |command| command.output())
+}
+
+/// Reads the clipboard, running the reader command with `run`
+///
+/// # Arguments
+/// * `wayland` - Whether the session is Wayland rather than X11
+/// * `run` - Runs the command and returns its output; replaced in tests
+fn read_clipboard_with(
+    wayland: bool,
+    run: impl FnOnce(&mut Command) -> std::io::Result<Output>,
+) -> Result<String> {
+    let (tool, mut command) = if wayland {
+        let mut command = Command::new("wl-paste");
+        // wl-paste adds a trailing newline unless asked not to; "text" picks
+        // whichever text type the source offers instead of e.g. an image
+        command.args(["--no-newline", "--type", "text"]);
+        ("wl-paste", command)
+    } else {
+        let mut command = Command::new("xclip");
+        command
+            .args(["-selection", "clipboard", "-o", "-target", "UTF8_STRING"])
+            .env(
+                "DISPLAY",
+                std::env::var("DISPLAY").unwrap_or_else(|_| ":0".to_string()),
+            );
+        ("xclip", command)
+    };
+
+    let output = run(&mut command).with_context(|| format!("Failed to execute {}", tool))?;
+    super::clipboard_output_text(tool, output)
+}
+
+/// Copies text to an X11 selection (`"clipboard"` or `"primary"`)
+///
+/// On Wayland the text goes to the matching Wayland selection, and to the X11
+/// one as well on a best effort basis so XWayland apps can paste it too.
+fn copy_to_selection(text: &str, selection: &str, wayland: bool) -> Result<()> {
+    if wayland {
+        copy_to_wayland_clipboard(text, selection == "primary")?;
+        copy_to_x11_clipboard(text, selection); // Best effort, don't fail if xclip missing
+        Ok(())
     } else {
-        paste_text_x11(text)
+        write_x11_selection(text, selection)
     }
 }
 
-/// Pastes text using xdotool on X11
-fn paste_text_x11(text: &str) -> Result<()> {
-    log::info!("Using xdotool for X11");
+/// Simulates the paste shortcut, returning the name of the tool that worked
+///
+/// # Arguments
+/// * `shortcut` - Key combination or click to simulate
+/// * `wayland` - Whether the session is Wayland rather than X11
+fn simulate_paste(shortcut: &PasteShortcut, wayland: bool) -> Result<&'static str> {
+    if wayland {
+        simulate_paste_wayland(shortcut)
+    } else {
+        simulate_paste_xdotool(shortcut)?;
+        Ok("xdotool")
+    }
+}
 
-    let display = std::env::var("DISPLAY").unwrap_or_else(|_| ":0".to_string());
+/// X11 selection the text is copied to before pasting with the shortcut
+fn x11_selection(shortcut: &PasteShortcut) -> &'static str {
+    if shortcut.key == PasteKey::MiddleClick {
+        "primary"
+    } else {
+        "clipboard"
+    }
+}
 
-    // First, copy to clipboard using xclip
-    let mut child = Command::new("xclip")
-        .args(["-selection", "clipboard"])
-        .env("DISPLAY", &display)
-        .stdin(std::process::Stdio::piped())
-        .spawn()
-        .context("Failed to spawn xclip")?;
+/// Arguments for xdotool that press the shortcut, e.g. `key ctrl+v` or `click 2`
+fn xdotool_args(shortcut: &PasteShortcut) -> Vec<String> {
+    let key = match shortcut.key {
+        PasteKey::Letter(c) => c.to_string(),
+        PasteKey::Insert => "Insert".to_string(),
+        PasteKey::MiddleClick => return vec!["click".to_string(), "2".to_string()],
+    };
 
-    if let Some(mut stdin) = child.stdin.take() {
-        use std::io::Write;
-        stdin
-            .write_all(text.as_bytes())
-            .context("Failed to write to xclip")?;
+    let combo = shortcut
+        .modifiers
+        .iter()
+        .map(|modifier| match modifier {
+            Modifier::Ctrl => "ctrl",
+            Modifier::Shift => "shift",
+            Modifier::Alt => "alt",
+            Modifier::Super => "super",
+        })
+        .chain(std::iter::once(key.as_str()))
+        .collect::<Vec<_>>()
+        .join("+");
+
+    vec!["key".to_string(), combo]
+}
+
+/// Arguments for ydotool that press the shortcut, e.g. `key ctrl+v`,
+/// or `None` for a middle click
+fn ydotool_args(shortcut: &PasteShortcut) -> Option<Vec<String>> {
+    if shortcut.key == PasteKey::MiddleClick {
+        return None;
     }
+    Some(xdotool_args(shortcut))
+}
 
-    child.wait().context("Failed to wait for xclip")?;
+/// Arguments for wtype that press the shortcut, e.g. `-M ctrl -k v -m ctrl`,
+/// or `None` for a middle click, which wtype cannot simulate
+fn wtype_args(shortcut: &PasteShortcut) -> Option<Vec<String>> {
+    let key = match shortcut.key {
+        PasteKey::Letter(c) => c.to_string(),
+        PasteKey::Insert => "Insert".to_string(),
+        PasteKey::MiddleClick => return None,
+    };
+    let modifier_name = |modifier: &Modifier| match modifier {
+        Modifier::Ctrl => "ctrl",
+        Modifier::Shift => "shift",
+        Modifier::Alt => "alt",
+        Modifier::Super => "logo",
+    };
+
+    let mut args = Vec::new();
+    for modifier in &shortcut.modifiers {
+        args.extend(["-M".to_string(), modifier_name(modifier).to_string()]);
+    }
+    args.extend(["-k".to_string(), key]);
+    for modifier in shortcut.modifiers.iter().rev() {
+        args.extend(["-m".to_string(), modifier_name(modifier).to_string()]);
+    }
+    Some(args)
+}
+
+/// Types text on Linux by simulating keystrokes with xdotool (X11) or wtype (Wayland)
+///
+/// Unicode characters are typed as-is and newlines become Return key presses.
+/// Slower than pasting for long text, but works in apps that ignore Ctrl+V.
+///
+/// # Arguments
+/// * `text` - The text to type
+///
+/// # Returns
+/// * `Ok(())` if the text was typed successfully
+/// * `Err` if the typing command failed
+pub fn type_text(text: &str) -> Result<()> {
+    log::info!("Typing text on Linux");
 
-    // Then paste using xdotool
+    if is_wayland() {
+        // wtype only reaches native Wayland apps, fall back to xdotool for XWayland
+        if let Err(wtype_err) = type_text_wtype(text) {
+            log::warn!("wtype failed ({}), trying xdotool for XWayland", wtype_err);
+            type_text_xdotool(text)?;
+        }
+    } else {
+        type_text_xdotool(text)?;
+    }
+
+    log::info!("Text typed successfully");
+    Ok(())
+}
+
+/// Types text using wtype (native Wayland)
+fn type_text_wtype(text: &str) -> Result<()> {
+    let output = Command::new("wtype")
+        .arg("--")
+        .arg(text)
+        .output()
+        .context("Failed to execute wtype")?;
+
+    if !output.status.success() {
+        let error = String::from_utf8_lossy(&output.stderr);
+        return Err(anyhow::anyhow!("wtype failed: {}", error));
+    }
+
+    Ok(())
+}
+
+/// Types text using xdotool (X11 and XWayland apps)
+fn type_text_xdotool(text: &str) -> Result<()> {
     let output = Command::new("xdotool")
-        .args(["key", "ctrl+v"])
-        .env("DISPLAY", &display)
+        .args(["type", "--clearmodifiers", "--"])
+        .arg(text)
+        .env(
+            "DISPLAY",
+            std::env::var("DISPLAY").unwrap_or_else(|_| ":0".to_string()),
+        )
         .output()
         .context("Failed to execute xdotool")?;
 
@@ -61,31 +369,59 @@ fn paste_text_x11(text: &str) -> Result<()> {
         return Err(anyhow::anyhow!("xdotool failed: {}", error));
     }
 
-    log::info!("Text pasted successfully using xdotool");
     Ok(())
 }
 
-/// Pastes text on Wayland, handling both native Wayland and XWayland apps
-fn paste_text_wayland(text: &str) -> Result<()> {
-    log::info!("Using Wayland paste with XWayland support");
+/// Simulates the paste shortcut on Wayland, returning the name of the tool that worked
+///
+/// Tries wtype first (native Wayland), then xdotool (XWayland), then ydotool,
+/// which works on compositors without the virtual keyboard protocol wtype needs.
+/// wtype cannot click, so middle clicks go to xdotool directly.
+fn simulate_paste_wayland(shortcut: &PasteShortcut) -> Result<&'static str> {
+    let mut errors = Vec::new();
 
-    // Copy to both Wayland and X11 clipboards for compatibility
-    copy_to_wayland_clipboard(text)?;
-    copy_to_x11_clipboard(text); // Best effort, don't fail if xclip missing
+    if let Some(args) = wtype_args(shortcut) {
+        match simulate_paste_wtype(&args) {
+            Ok(()) => return Ok("wtype"),
+            Err(e) => {
+                log::warn!("wtype failed ({}), trying xdotool for XWayland", e);
+                errors.push(e.to_string());
+            }
+        }
+    }
 
-    // Try wtype first (native Wayland), fall back to xdotool (XWayland)
-    if let Err(wtype_err) = simulate_paste_wtype() {
-        log::warn!("wtype failed ({}), trying xdotool for XWayland", wtype_err);
-        simulate_paste_xdotool()?;
+    match simulate_paste_xdotool(shortcut) {
+        Ok(()) => return Ok("xdotool"),
+        Err(e) => {
+            log::warn!("xdotool failed ({}), trying ydotool", e);
+            errors.push(e.to_string());
+        }
     }
 
-    log::info!("Text pasted successfully");
-    Ok(())
+    if let Some(args) = ydotool_args(shortcut) {
+        if *YDOTOOL_AVAILABLE {
+            match simulate_paste_ydotool(&args) {
+                Ok(()) => return Ok("ydotool"),
+                Err(e) => errors.push(e.to_string()),
+            }
+        } else {
+            errors.push("ydotool is not installed".to_string());
+        }
+    }
+
+    Err(anyhow::anyhow!(
+        "No paste backend worked: {}",
+        errors.join("; ")
+    ))
 }
 
-/// Copy text to Wayland clipboard using wl-copy
-fn copy_to_wayland_clipboard(text: &str) -> Result<()> {
-    let mut child = Command::new("wl-copy")
+/// Copy text to Wayland clipboard, or the primary selection, using wl-copy
+fn copy_to_wayland_clipboard(text: &str, primary: bool) -> Result<()> {
+    let mut command = Command::new("wl-copy");
+    if primary {
+        command.arg("--primary");
+    }
+    let mut child = command
         .stdin(std::process::Stdio::piped())
         .spawn()
         .context("Failed to spawn wl-copy")?;
@@ -102,40 +438,41 @@ fn copy_to_wayland_clipboard(text: &str) -> Result<()> {
     Ok(())
 }
 
-/// Copy text to X11 clipboard using xclip (for XWayland apps)
-fn copy_to_x11_clipboard(text: &str) {
-    let result = (|| -> Result<()> {
-        let mut child = Command::new("xclip")
-            .args(["-selection", "clipboard"])
-            .env(
-                "DISPLAY",
-                std::env::var("DISPLAY").unwrap_or_else(|_| ":0".to_string()),
-            )
-            .stdin(std::process::Stdio::piped())
-            .spawn()
-            .context("Failed to spawn xclip")?;
-
-        if let Some(mut stdin) = child.stdin.take() {
-            use std::io::Write;
-            stdin
-                .write_all(text.as_bytes())
-                .context("Failed to write to xclip")?;
-        }
+/// Copy text to an X11 selection using xclip
+fn write_x11_selection(text: &str, selection: &str) -> Result<()> {
+    let mut child = Command::new("xclip")
+        .args(["-selection", selection])
+        .env(
+            "DISPLAY",
+            std::env::var("DISPLAY").unwrap_or_else(|_| ":0".to_string()),
+        )
+        .stdin(std::process::Stdio::piped())
+        .spawn()
+        .context("Failed to spawn xclip")?;
 
-        child.wait().context("Failed to wait for xclip")?;
-        log::info!("Copied to X11 clipboard");
-        Ok(())
-    })();
+    if let Some(mut stdin) = child.stdin.take() {
+        use std::io::Write;
+        stdin
+            .write_all(text.as_bytes())
+            .context("Failed to write to xclip")?;
+    }
 
-    if let Err(e) = result {
+    child.wait().context("Failed to wait for xclip")?;
+    log::info!("Copied to X11 clipboard");
+    Ok(())
+}
+
+/// Copy text to an X11 selection for XWayland apps, logging failures
+fn copy_to_x11_clipboard(text: &str, selection: &str) {
+    if let Err(e) = write_x11_selection(text, selection) {
         log::warn!("Failed to copy to X11 clipboard: {}", e);
     }
 }
 
-/// Simulate Ctrl+V using wtype (native Wayland)
-fn simulate_paste_wtype() -> Result<()> {
+/// Simulate the paste shortcut using wtype (native Wayland)
+fn simulate_paste_wtype(args: &[String]) -> Result<()> {
     let output = Command::new("wtype")
-        .args(["-M", "ctrl", "v", "-m", "ctrl"])
+        .args(args)
         .output()
         .context("Failed to execute wtype")?;
 
@@ -147,10 +484,25 @@ fn simulate_paste_wtype() -> Result<()> {
     Ok(())
 }
 
-/// Simulate Ctrl+V using xdotool (XWayland apps)
-fn simulate_paste_xdotool() -> Result<()> {
+/// Simulate the paste shortcut using ydotool (uinput, needs the ydotoold daemon)
+fn simulate_paste_ydotool(args: &[String]) -> Result<()> {
+    let output = Command::new("ydotool")
+        .args(args)
+        .output()
+        .context("Failed to execute ydotool")?;
+
+    if !output.status.success() {
+        let error = String::from_utf8_lossy(&output.stderr);
+        return Err(anyhow::anyhow!("ydotool failed: {}", error));
+    }
+
+    Ok(())
+}
+
+/// Simulate the paste shortcut using xdotool (XWayland apps)
+fn simulate_paste_xdotool(shortcut: &PasteShortcut) -> Result<()> {
     let output = Command::new("xdotool")
-        .args(["key", "ctrl+v"])
+        .args(xdotool_args(shortcut))
         .env(
             "DISPLAY",
             std::env::var("DISPLAY").unwrap_or_else(|_| ":0".to_string()),
@@ -165,3 +517,204 @@ fn simulate_paste_xdotool() -> Result<()> {
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn shortcut(value: &str) -> PasteShortcut {
+        value.parse().unwrap()
+    }
+
+    /// Program and arguments of a command
+    fn command_line(command: &Command) -> Vec<String> {
+        std::iter::once(command.get_program())
+            .chain(command.get_args())
+            .map(|arg| arg.to_string_lossy().into_owned())
+            .collect()
+    }
+
+    #[test]
+    fn test_read_clipboard_round_trips_utf8() {
+        let text = "Grüße, 日本語 👋\nzweite Zeile";
+        for (wayland, tool) in [(false, "xclip"), (true, "wl-paste")] {
+            let mut command_run = Vec::new();
+            let read = read_clipboard_with(wayland, |command| {
+                command_run = command_line(command);
+                Ok(super::super::fake_output(0, text.as_bytes(), ""))
+            })
+            .unwrap();
+
+            assert_eq!(read, text);
+            assert_eq!(command_run[0], tool);
+        }
+    }
+
+    #[test]
+    fn test_read_clipboard_requests_utf8_text() {
+        let mut command_run = Vec::new();
+        read_clipboard_with(false, |command| {
+            command_run = command_line(command);
+            Ok(super::super::fake_output(0, b"", ""))
+        })
+        .unwrap();
+        assert_eq!(
+            command_run,
+            vec![
+                "xclip",
+                "-selection",
+                "clipboard",
+                "-o",
+                "-target",
+                "UTF8_STRING"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_read_clipboard_reports_failures() {
+        let err = read_clipboard_with(true, |_| {
+            Ok(super::super::fake_output(1, b"", "Nothing is copied\n"))
+        })
+        .unwrap_err();
+        assert_eq!(err.to_string(), "wl-paste failed: Nothing is copied");
+
+        let err = read_clipboard_with(false, |_| {
+            Err(std::io::Error::from(std::io::ErrorKind::NotFound))
+        })
+        .unwrap_err();
+        assert_eq!(err.to_string(), "Failed to execute xclip");
+    }
+
+    #[test]
+    fn test_xdotool_args() {
+        assert_eq!(xdotool_args(&shortcut("Ctrl+V")), vec!["key", "ctrl+v"]);
+        assert_eq!(
+            xdotool_args(&shortcut("Shift+Insert")),
+            vec!["key", "shift+Insert"]
+        );
+        assert_eq!(xdotool_args(&shortcut("MiddleClick")), vec!["click", "2"]);
+    }
+
+    #[test]
+    fn test_wtype_args_release_modifiers_in_reverse() {
+        assert_eq!(
+            wtype_args(&shortcut("Ctrl+Shift+V")).unwrap(),
+            vec!["-M", "ctrl", "-M", "shift", "-k", "v", "-m", "shift", "-m", "ctrl"]
+        );
+        assert_eq!(wtype_args(&shortcut("MiddleClick")), None);
+    }
+
+    #[test]
+    fn test_ydotool_args() {
+        assert_eq!(
+            ydotool_args(&shortcut("Ctrl+V")).unwrap(),
+            vec!["key", "ctrl+v"]
+        );
+        assert_eq!(ydotool_args(&shortcut("MiddleClick")), None);
+    }
+
+    /// Creates an empty directory to use as a fake PATH
+    fn fake_path(name: &str) -> std::path::PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "rustler_paste_tools_{}_{}",
+            std::process::id(),
+            name
+        ));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn install(dir: &Path, name: &str, mode: u32) {
+        use std::os::unix::fs::PermissionsExt;
+        let path = dir.join(name);
+        std::fs::write(&path, "#!/bin/sh\n").unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(mode)).unwrap();
+    }
+
+    fn missing_names(dir: &Path, wayland: bool) -> Vec<&'static str> {
+        missing_paste_tools(dir.as_os_str(), wayland)
+            .iter()
+            .map(|tool| tool.name)
+            .collect()
+    }
+
+    #[test]
+    fn test_reports_all_tools_missing_from_empty_path() {
+        let dir = fake_path("empty");
+
+        assert_eq!(missing_names(&dir, false), vec!["xclip", "xdotool"]);
+        assert_eq!(missing_names(&dir, true), vec!["wl-copy", "wtype"]);
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_finds_tools_across_path_entries() {
+        let bin = fake_path("bin");
+        let local = fake_path("local");
+        install(&bin, "xclip", 0o755);
+        install(&local, "xdotool", 0o755);
+        let search_path = std::env::join_paths([&bin, &local]).unwrap();
+
+        assert!(missing_paste_tools(&search_path, false).is_empty());
+        assert!(missing_copy_tools(&search_path, false).is_empty());
+        // xdotool can press the shortcut on Wayland too, only wl-copy is missing
+        assert_eq!(
+            missing_paste_tools(&search_path, true),
+            vec![missing_tool("wl-copy")]
+        );
+        std::fs::remove_dir_all(&bin).ok();
+        std::fs::remove_dir_all(&local).ok();
+    }
+
+    #[test]
+    fn test_ignores_files_that_are_not_executable() {
+        let dir = fake_path("noexec");
+        install(&dir, "xclip", 0o644);
+        install(&dir, "xdotool", 0o755);
+        std::fs::create_dir(dir.join("wl-copy")).unwrap();
+
+        assert_eq!(missing_names(&dir, false), vec!["xclip"]);
+        assert_eq!(missing_names(&dir, true), vec!["wl-copy"]);
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_copying_needs_only_the_clipboard_tool() {
+        let dir = fake_path("copy");
+        install(&dir, "wl-copy", 0o755);
+
+        assert!(missing_copy_tools(dir.as_os_str(), true).is_empty());
+        assert_eq!(missing_names(&dir, true), vec!["wtype"]);
+        assert_eq!(
+            missing_copy_tools(dir.as_os_str(), false),
+            vec![missing_tool("xclip")]
+        );
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_missing_tools_error_lists_names_and_hints() {
+        let error = MissingPasteTools {
+            missing: vec![missing_tool("wl-copy"), missing_tool("wtype")],
+        };
+
+        assert_eq!(
+            error.to_string(),
+            "Missing tools needed for pasting: wl-copy, wtype"
+        );
+        let json = serde_json::to_value(&error).unwrap();
+        assert_eq!(json["missing"][0]["name"], "wl-copy");
+        assert_eq!(
+            json["missing"][0]["installHint"],
+            "Install the 'wl-clipboard' package"
+        );
+    }
+
+    #[test]
+    fn test_middle_click_uses_primary_selection() {
+        assert_eq!(x11_selection(&shortcut("MiddleClick")), "primary");
+        assert_eq!(x11_selection(&shortcut("Ctrl+V")), "clipboard");
+    }
+}
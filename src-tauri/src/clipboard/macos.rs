@@ -1,17 +1,84 @@
+use super::shortcut::{Modifier, PasteKey, PasteShortcut};
 use anyhow::{Context, Result};
-use std::process::Command;
+use std::process::{Command, Output};
+use std::time::Duration;
+
+/// Builds the System Events command that presses the paste shortcut,
+/// e.g. `keystroke "v" using {command down}`
+fn paste_keystroke(shortcut: &PasteShortcut) -> Result<String> {
+    let key = match shortcut.key {
+        PasteKey::Letter(c) => format!("keystroke \"{}\"", c),
+        // Key code 114 is the Help/Insert key
+        PasteKey::Insert => "key code 114".to_string(),
+        PasteKey::MiddleClick => {
+            return Err(anyhow::anyhow!(
+                "MiddleClick pasting is not supported on macOS"
+            ))
+        }
+    };
+
+    let modifiers: Vec<&str> = shortcut
+        .modifiers
+        .iter()
+        .map(|modifier| match modifier {
+            Modifier::Ctrl => "control down",
+            Modifier::Shift => "shift down",
+            Modifier::Alt => "option down",
+            Modifier::Super => "command down",
+        })
+        .collect();
+
+    if modifiers.is_empty() {
+        Ok(key)
+    } else {
+        Ok(format!("{} using {{{}}}", key, modifiers.join(", ")))
+    }
+}
 
 /// Pastes text on macOS using AppleScript
 ///
 /// # Arguments
 /// * `text` - The text to paste
+/// * `shortcut` - Key combination to press after setting the clipboard
+/// * `delay` - Time to wait between setting the clipboard and simulating the shortcut
 ///
 /// # Returns
 /// * `Ok(())` if the text was pasted successfully
 /// * `Err` if the AppleScript command failed
-pub fn paste_text(text: &str) -> Result<()> {
+pub fn paste_text(text: &str, shortcut: &PasteShortcut, delay: Duration) -> Result<()> {
     log::info!("Pasting text on macOS using AppleScript");
 
+    copy_to_clipboard(text)?;
+    super::wait_before_paste(delay);
+    simulate_paste(shortcut)?;
+
+    log::info!("Text pasted successfully on macOS");
+    Ok(())
+}
+
+/// Presses the paste shortcut through System Events
+fn simulate_paste(shortcut: &PasteShortcut) -> Result<()> {
+    let keystroke = paste_keystroke(shortcut)?;
+
+    run_applescript(&format!(
+        r#"
+        tell application "System Events"
+            {}
+        end tell
+        "#,
+        keystroke
+    ))
+}
+
+/// Copies text to the clipboard on macOS using AppleScript
+///
+/// # Arguments
+/// * `text` - The text to copy
+///
+/// # Returns
+/// * `Ok(())` if the text was copied
+/// * `Err` if the AppleScript command failed
+pub fn copy_to_clipboard(text: &str) -> Result<()> {
     // Escape special characters for AppleScript
     let escaped_text = text
         .replace("\\", "\\\\")
@@ -19,21 +86,39 @@ pub fn paste_text(text: &str) -> Result<()> {
         .replace("\n", "\\n")
         .replace("\r", "\\r");
 
-    // AppleScript to set clipboard and paste
-    let script = format!(
-        r#"
-        set the clipboard to "{}"
-        tell application "System Events"
-            keystroke "v" using command down
-        end tell
-        "#,
-        escaped_text
-    );
+    run_applescript(&format!("set the clipboard to \"{}\"", escaped_text))
+}
+
+/// Reads text from the clipboard on macOS using pbpaste
+///
+/// # Returns
+/// * `Ok(String)` with the clipboard text
+/// * `Err` if pbpaste failed
+pub fn read_clipboard() -> Result<String> {
+    read_clipboard_with(|command| command.output())
+}
+
+/// Reads the clipboard, running pbpaste with `run`
+///
+/// # Arguments
+/// * `run` - Runs the command and returns its output; replaced in tests
+fn read_clipboard_with(
+    run: impl FnOnce(&mut Command) -> std::io::Result<Output>,
+) -> Result<String> {
+    let mut command = Command::new("pbpaste");
+    // pbpaste converts the text to the locale's encoding, which isn't UTF-8 for
+    // apps launched from Finder without LANG set
+    command.env("LANG", "en_US.UTF-8");
 
-    // Execute AppleScript
+    let output = run(&mut command).context("Failed to execute pbpaste")?;
+    super::clipboard_output_text("pbpaste", output)
+}
+
+/// Runs an AppleScript with osascript
+fn run_applescript(script: &str) -> Result<()> {
     let output = Command::new("osascript")
         .arg("-e")
-        .arg(&script)
+        .arg(script)
         .output()
         .context("Failed to execute osascript")?;
 
@@ -42,6 +127,95 @@ pub fn paste_text(text: &str) -> Result<()> {
         return Err(anyhow::anyhow!("AppleScript failed: {}", error));
     }
 
-    log::info!("Text pasted successfully on macOS");
     Ok(())
 }
+
+/// Escapes text for use inside an AppleScript string literal
+fn escape_applescript(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+/// Builds the AppleScript that types text line by line, pressing Return between lines
+fn type_script(text: &str) -> String {
+    let mut commands = Vec::new();
+    for (i, line) in text.split('\n').enumerate() {
+        if i > 0 {
+            // Key code 36 is the Return key
+            commands.push("key code 36".to_string());
+        }
+        let line = line.trim_end_matches('\r');
+        if !line.is_empty() {
+            commands.push(format!("keystroke \"{}\"", escape_applescript(line)));
+        }
+    }
+
+    format!(
+        r#"
+        tell application "System Events"
+            {}
+        end tell
+        "#,
+        commands.join("\n            ")
+    )
+}
+
+/// Types text on macOS by simulating keystrokes with AppleScript
+///
+/// Unicode characters are typed as-is and newlines become Return key presses.
+/// Slower than pasting for long text, but works in apps that block Cmd+V.
+///
+/// # Arguments
+/// * `text` - The text to type
+///
+/// # Returns
+/// * `Ok(())` if the text was typed successfully
+/// * `Err` if the AppleScript command failed
+pub fn type_text(text: &str) -> Result<()> {
+    log::info!("Typing text on macOS using AppleScript");
+
+    run_applescript(&type_script(text))?;
+
+    log::info!("Text typed successfully on macOS");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_clipboard_round_trips_utf8() {
+        let text = "Grüße, 日本語 👋\nzweite Zeile";
+        let mut program = String::new();
+        let read = read_clipboard_with(|command| {
+            program = command.get_program().to_string_lossy().into_owned();
+            Ok(super::super::fake_output(0, text.as_bytes(), ""))
+        })
+        .unwrap();
+
+        assert_eq!(read, text);
+        assert_eq!(program, "pbpaste");
+    }
+
+    #[test]
+    fn test_read_clipboard_asks_pbpaste_for_utf8() {
+        let mut lang = None;
+        read_clipboard_with(|command| {
+            lang = command
+                .get_envs()
+                .find(|(key, _)| *key == "LANG")
+                .and_then(|(_, value)| value.map(|value| value.to_owned()));
+            Ok(super::super::fake_output(0, b"", ""))
+        })
+        .unwrap();
+
+        assert_eq!(lang.as_deref(), Some(std::ffi::OsStr::new("en_US.UTF-8")));
+    }
+
+    #[test]
+    fn test_read_clipboard_reports_missing_pbpaste() {
+        let err = read_clipboard_with(|_| Err(std::io::Error::from(std::io::ErrorKind::NotFound)))
+            .unwrap_err();
+        assert_eq!(err.to_string(), "Failed to execute pbpaste");
+    }
+}
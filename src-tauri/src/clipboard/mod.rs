@@ -1,4 +1,13 @@
 use anyhow::Result;
+use shortcut::PasteShortcut;
+use std::cell::RefCell;
+use std::time::Duration;
+
+/// Formatting of text before it is pasted (Markdown, HTML)
+pub mod format;
+
+/// Parsing of the configurable paste shortcut
+pub mod shortcut;
 
 // Platform-specific clipboard implementations
 #[cfg(target_os = "macos")]
@@ -7,32 +16,66 @@ mod macos;
 #[cfg(target_os = "linux")]
 mod linux;
 
+#[cfg(target_os = "linux")]
+pub use linux::MissingPasteTools;
+
 #[cfg(target_os = "windows")]
 mod windows;
 
+/// Maximum delay between copying the text and simulating the paste shortcut
+pub const MAX_PASTE_DELAY_MS: u64 = 2000;
+
+thread_local! {
+    /// Sleep used by `wait_before_paste`; tests replace it to observe the requested delay
+    static SLEEP_HOOK: RefCell<Box<dyn Fn(Duration)>> = RefCell::new(Box::new(std::thread::sleep));
+}
+
+/// Replaces the sleep used by `wait_before_paste` on the current thread
+#[cfg(test)]
+fn set_sleep_hook(hook: impl Fn(Duration) + 'static) {
+    SLEEP_HOOK.with(|sleep| *sleep.borrow_mut() = Box::new(hook));
+}
+
+/// Blocks the calling thread before the paste shortcut is simulated, giving
+/// the target application time to regain focus after the hotkey
+///
+/// Platform paste functions run off the main thread, so blocking is fine.
+///
+/// # Arguments
+/// * `delay` - Configured paste delay; nothing happens if it is zero
+fn wait_before_paste(delay: Duration) {
+    if delay.is_zero() {
+        return;
+    }
+    log::debug!("Waiting {} ms before pasting", delay.as_millis());
+    SLEEP_HOOK.with(|sleep| (sleep.borrow())(delay));
+}
+
 /// Pastes text to the active application using platform-specific methods
 ///
 /// # Arguments
 /// * `text` - The text to paste
+/// * `shortcut` - Key combination simulated to paste, e.g. Ctrl+V or Shift+Insert
+/// * `delay` - Time to wait between copying the text and simulating the shortcut
 ///
 /// # Returns
 /// * `Ok(())` if the text was pasted successfully
 /// * `Err` if pasting failed
 #[allow(dead_code)]
-pub fn paste_text(text: &str) -> Result<()> {
+pub fn paste_text(text: &str, shortcut: &PasteShortcut, delay: Duration) -> Result<()> {
     #[cfg(target_os = "macos")]
     {
-        macos::paste_text(text)
+        macos::paste_text(text, shortcut, delay)
     }
 
     #[cfg(target_os = "linux")]
     {
-        linux::paste_text(text)
+        linux::paste_text(text, shortcut, delay)
     }
 
     #[cfg(target_os = "windows")]
     {
-        windows::paste_text(text)
+        windows::paste_text(text, shortcut, delay)
     }
 
     #[cfg(not(any(target_os = "macos", target_os = "linux", target_os = "windows")))]
@@ -42,3 +85,233 @@ pub fn paste_text(text: &str) -> Result<()> {
         ))
     }
 }
+
+/// Pastes HTML to the active application
+///
+/// On Windows the clipboard is set in the `CF_HTML` format so rich-text editors
+/// receive formatted text. Elsewhere the markup is pasted as plain text.
+///
+/// # Arguments
+/// * `html` - The HTML fragment to paste
+/// * `shortcut` - Key combination simulated to paste
+/// * `delay` - Time to wait between copying the HTML and simulating the shortcut
+///
+/// # Returns
+/// * `Ok(())` if the HTML was pasted successfully
+/// * `Err` if pasting failed
+pub fn paste_html(html: &str, shortcut: &PasteShortcut, delay: Duration) -> Result<()> {
+    #[cfg(target_os = "windows")]
+    {
+        windows::paste_html(html, shortcut, delay)
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        paste_text(html, shortcut, delay)
+    }
+}
+
+/// Copies text to the clipboard without pasting it
+///
+/// # Arguments
+/// * `text` - The text to copy
+///
+/// # Returns
+/// * `Ok(())` if the text was copied
+/// * `Err` if copying failed
+pub fn copy_to_clipboard(text: &str) -> Result<()> {
+    #[cfg(target_os = "macos")]
+    {
+        macos::copy_to_clipboard(text)
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        linux::copy_to_clipboard(text)
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        windows::copy_to_clipboard(text)
+    }
+
+    #[cfg(not(any(target_os = "macos", target_os = "linux", target_os = "windows")))]
+    {
+        Err(anyhow::anyhow!("Clipboard not supported on this platform"))
+    }
+}
+
+/// Copies HTML to the clipboard without pasting it
+///
+/// Like `paste_html`, this uses the `CF_HTML` format on Windows and copies the
+/// markup as plain text elsewhere.
+///
+/// # Arguments
+/// * `html` - The HTML fragment to copy
+///
+/// # Returns
+/// * `Ok(())` if the HTML was copied
+/// * `Err` if copying failed
+pub fn copy_html(html: &str) -> Result<()> {
+    #[cfg(target_os = "windows")]
+    {
+        windows::copy_html(html)
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        copy_to_clipboard(html)
+    }
+}
+
+/// Reads text from the clipboard
+///
+/// # Returns
+/// * `Ok(String)` with the clipboard text
+/// * `Err` if the clipboard could not be read or doesn't hold valid text
+pub fn read_clipboard() -> Result<String> {
+    #[cfg(target_os = "macos")]
+    {
+        macos::read_clipboard()
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        linux::read_clipboard()
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        windows::read_clipboard()
+    }
+
+    #[cfg(not(any(target_os = "macos", target_os = "linux", target_os = "windows")))]
+    {
+        Err(anyhow::anyhow!("Clipboard not supported on this platform"))
+    }
+}
+
+/// Turns the output of a command-line clipboard reader into text
+///
+/// # Arguments
+/// * `tool` - Name of the command, for error messages
+/// * `output` - Output of the command
+///
+/// # Returns
+/// * `Ok(String)` with the command's stdout
+/// * `Err` if the command failed or its output is not UTF-8
+#[cfg(any(target_os = "macos", target_os = "linux"))]
+fn clipboard_output_text(tool: &str, output: std::process::Output) -> Result<String> {
+    if !output.status.success() {
+        let error = String::from_utf8_lossy(&output.stderr);
+        anyhow::bail!("{} failed: {}", tool, error.trim());
+    }
+    String::from_utf8(output.stdout)
+        .map_err(|_| anyhow::anyhow!("The clipboard does not hold UTF-8 text"))
+}
+
+/// Types text into the active application by simulating keystrokes
+///
+/// An alternative to `paste_text` for apps that don't honor the paste shortcut
+/// (terminals, games, secure fields). Slower for long text.
+///
+/// # Arguments
+/// * `text` - The text to type
+///
+/// # Returns
+/// * `Ok(())` if the text was typed successfully
+/// * `Err` if typing failed
+pub fn type_text(text: &str) -> Result<()> {
+    #[cfg(target_os = "macos")]
+    {
+        macos::type_text(text)
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        linux::type_text(text)
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        windows::type_text(text)
+    }
+
+    #[cfg(not(any(target_os = "macos", target_os = "linux", target_os = "windows")))]
+    {
+        Err(anyhow::anyhow!(
+            "Typing text not supported on this platform"
+        ))
+    }
+}
+
+/// Builds the output of a clipboard reader for tests that fake subprocesses
+#[cfg(all(test, any(target_os = "macos", target_os = "linux")))]
+fn fake_output(code: i32, stdout: &[u8], stderr: &str) -> std::process::Output {
+    use std::os::unix::process::ExitStatusExt;
+    std::process::Output {
+        // Wait statuses keep the exit code in the second byte
+        status: std::process::ExitStatus::from_raw(code << 8),
+        stdout: stdout.to_vec(),
+        stderr: stderr.as_bytes().to_vec(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::rc::Rc;
+
+    /// Replaces the sleep with one recording the requested durations
+    fn record_sleeps() -> Rc<RefCell<Vec<Duration>>> {
+        let requested = Rc::new(RefCell::new(Vec::new()));
+        let recorder = Rc::clone(&requested);
+        set_sleep_hook(move |delay| recorder.borrow_mut().push(delay));
+        requested
+    }
+
+    #[test]
+    fn test_wait_before_paste_sleeps_for_the_delay() {
+        let requested = record_sleeps();
+
+        wait_before_paste(Duration::from_millis(350));
+        wait_before_paste(Duration::from_millis(MAX_PASTE_DELAY_MS));
+
+        assert_eq!(
+            *requested.borrow(),
+            vec![
+                Duration::from_millis(350),
+                Duration::from_millis(MAX_PASTE_DELAY_MS)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_zero_delay_does_not_sleep() {
+        let requested = record_sleeps();
+
+        wait_before_paste(Duration::ZERO);
+
+        assert!(requested.borrow().is_empty());
+    }
+
+    #[cfg(any(target_os = "macos", target_os = "linux"))]
+    #[test]
+    fn test_clipboard_output_text() {
+        let text = "Grüße 👋\nzweite Zeile";
+        assert_eq!(
+            clipboard_output_text("pbpaste", fake_output(0, text.as_bytes(), "")).unwrap(),
+            text
+        );
+
+        let err = clipboard_output_text(
+            "xclip",
+            fake_output(1, b"", "target STRING not available\n"),
+        )
+        .unwrap_err();
+        assert_eq!(err.to_string(), "xclip failed: target STRING not available");
+
+        let err = clipboard_output_text("wl-paste", fake_output(0, &[0xff, 0xfe], "")).unwrap_err();
+        assert_eq!(err.to_string(), "The clipboard does not hold UTF-8 text");
+    }
+}
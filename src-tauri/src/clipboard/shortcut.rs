@@ -0,0 +1,194 @@
+use anyhow::{bail, Result};
+use std::str::FromStr;
+
+/// Modifier key held down while the paste key is pressed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Modifier {
+    Ctrl,
+    Shift,
+    Alt,
+    /// Cmd on macOS, the Windows key on Windows, Super on Linux
+    Super,
+}
+
+/// Key or mouse button that triggers the paste
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PasteKey {
+    /// A letter key, stored lowercase
+    Letter(char),
+    /// The Insert key, as in Shift+Insert
+    Insert,
+    /// The middle mouse button, pasting the primary selection on Linux
+    MiddleClick,
+}
+
+/// Key combination used to paste text into the active application
+///
+/// Parsed from strings like `"Ctrl+V"`, `"Cmd+V"`, `"Shift+Insert"` or `"MiddleClick"`.
+/// Names are case-insensitive; `CommandOrControl` means Cmd on macOS and Ctrl elsewhere,
+/// as in hotkeys.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PasteShortcut {
+    /// Modifiers in the order they are pressed
+    pub modifiers: Vec<Modifier>,
+    pub key: PasteKey,
+}
+
+/// Platform-native paste shortcut: Cmd+V on macOS, Ctrl+V elsewhere
+pub fn default_paste_shortcut() -> &'static str {
+    if cfg!(target_os = "macos") {
+        "Cmd+V"
+    } else {
+        "Ctrl+V"
+    }
+}
+
+/// Parses a modifier name, returning `None` if the name is not a modifier
+fn parse_modifier(name: &str) -> Option<Modifier> {
+    match name {
+        "ctrl" | "control" => Some(Modifier::Ctrl),
+        "shift" => Some(Modifier::Shift),
+        "alt" | "option" => Some(Modifier::Alt),
+        "cmd" | "command" | "super" | "meta" | "win" => Some(Modifier::Super),
+        "commandorcontrol" | "cmdorctrl" if cfg!(target_os = "macos") => Some(Modifier::Super),
+        "commandorcontrol" | "cmdorctrl" => Some(Modifier::Ctrl),
+        _ => None,
+    }
+}
+
+/// Parses a key name, returning `None` if the key is not supported
+fn parse_key(name: &str) -> Option<PasteKey> {
+    match name {
+        "insert" | "ins" => Some(PasteKey::Insert),
+        "middleclick" => Some(PasteKey::MiddleClick),
+        _ => {
+            let mut chars = name.chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), None) if c.is_ascii_alphabetic() => Some(PasteKey::Letter(c)),
+                _ => None,
+            }
+        }
+    }
+}
+
+impl FromStr for PasteShortcut {
+    type Err = anyhow::Error;
+
+    fn from_str(value: &str) -> Result<Self> {
+        let parts: Vec<String> = value
+            .split('+')
+            .map(|part| part.trim().to_lowercase())
+            .collect();
+        let Some((key_name, modifier_names)) = parts.split_last() else {
+            bail!("Paste shortcut is empty");
+        };
+
+        let Some(key) = parse_key(key_name) else {
+            bail!(
+                "'{}' is not a supported paste key; use a letter, Insert or MiddleClick",
+                key_name
+            );
+        };
+
+        let mut modifiers = Vec::new();
+        for name in modifier_names {
+            let Some(modifier) = parse_modifier(name) else {
+                bail!("'{}' is not a modifier key", name);
+            };
+            if modifiers.contains(&modifier) {
+                bail!("Modifier '{}' is used twice", name);
+            }
+            modifiers.push(modifier);
+        }
+
+        if key == PasteKey::MiddleClick {
+            if !modifiers.is_empty() {
+                bail!("MiddleClick cannot be combined with modifier keys");
+            }
+            if !cfg!(target_os = "linux") {
+                bail!("MiddleClick pasting is only supported on Linux");
+            }
+        } else if modifiers.is_empty() {
+            bail!("Paste shortcut '{}' needs at least one modifier", value);
+        }
+
+        Ok(Self { modifiers, key })
+    }
+}
+
+impl Default for PasteShortcut {
+    fn default() -> Self {
+        let modifier = if cfg!(target_os = "macos") {
+            Modifier::Super
+        } else {
+            Modifier::Ctrl
+        };
+        Self {
+            modifiers: vec![modifier],
+            key: PasteKey::Letter('v'),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(value: &str) -> Result<PasteShortcut> {
+        value.parse()
+    }
+
+    #[test]
+    fn test_parses_common_shortcuts() {
+        assert_eq!(
+            parse("Ctrl+V").unwrap(),
+            PasteShortcut {
+                modifiers: vec![Modifier::Ctrl],
+                key: PasteKey::Letter('v'),
+            }
+        );
+        assert_eq!(
+            parse("cmd+shift+v").unwrap(),
+            PasteShortcut {
+                modifiers: vec![Modifier::Super, Modifier::Shift],
+                key: PasteKey::Letter('v'),
+            }
+        );
+        assert_eq!(
+            parse(" Shift + Insert ").unwrap(),
+            PasteShortcut {
+                modifiers: vec![Modifier::Shift],
+                key: PasteKey::Insert,
+            }
+        );
+    }
+
+    #[test]
+    fn test_default_matches_default_string() {
+        assert_eq!(
+            parse(default_paste_shortcut()).unwrap(),
+            PasteShortcut::default()
+        );
+    }
+
+    #[test]
+    fn test_rejects_unparseable_combos() {
+        for value in [
+            "",
+            "V",
+            "Ctrl+",
+            "Ctrl+Hyper+V",
+            "Ctrl+F13",
+            "Ctrl+Ctrl+V",
+            "Ctrl+V+Shift",
+            "Shift+MiddleClick",
+        ] {
+            assert!(parse(value).is_err(), "'{}' should be rejected", value);
+        }
+    }
+
+    #[test]
+    fn test_middle_click_only_on_linux() {
+        assert_eq!(parse("MiddleClick").is_ok(), cfg!(target_os = "linux"));
+    }
+}
@@ -1,14 +1,22 @@
+use super::format::cf_html;
+use super::shortcut::{Modifier, PasteKey, PasteShortcut};
 use anyhow::{Context, Result};
-use windows::Win32::Foundation::HWND;
+use std::time::Duration;
+use windows::core::w;
+use windows::Win32::Foundation::{HGLOBAL, HWND};
 use windows::Win32::System::DataExchange::{
-    CloseClipboard, EmptyClipboard, OpenClipboard, SetClipboardData,
+    CloseClipboard, EmptyClipboard, GetClipboardData, IsClipboardFormatAvailable, OpenClipboard,
+    RegisterClipboardFormatW, SetClipboardData,
 };
 const CF_UNICODETEXT: u32 = 13;
 use windows::Win32::Foundation::HANDLE;
-use windows::Win32::System::Memory::{GlobalAlloc, GlobalLock, GlobalUnlock, GMEM_MOVEABLE};
+use windows::Win32::System::Memory::{
+    GlobalAlloc, GlobalLock, GlobalSize, GlobalUnlock, GMEM_MOVEABLE,
+};
 use windows::Win32::UI::Input::KeyboardAndMouse::{
-    SendInput, INPUT, INPUT_0, INPUT_KEYBOARD, KEYBDINPUT, KEYBD_EVENT_FLAGS, KEYEVENTF_KEYUP,
-    VIRTUAL_KEY, VK_CONTROL, VK_V,
+    SendInput, INPUT, INPUT_0, INPUT_KEYBOARD, KEYBDINPUT, KEYBD_EVENT_FLAGS,
+    KEYEVENTF_EXTENDEDKEY, KEYEVENTF_KEYUP, KEYEVENTF_UNICODE, VIRTUAL_KEY, VK_CONTROL, VK_INSERT,
+    VK_LWIN, VK_MENU, VK_RETURN, VK_SHIFT,
 };
 
 // Removed as it's now imported from windows::Win32::System::DataExchange
@@ -17,74 +25,323 @@ use windows::Win32::UI::Input::KeyboardAndMouse::{
 ///
 /// # Arguments
 /// * `text` - The text to paste
+/// * `shortcut` - Key combination to simulate after setting the clipboard
+/// * `delay` - Time to wait between setting the clipboard and simulating the shortcut
 ///
 /// # Returns
 /// * `Ok(())` if the text was pasted successfully
 /// * `Err` if the clipboard operation or SendInput failed
-pub fn paste_text(text: &str) -> Result<()> {
+pub fn paste_text(text: &str, shortcut: &PasteShortcut, delay: Duration) -> Result<()> {
     log::info!("Pasting text on Windows using Win32 API");
 
-    unsafe {
-        // Set clipboard data
-        set_clipboard_text(text)?;
+    copy_to_clipboard(text)?;
+    super::wait_before_paste(delay);
+    simulate_paste(shortcut)?;
+
+    log::info!("Text pasted successfully on Windows");
+    Ok(())
+}
+
+/// Copies text to the Windows clipboard as `CF_UNICODETEXT`
+///
+/// # Arguments
+/// * `text` - The text to copy
+///
+/// # Returns
+/// * `Ok(())` if the text was copied
+/// * `Err` if the clipboard operation failed
+pub fn copy_to_clipboard(text: &str) -> Result<()> {
+    unsafe { set_clipboard_text(text) }
+}
+
+/// Copies an HTML fragment to the Windows clipboard as `CF_HTML`
+///
+/// # Arguments
+/// * `html` - The HTML fragment to copy
+///
+/// # Returns
+/// * `Ok(())` if the HTML was copied
+/// * `Err` if the clipboard operation failed
+pub fn copy_html(html: &str) -> Result<()> {
+    unsafe { set_clipboard_html(html) }
+}
+
+/// Reads text from the Windows clipboard as `CF_UNICODETEXT`
+///
+/// # Returns
+/// * `Ok(String)` with the clipboard text, empty if the clipboard holds no text
+/// * `Err` if the clipboard could not be read
+pub fn read_clipboard() -> Result<String> {
+    unsafe { read_clipboard_text(HWND(std::ptr::null_mut())) }
+}
+
+/// Simulates the paste shortcut (Ctrl+V by default) in the active application
+fn simulate_paste(shortcut: &PasteShortcut) -> Result<()> {
+    unsafe { simulate_paste_shortcut(shortcut) }
+}
 
-        // Simulate Ctrl+V key press
-        simulate_paste_shortcut()?;
+/// Pastes HTML on Windows by setting the clipboard in the `CF_HTML` format
+///
+/// # Arguments
+/// * `html` - The HTML fragment to paste
+/// * `shortcut` - Key combination to simulate after setting the clipboard
+/// * `delay` - Time to wait between setting the clipboard and simulating the shortcut
+///
+/// # Returns
+/// * `Ok(())` if the HTML was pasted successfully
+/// * `Err` if the clipboard operation or SendInput failed
+pub fn paste_html(html: &str, shortcut: &PasteShortcut, delay: Duration) -> Result<()> {
+    log::info!("Pasting HTML on Windows using Win32 API");
+
+    copy_html(html)?;
+    super::wait_before_paste(delay);
+    simulate_paste(shortcut)?;
+
+    log::info!("HTML pasted successfully on Windows");
+    Ok(())
+}
+
+/// Types text on Windows by sending Unicode keyboard events with SendInput
+///
+/// Every UTF-16 code unit is sent as a `KEYEVENTF_UNICODE` event, so characters
+/// outside the keyboard layout work too; newlines are sent as the Return key.
+/// Slower than pasting for long text, but works in apps that ignore Ctrl+V.
+///
+/// # Arguments
+/// * `text` - The text to type
+///
+/// # Returns
+/// * `Ok(())` if the text was typed successfully
+/// * `Err` if SendInput did not accept all events
+pub fn type_text(text: &str) -> Result<()> {
+    log::info!("Typing text on Windows using SendInput");
+
+    let mut inputs = Vec::new();
+    for unit in typing_units(text) {
+        unsafe {
+            match unit {
+                TypingUnit::Return => {
+                    inputs.push(create_keyboard_input(VK_RETURN, false));
+                    inputs.push(create_keyboard_input(VK_RETURN, true));
+                }
+                TypingUnit::Utf16(code) => {
+                    inputs.push(create_unicode_input(code, false));
+                    inputs.push(create_unicode_input(code, true));
+                }
+            }
+        }
     }
 
-    log::info!("Text pasted successfully on Windows");
+    if inputs.is_empty() {
+        return Ok(());
+    }
+
+    let sent = unsafe { SendInput(&inputs, std::mem::size_of::<INPUT>() as i32) };
+    if sent as usize != inputs.len() {
+        return Err(anyhow::anyhow!(
+            "Failed to send input events ({} of {} sent)",
+            sent,
+            inputs.len()
+        ));
+    }
+
+    log::info!("Text typed successfully on Windows");
     Ok(())
 }
 
+/// A single key to send while typing
+enum TypingUnit {
+    /// The Return key, sent for newlines
+    Return,
+    /// A UTF-16 code unit sent as a Unicode event
+    Utf16(u16),
+}
+
+/// Splits text into the keys to send; `\r\n` and `\n` both become one Return
+fn typing_units(text: &str) -> Vec<TypingUnit> {
+    let mut units = Vec::new();
+    let mut buffer = [0u16; 2];
+    for c in text.chars() {
+        match c {
+            '\r' => {}
+            '\n' => units.push(TypingUnit::Return),
+            c => units.extend(
+                c.encode_utf16(&mut buffer)
+                    .iter()
+                    .map(|&u| TypingUnit::Utf16(u)),
+            ),
+        }
+    }
+    units
+}
+
+/// Creates a keyboard input that types a UTF-16 code unit
+unsafe fn create_unicode_input(code: u16, key_up: bool) -> INPUT {
+    INPUT {
+        r#type: INPUT_KEYBOARD,
+        Anonymous: INPUT_0 {
+            ki: KEYBDINPUT {
+                wVk: VIRTUAL_KEY(0),
+                wScan: code,
+                dwFlags: if key_up {
+                    KEYEVENTF_UNICODE | KEYEVENTF_KEYUP
+                } else {
+                    KEYEVENTF_UNICODE
+                },
+                time: 0,
+                dwExtraInfo: 0,
+            },
+        },
+    }
+}
+
 /// Sets text to the Windows clipboard
 unsafe fn set_clipboard_text(text: &str) -> Result<()> {
+    // Convert text to NUL-terminated UTF-16
+    let wide: Vec<u8> = text
+        .encode_utf16()
+        .chain(std::iter::once(0))
+        .flat_map(u16::to_ne_bytes)
+        .collect();
+
+    set_clipboard_data(CF_UNICODETEXT, &wide)
+}
+
+/// Sets an HTML fragment to the Windows clipboard in the `CF_HTML` format
+unsafe fn set_clipboard_html(html: &str) -> Result<()> {
+    let format = RegisterClipboardFormatW(w!("HTML Format"));
+    if format == 0 {
+        return Err(anyhow::anyhow!(
+            "Failed to register the HTML clipboard format"
+        ));
+    }
+
+    // CF_HTML is NUL-terminated UTF-8
+    let mut payload = cf_html(html).into_bytes();
+    payload.push(0);
+
+    set_clipboard_data(format, &payload)
+}
+
+/// Reads `CF_UNICODETEXT` from the clipboard, opening it on behalf of `owner`
+unsafe fn read_clipboard_text(owner: HWND) -> Result<String> {
+    OpenClipboard(owner).context("Failed to open clipboard")?;
+    let text = clipboard_unicode_text();
+    // Close even if reading failed, or other apps can't use the clipboard
+    let _ = CloseClipboard();
+    text
+}
+
+/// Copies the `CF_UNICODETEXT` data out of the open clipboard
+unsafe fn clipboard_unicode_text() -> Result<String> {
+    if IsClipboardFormatAvailable(CF_UNICODETEXT).is_err() {
+        return Ok(String::new());
+    }
+
+    let handle = GetClipboardData(CF_UNICODETEXT).context("Failed to get clipboard data")?;
+    let hglob = HGLOBAL(handle.0);
+    let locked = GlobalLock(hglob) as *const u16;
+    if locked.is_null() {
+        return Err(anyhow::anyhow!("Failed to lock clipboard memory"));
+    }
+
+    let text = utf16_until_nul(locked, GlobalSize(hglob) / std::mem::size_of::<u16>());
+    let _ = GlobalUnlock(hglob);
+    text
+}
+
+/// Decodes NUL-terminated UTF-16 text
+///
+/// # Safety
+/// `data` must point to at least `max_units` readable UTF-16 code units. The text
+/// ends at the first NUL, or after `max_units` units if there is none.
+unsafe fn utf16_until_nul(data: *const u16, max_units: usize) -> Result<String> {
+    let units = std::slice::from_raw_parts(data, max_units);
+    let len = units
+        .iter()
+        .position(|&unit| unit == 0)
+        .unwrap_or(units.len());
+    String::from_utf16(&units[..len]).context("The clipboard does not hold valid UTF-16 text")
+}
+
+/// Replaces the Windows clipboard contents with data in the given format
+unsafe fn set_clipboard_data(format: u32, data: &[u8]) -> Result<()> {
     // Open clipboard
     OpenClipboard(HWND(std::ptr::null_mut())).context("Failed to open clipboard")?;
 
     // Empty clipboard
     EmptyClipboard().context("Failed to empty clipboard")?;
 
-    // Convert text to UTF-16
-    let wide: Vec<u16> = text.encode_utf16().chain(std::iter::once(0)).collect();
-    let size = wide.len() * std::mem::size_of::<u16>();
-
     // Allocate global memory
-    let hglob = GlobalAlloc(GMEM_MOVEABLE, size).context("Failed to allocate global memory")?;
+    let hglob =
+        GlobalAlloc(GMEM_MOVEABLE, data.len()).context("Failed to allocate global memory")?;
 
-    // Lock memory and copy text
+    // Lock memory and copy data
     let locked = GlobalLock(hglob);
     if locked.is_null() {
         let _ = CloseClipboard();
         return Err(anyhow::anyhow!("Failed to lock global memory"));
     }
 
-    std::ptr::copy_nonoverlapping(wide.as_ptr(), locked as *mut u16, wide.len());
+    std::ptr::copy_nonoverlapping(data.as_ptr(), locked as *mut u8, data.len());
     let _ = GlobalUnlock(hglob);
 
     // Set clipboard data - convert HGLOBAL to HANDLE
-    SetClipboardData(CF_UNICODETEXT, HANDLE(hglob.0)).context("Failed to set clipboard data")?;
+    SetClipboardData(format, HANDLE(hglob.0)).context("Failed to set clipboard data")?;
 
     CloseClipboard().context("Failed to close clipboard")?;
 
     Ok(())
 }
 
-/// Simulates Ctrl+V key press using SendInput
-unsafe fn simulate_paste_shortcut() -> Result<()> {
-    let mut inputs: [INPUT; 4] = std::mem::zeroed();
+/// Virtual key of a modifier
+fn modifier_key(modifier: Modifier) -> VIRTUAL_KEY {
+    match modifier {
+        Modifier::Ctrl => VK_CONTROL,
+        Modifier::Shift => VK_SHIFT,
+        Modifier::Alt => VK_MENU,
+        Modifier::Super => VK_LWIN,
+    }
+}
+
+/// Virtual key of the paste key, and whether it is an extended key
+fn paste_key(key: PasteKey) -> Result<(VIRTUAL_KEY, bool)> {
+    match key {
+        // The virtual key codes of letters are their uppercase ASCII codes
+        PasteKey::Letter(c) => Ok((VIRTUAL_KEY(c.to_ascii_uppercase() as u16), false)),
+        // Without the extended flag, Insert would be sent as numpad 0
+        PasteKey::Insert => Ok((VK_INSERT, true)),
+        PasteKey::MiddleClick => Err(anyhow::anyhow!(
+            "MiddleClick pasting is not supported on Windows"
+        )),
+    }
+}
+
+/// Simulates the paste shortcut using SendInput
+///
+/// Modifiers are pressed in order, then the key is pressed and released,
+/// then the modifiers are released in reverse order.
+unsafe fn simulate_paste_shortcut(shortcut: &PasteShortcut) -> Result<()> {
+    let (key, extended) = paste_key(shortcut.key)?;
 
-    // Ctrl down
-    inputs[0] = create_keyboard_input(VK_CONTROL, false);
-    // V down
-    inputs[1] = create_keyboard_input(VK_V, false);
-    // V up
-    inputs[2] = create_keyboard_input(VK_V, true);
-    // Ctrl up
-    inputs[3] = create_keyboard_input(VK_CONTROL, true);
+    let mut inputs = Vec::with_capacity(shortcut.modifiers.len() * 2 + 2);
+    for &modifier in &shortcut.modifiers {
+        inputs.push(create_keyboard_input(modifier_key(modifier), false));
+    }
+    for key_up in [false, true] {
+        let mut input = create_keyboard_input(key, key_up);
+        if extended {
+            input.Anonymous.ki.dwFlags |= KEYEVENTF_EXTENDEDKEY;
+        }
+        inputs.push(input);
+    }
+    for &modifier in shortcut.modifiers.iter().rev() {
+        inputs.push(create_keyboard_input(modifier_key(modifier), true));
+    }
 
     let result = SendInput(&inputs, std::mem::size_of::<INPUT>() as i32);
 
-    if result != 4 {
+    if result as usize != inputs.len() {
         return Err(anyhow::anyhow!("Failed to send input events"));
     }
 
@@ -110,3 +367,57 @@ unsafe fn create_keyboard_input(vk: VIRTUAL_KEY, key_up: bool) -> INPUT {
         },
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn utf16(text: &str) -> Vec<u16> {
+        text.encode_utf16().chain(std::iter::once(0)).collect()
+    }
+
+    #[test]
+    fn test_utf16_until_nul_round_trips() {
+        let text = "Grüße, 日本語 👋\r\nzweite Zeile";
+        let data = utf16(text);
+
+        let read = unsafe { utf16_until_nul(data.as_ptr(), data.len()) }.unwrap();
+        assert_eq!(read, text);
+    }
+
+    #[test]
+    fn test_utf16_until_nul_stays_within_bounds() {
+        // Clipboard memory can be larger than the text, or lack the terminator
+        let mut data = utf16("abc");
+        data.extend(utf16("garbage"));
+        assert_eq!(
+            unsafe { utf16_until_nul(data.as_ptr(), data.len()) }.unwrap(),
+            "abc"
+        );
+
+        let unterminated: Vec<u16> = "abcdef".encode_utf16().collect();
+        assert_eq!(
+            unsafe { utf16_until_nul(unterminated.as_ptr(), 3) }.unwrap(),
+            "abc"
+        );
+    }
+
+    #[test]
+    fn test_utf16_until_nul_rejects_unpaired_surrogates() {
+        let data = [0xD800u16, 0x0041, 0];
+        assert!(unsafe { utf16_until_nul(data.as_ptr(), data.len()) }.is_err());
+    }
+
+    #[test]
+    fn test_read_clipboard_with_invalid_owner_window_fails() {
+        // A window handle that doesn't belong to any window
+        let owner = HWND(0xDEAD_BEEF_usize as *mut _);
+
+        let err = unsafe { read_clipboard_text(owner) }.unwrap_err();
+        assert!(
+            err.to_string().contains("Failed to open clipboard"),
+            "{}",
+            err
+        );
+    }
+}
@@ -1,15 +1,341 @@
 use crate::clipboard;
+use crate::clipboard::format::format_paste_text;
+use crate::clipboard::shortcut::PasteShortcut;
+use crate::commands::settings::{current_settings, OutputMode, PasteFormat, Settings};
+use crate::postprocess::command::{
+    expand_placeholders, run_postprocess_command, MAX_POSTPROCESS_OUTPUT_BYTES,
+};
+use crate::postprocess::delivery_text;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
 
-/// Pastes text to the active application
+/// Event emitted with the missing tools when pasting fails because they are not installed
+#[cfg(target_os = "linux")]
+const PASTE_TOOLS_MISSING_EVENT: &str = "paste-tools-missing";
+
+/// Emits `paste-tools-missing` if pasting failed because required tools are
+/// not installed, so the frontend can tell the user what to install
+#[cfg(target_os = "linux")]
+fn report_missing_paste_tools(app: &AppHandle, error: &anyhow::Error) {
+    if let Some(missing) = error.downcast_ref::<clipboard::MissingPasteTools>() {
+        let _ = app.emit(PASTE_TOOLS_MISSING_EVENT, missing);
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+fn report_missing_paste_tools(_app: &AppHandle, _error: &anyhow::Error) {}
+
+/// How `paste_text` delivers text to the active application
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Delivery {
+    /// Copy to the clipboard and simulate the paste shortcut
+    Paste,
+    /// Simulate typing every character
+    Type,
+    /// Only copy to the clipboard, for the user to review and paste by hand
+    CopyOnly,
+}
+
+/// Where text handed to `deliver_text` comes from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TextOrigin {
+    /// Fresh dictation output; the split phrase is applied to it
+    Dictation,
+    /// Text delivered again as stored, e.g. a history entry, which was split
+    /// when it was saved
+    History,
+}
+
+/// Applies the split phrase to fresh dictation output; stored text is kept as is
+fn split_for_delivery(text: String, origin: TextOrigin, settings: &Settings) -> String {
+    match origin {
+        TextOrigin::Dictation => delivery_text(&text, settings),
+        TextOrigin::History => text,
+    }
+}
+
+/// Picks how text is delivered from the output mode and the auto-paste setting
+fn choose_delivery(output_mode: OutputMode, auto_paste: bool) -> Delivery {
+    match (auto_paste, output_mode) {
+        (false, _) => Delivery::CopyOnly,
+        (true, OutputMode::Paste) => Delivery::Paste,
+        (true, OutputMode::Type) => Delivery::Type,
+    }
+}
+
+/// Language substituted for `{language}`: the detected language of the text,
+/// or the configured one (possibly "auto") if it isn't known
+fn placeholder_language<'a>(detected: Option<&'a str>, settings: &'a Settings) -> &'a str {
+    detected
+        .filter(|language| !language.is_empty())
+        .unwrap_or(&settings.language)
+}
+
+/// Pipes text through the post-processing command from settings, if one is set
+///
+/// Falls back to the original text if the command fails, logging a warning
+/// and emitting a `postprocess-command-failed` event.
+async fn apply_postprocess_command(
+    app: &AppHandle,
+    text: String,
+    language: Option<&str>,
+    settings: &Settings,
+) -> String {
+    let Some(command) = settings
+        .postprocess_command
+        .as_deref()
+        .map(str::trim)
+        .filter(|command| !command.is_empty())
+    else {
+        return text;
+    };
+
+    let command = expand_placeholders(
+        command,
+        placeholder_language(language, settings),
+        &settings.model,
+    );
+    let timeout = Duration::from_millis(settings.postprocess_timeout_ms);
+    let input = text.clone();
+
+    let result = tokio::task::spawn_blocking(move || {
+        run_postprocess_command(&command, &input, timeout, MAX_POSTPROCESS_OUTPUT_BYTES)
+    })
+    .await
+    .map_err(|e| anyhow::anyhow!("Post-processing task failed: {}", e))
+    .and_then(|result| result);
+
+    match result {
+        Ok(processed) => processed,
+        Err(e) => {
+            log::warn!(
+                "Post-processing command failed, pasting original text: {:#}",
+                e
+            );
+            let _ = app.emit(
+                "postprocess-command-failed",
+                serde_json::json!({ "message": format!("{:#}", e) }),
+            );
+            text
+        }
+    }
+}
+
+/// Pastes freshly transcribed text to the active application
+///
+/// If a split phrase is configured, the split parts are pasted joined with the
+/// configured separator, or only the first part, depending on the settings.
+/// Nothing is pasted if no text is left, e.g. when only the split phrase was
+/// spoken. If a post-processing command is configured, the text is piped
+/// through it first.
+///
+/// With the `type` output mode the text is typed as keystrokes instead of
+/// pasted; otherwise the text is formatted with the configured paste format and
+/// the configured paste shortcut is simulated after the configured paste delay.
+/// With auto-paste turned off, the formatted text is only copied to the
+/// clipboard. On Linux, a `paste-tools-missing` event lists the tools to
+/// install if any are missing.
 ///
 /// # Arguments
+/// * `app` - Application handle used to report post-processing failures
 /// * `text` - The text to paste at the current cursor position
+/// * `language` - Detected language of the transcription, substituted for
+///   `{language}` in the post-processing command instead of the configured one
 ///
 /// # Returns
 /// * `Ok(())` if the text was pasted successfully
 /// * `Err(String)` if pasting failed
 #[tauri::command]
-pub fn paste_text(text: String) -> Result<(), String> {
-    log::info!("Pasting text: {}...", &text[..text.len().min(50)]);
-    clipboard::paste_text(&text).map_err(|e| format!("Failed to paste text: {}", e))
+pub async fn paste_text(
+    app: AppHandle,
+    text: String,
+    language: Option<String>,
+) -> Result<(), String> {
+    deliver_text(&app, text, TextOrigin::Dictation, language.as_deref()).await
+}
+
+/// Pastes, types or copies text as configured; the body of `paste_text`,
+/// shared with the dictation pipeline and history re-pastes
+///
+/// The split phrase only applies to dictation output. Text that ends up empty
+/// after processing is not delivered. `language` is the detected language of
+/// the text, if known.
+pub async fn deliver_text(
+    app: &AppHandle,
+    text: String,
+    origin: TextOrigin,
+    language: Option<&str>,
+) -> Result<(), String> {
+    let (text, delivery, shortcut, format, delay) = match current_settings() {
+        Ok(settings) => {
+            let text = split_for_delivery(text, origin, &settings);
+            let text = apply_postprocess_command(app, text, language, &settings).await;
+            // Saved settings are validated, but a hand-edited file may still be broken
+            let shortcut = settings.paste_shortcut.parse().unwrap_or_else(|e| {
+                log::warn!("Using the default paste shortcut: {}", e);
+                PasteShortcut::default()
+            });
+            (
+                text,
+                choose_delivery(settings.output_mode, settings.auto_paste),
+                shortcut,
+                settings.paste_format,
+                Duration::from_millis(settings.paste_delay_ms),
+            )
+        }
+        Err(e) => {
+            log::warn!("Pasting text unchanged, failed to load settings: {}", e);
+            (
+                text,
+                choose_delivery(OutputMode::default(), true),
+                PasteShortcut::default(),
+                PasteFormat::default(),
+                Duration::ZERO,
+            )
+        }
+    };
+
+    if text.trim().is_empty() {
+        log::info!("No text left to deliver after processing, skipping");
+        return Ok(());
+    }
+
+    match delivery {
+        Delivery::Paste => {
+            log::info!(
+                "Pasting text: {}...",
+                text.chars().take(50).collect::<String>()
+            );
+            let text = format_paste_text(&text, format);
+            // The paste delay blocks, so keep it off the async runtime
+            let result = tokio::task::spawn_blocking(move || match format {
+                PasteFormat::Html => clipboard::paste_html(&text, &shortcut, delay),
+                _ => clipboard::paste_text(&text, &shortcut, delay),
+            })
+            .await
+            .map_err(|e| format!("Failed to paste text: {}", e))?;
+            result.map_err(|e| {
+                report_missing_paste_tools(app, &e);
+                format!("Failed to paste text: {}", e)
+            })
+        }
+        Delivery::CopyOnly => {
+            log::info!("Auto-paste is off, copying text to the clipboard");
+            let text = format_paste_text(&text, format);
+            let result = match format {
+                PasteFormat::Html => clipboard::copy_html(&text),
+                _ => clipboard::copy_to_clipboard(&text),
+            };
+            result.map_err(|e| {
+                report_missing_paste_tools(app, &e);
+                format!("Failed to copy text: {}", e)
+            })
+        }
+        Delivery::Type => {
+            log::info!("Typing {} characters", text.chars().count());
+            // Simulated keystrokes block until the last key is sent
+            tokio::task::spawn_blocking(move || clipboard::type_text(&text))
+                .await
+                .map_err(|e| format!("Failed to type text: {}", e))?
+                .map_err(|e| format!("Failed to type text: {}", e))
+        }
+    }
+}
+
+/// Copies text to the clipboard without pasting it
+///
+/// # Arguments
+/// * `app` - Application handle used to report missing clipboard tools
+/// * `text` - The text to copy, unchanged
+///
+/// # Returns
+/// * `Ok(())` if the text was copied
+/// * `Err(String)` if copying failed
+#[tauri::command]
+pub fn copy_to_clipboard(app: AppHandle, text: String) -> Result<(), String> {
+    clipboard::copy_to_clipboard(&text).map_err(|e| {
+        report_missing_paste_tools(&app, &e);
+        format!("Failed to copy text: {}", e)
+    })
+}
+
+/// Reads text from the clipboard
+///
+/// # Returns
+/// * `Ok(String)` with the clipboard text
+/// * `Err(String)` if the clipboard could not be read
+#[tauri::command]
+pub async fn read_clipboard() -> Result<String, String> {
+    // The command-line readers block until the clipboard owner answers
+    tokio::task::spawn_blocking(clipboard::read_clipboard)
+        .await
+        .map_err(|e| format!("Failed to read clipboard: {}", e))?
+        .map_err(|e| format!("Failed to read clipboard: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::settings::SplitDelivery;
+
+    #[test]
+    fn test_auto_paste_pastes_or_types_per_output_mode() {
+        assert_eq!(choose_delivery(OutputMode::Paste, true), Delivery::Paste);
+        assert_eq!(choose_delivery(OutputMode::Type, true), Delivery::Type);
+    }
+
+    #[test]
+    fn test_without_auto_paste_text_is_only_copied() {
+        for mode in OutputMode::ALL {
+            assert_eq!(choose_delivery(mode, false), Delivery::CopyOnly);
+        }
+    }
+
+    #[test]
+    fn test_split_phrase_only_applies_to_dictation() {
+        let settings = Settings {
+            split_phrase: Some("next note".to_string()),
+            split_delivery: SplitDelivery::FirstOnly,
+            ..Settings::default()
+        };
+        let text = "Buy milk next note call mom".to_string();
+
+        assert_eq!(
+            split_for_delivery(text.clone(), TextOrigin::Dictation, &settings),
+            "Buy milk"
+        );
+        assert_eq!(
+            split_for_delivery(text.clone(), TextOrigin::History, &settings),
+            text
+        );
+        // Only the phrase was spoken
+        assert_eq!(
+            split_for_delivery("Next note.".to_string(), TextOrigin::Dictation, &settings),
+            ""
+        );
+    }
+
+    #[test]
+    fn test_placeholder_language_prefers_detected_language() {
+        let settings = Settings {
+            language: "auto".to_string(),
+            ..Settings::default()
+        };
+
+        assert_eq!(placeholder_language(Some("de"), &settings), "de");
+        assert_eq!(placeholder_language(None, &settings), "auto");
+        assert_eq!(placeholder_language(Some(""), &settings), "auto");
+    }
+
+    #[test]
+    fn test_auto_paste_defaults_to_on() {
+        assert!(Settings::default().auto_paste);
+        assert_eq!(
+            choose_delivery(
+                Settings::default().output_mode,
+                Settings::default().auto_paste
+            ),
+            Delivery::Paste
+        );
+    }
 }
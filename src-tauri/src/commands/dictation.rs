@@ -0,0 +1,323 @@
+use crate::clipboard;
+use crate::commands::clipboard::{deliver_text, TextOrigin};
+use crate::commands::history::save_transcription;
+use crate::commands::recording::stop_and_save_recording;
+use crate::commands::settings::get_settings;
+use crate::commands::transcription::run_transcription;
+use crate::AppState;
+use serde::Serialize;
+use std::path::Path;
+use std::sync::Arc;
+use tauri::{AppHandle, Emitter, State};
+
+/// Event emitted when dictated text could not be pasted
+const PASTE_ERROR_EVENT: &str = "paste-error";
+
+/// Copies text to the clipboard; stubbed in tests
+pub trait ClipboardWriter {
+    /// Copies text to the clipboard, replacing its contents
+    fn copy(&self, text: &str) -> anyhow::Result<()>;
+}
+
+/// The system clipboard
+struct SystemClipboard;
+
+impl ClipboardWriter for SystemClipboard {
+    fn copy(&self, text: &str) -> anyhow::Result<()> {
+        clipboard::copy_to_clipboard(text)
+    }
+}
+
+/// Payload of the `paste-error` event
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PasteError {
+    /// Why pasting failed, e.g. a missing tool or a denied permission
+    pub message: String,
+    /// True if the text was left on the clipboard for pasting by hand
+    pub on_clipboard: bool,
+}
+
+/// Puts text that could not be pasted on the clipboard so it isn't lost
+///
+/// Pasting may fail before or after the text reached the clipboard, or the
+/// text may have been typed instead, so it is always copied again.
+///
+/// # Arguments
+/// * `clipboard` - Clipboard to copy the text to
+/// * `text` - Text that could not be pasted
+/// * `message` - Error from pasting
+///
+/// # Returns
+/// The `paste-error` payload, telling whether the text is on the clipboard
+fn recover_failed_paste(
+    clipboard: &dyn ClipboardWriter,
+    text: &str,
+    message: String,
+) -> PasteError {
+    let on_clipboard = match clipboard.copy(text) {
+        Ok(()) => true,
+        Err(e) => {
+            log::warn!("Failed to copy the unpasted text to the clipboard: {:#}", e);
+            false
+        }
+    };
+    PasteError {
+        message,
+        on_clipboard,
+    }
+}
+
+/// Outcome of a dictation whose transcription succeeded
+///
+/// Pasting and saving to history can fail independently of each other; their
+/// errors are reported here instead of failing the whole dictation, since the
+/// text itself is not lost.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DictationResult {
+    /// Final transcribed text
+    pub text: String,
+    /// ID of the history record, `None` if nothing was stored
+    pub history_id: Option<String>,
+    /// Why the text could not be pasted (or copied), `None` if delivery succeeded or was skipped
+    pub paste_error: Option<String>,
+    /// Why the text could not be saved to history, `None` if it was saved or skipped
+    pub history_error: Option<String>,
+}
+
+impl DictationResult {
+    /// Collects the outcomes of the steps after transcription
+    ///
+    /// # Arguments
+    /// * `text` - Transcribed text
+    /// * `history` - ID of the stored record if one was stored, or the error from storing it
+    /// * `delivery` - Result of pasting the text
+    fn new(
+        text: String,
+        history: Result<Option<String>, String>,
+        delivery: Result<(), String>,
+    ) -> Self {
+        let (history_id, history_error) = match history {
+            Ok(id) => (id, None),
+            Err(e) => (None, Some(e)),
+        };
+        Self {
+            text,
+            history_id,
+            paste_error: delivery.err(),
+            history_error,
+        }
+    }
+
+    /// Result for a recording without speech: nothing to paste or store
+    fn empty() -> Self {
+        Self {
+            text: String::new(),
+            history_id: None,
+            paste_error: None,
+            history_error: None,
+        }
+    }
+}
+
+/// Stops the recording and runs the rest of the dictation in the backend:
+/// transcription with the configured model, pasting, and saving to history
+///
+/// Replaces the `stop_recording` → `transcribe_audio` → `paste_text` →
+/// `add_history` round trips through the frontend, so dictation finishes even
+/// while the window is hidden or busy. The usual `recording-status`,
+/// `processing-status` and `transcription-complete` events are emitted along
+/// the way. Right after `transcription-complete`, the text is delivered like
+/// `paste_text` does, so with auto-paste turned off it is only copied to the
+/// clipboard. If delivery fails, the text is put on the clipboard and a
+/// `paste-error` event is emitted. The text is saved to history unless
+/// `save_history` is off. The recording is removed from the temp directory
+/// afterwards, unless the transcription failed or was cancelled, so it can be
+/// retried.
+///
+/// # Arguments
+/// * `app` - Application handle used to emit status events
+/// * `state` - Application state holding the recording
+///
+/// # Returns
+/// * `Ok(DictationResult)` with the text and any paste or history error
+/// * `Err(String)` if recording or transcription failed, or `"cancelled"`
+///   if the transcription was cancelled
+#[tauri::command]
+pub async fn finish_dictation(
+    app: AppHandle,
+    state: State<'_, Arc<AppState>>,
+) -> Result<DictationResult, String> {
+    let audio_path = stop_and_save_recording(&app, &state)?;
+
+    let settings = get_settings()
+        .await
+        .map_err(|e| format!("Failed to get settings: {}", e))?;
+    let transcription = run_transcription(
+        app.clone(),
+        audio_path.clone(),
+        settings.model.clone(),
+        None,
+        &state,
+    )
+    .await;
+    // A failed or cancelled transcription keeps the recording so it can be retried
+    let result = transcription?;
+
+    if result.text.trim().is_empty() {
+        log::info!("Dictation produced no text, nothing to paste or store");
+        remove_recording(Path::new(&audio_path));
+        return Ok(DictationResult::empty());
+    }
+
+    let delivery = deliver_text(
+        &app,
+        result.text.clone(),
+        TextOrigin::Dictation,
+        result.language.as_deref(),
+    )
+    .await;
+    if let Err(e) = &delivery {
+        log::warn!(
+            "Dictation transcribed but delivering the text failed: {}",
+            e
+        );
+        let error = recover_failed_paste(&SystemClipboard, &result.text, e.clone());
+        let _ = app.emit(PASTE_ERROR_EVENT, &error);
+    }
+
+    let history = save_transcription(&result, &settings);
+    if let Err(e) = &history {
+        log::warn!("Dictation transcribed but saving to history failed: {}", e);
+    }
+    remove_recording(Path::new(&audio_path));
+
+    Ok(DictationResult::new(result.text, history, delivery))
+}
+
+/// Deletes a recording from the temp directory, logging failures
+fn remove_recording(path: &Path) {
+    if let Err(e) = std::fs::remove_file(path) {
+        log::warn!("Failed to remove recording {:?}: {}", path, e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+
+    /// Clipboard remembering what was copied, or failing every copy
+    #[derive(Default)]
+    struct FakeClipboard {
+        copied: RefCell<Vec<String>>,
+        fail: bool,
+    }
+
+    impl ClipboardWriter for FakeClipboard {
+        fn copy(&self, text: &str) -> anyhow::Result<()> {
+            if self.fail {
+                anyhow::bail!("wl-copy not found");
+            }
+            self.copied.borrow_mut().push(text.to_string());
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_failed_paste_leaves_text_on_clipboard() {
+        let clipboard = FakeClipboard::default();
+
+        let error = recover_failed_paste(
+            &clipboard,
+            "Hello world",
+            "Failed to paste text: xdotool not found".to_string(),
+        );
+
+        assert_eq!(
+            error,
+            PasteError {
+                message: "Failed to paste text: xdotool not found".to_string(),
+                on_clipboard: true,
+            }
+        );
+        assert_eq!(*clipboard.copied.borrow(), vec!["Hello world"]);
+    }
+
+    #[test]
+    fn test_failed_paste_reports_when_clipboard_fails_too() {
+        let clipboard = FakeClipboard {
+            fail: true,
+            ..Default::default()
+        };
+
+        let error = recover_failed_paste(&clipboard, "Hello world", "denied".to_string());
+
+        assert!(!error.on_clipboard);
+        assert_eq!(
+            serde_json::to_value(&error).unwrap(),
+            serde_json::json!({ "message": "denied", "onClipboard": false })
+        );
+    }
+
+    #[test]
+    fn test_result_reports_paste_failure_with_text() {
+        let result = DictationResult::new(
+            "Hello world".to_string(),
+            Ok(Some("record-1".to_string())),
+            Err("Failed to paste text: xdotool not found".to_string()),
+        );
+
+        assert_eq!(result.text, "Hello world");
+        assert_eq!(result.history_id.as_deref(), Some("record-1"));
+        assert_eq!(
+            result.paste_error.as_deref(),
+            Some("Failed to paste text: xdotool not found")
+        );
+        assert_eq!(result.history_error, None);
+    }
+
+    #[test]
+    fn test_result_reports_history_failure() {
+        let result = DictationResult::new(
+            "Hello world".to_string(),
+            Err("Failed to write history file".to_string()),
+            Ok(()),
+        );
+
+        assert_eq!(result.history_id, None);
+        assert_eq!(
+            result.history_error.as_deref(),
+            Some("Failed to write history file")
+        );
+        assert_eq!(result.paste_error, None);
+    }
+
+    #[test]
+    fn test_result_serializes_camel_case() {
+        let json = serde_json::to_value(DictationResult::empty()).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "text": "",
+                "historyId": null,
+                "pasteError": null,
+                "historyError": null,
+            })
+        );
+    }
+
+    #[test]
+    fn test_remove_recording_deletes_file() {
+        let path =
+            std::env::temp_dir().join(format!("rustler_dictation_{}.wav", std::process::id()));
+        std::fs::write(&path, b"RIFF").unwrap();
+
+        remove_recording(&path);
+        assert!(!path.exists());
+
+        // Already gone: only logged
+        remove_recording(&path);
+    }
+}
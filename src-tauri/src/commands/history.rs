@@ -1,6 +1,70 @@
+use crate::commands::clipboard::{copy_to_clipboard, deliver_text, TextOrigin};
+use crate::commands::settings::{current_settings, Settings};
+use crate::postprocess::split_transcription;
+use crate::storage::{no_migration, JsonStore};
+use crate::whisper::result::{overall_confidence, Segment, TranscriptionResult};
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
 use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fs;
-use std::path::PathBuf;
+use std::io::{BufWriter, Write};
+use std::ops::Bound;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Emitter};
+
+/// Schema version of the history file
+const HISTORY_VERSION: u32 = 1;
+
+/// Maximum number of segments stored per record, bounding history file growth
+const MAX_STORED_SEGMENTS: usize = 2000;
+
+/// Default number of records kept in history
+pub const DEFAULT_HISTORY_MAX_ENTRIES: usize = 100;
+
+/// Upper bound of the configurable history size
+///
+/// The whole history is one JSON file that is rewritten on every change, so
+/// far more records would make each save noticeably slow. Users who want to
+/// keep everything can set the size to 0 (unlimited) instead.
+pub const MAX_HISTORY_MAX_ENTRIES: usize = 10_000;
+
+/// Event emitted when the history holds more records than a newly lowered limit
+const HISTORY_TRUNCATED_EVENT: &str = "history-truncated";
+
+/// A timed piece of a transcription, stored so it can be exported later
+/// without re-transcribing the audio
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HistorySegment {
+    pub start_ms: i64,
+    pub end_ms: i64,
+    pub text: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub confidence: Option<f32>,
+}
+
+impl From<&HistorySegment> for Segment {
+    fn from(segment: &HistorySegment) -> Self {
+        let start_ms = segment.start_ms.max(0) as u64;
+        Segment {
+            start_ms,
+            end_ms: (segment.end_ms.max(0) as u64).max(start_ms),
+            text: segment.text.trim().to_string(),
+            confidence: segment.confidence,
+        }
+    }
+}
+
+impl From<&Segment> for HistorySegment {
+    fn from(segment: &Segment) -> Self {
+        HistorySegment {
+            start_ms: segment.start_ms as i64,
+            end_ms: segment.end_ms as i64,
+            text: segment.text.clone(),
+            confidence: segment.confidence,
+        }
+    }
+}
 
 /// A single transcription record
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -12,6 +76,262 @@ pub struct TranscriptionRecord {
     pub duration_ms: Option<u64>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub model: Option<String>,
+    /// Shared by the records split from one transcription on the split phrase
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub group_id: Option<String>,
+    /// Language code the text was transcribed in
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+    /// Set when the speech was translated into English
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub translated: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub segments: Option<Vec<HistorySegment>>,
+    /// Set when segments beyond `MAX_STORED_SEGMENTS` were dropped
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub segments_truncated: bool,
+    /// User-assigned labels such as "meeting" or "todo"
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// Number of words in `text`
+    #[serde(default)]
+    pub word_count: u32,
+    /// Set once the user corrected the text after transcription
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub edited: bool,
+    /// Confidence of the transcription (0.0 - 1.0), if Whisper reported token probabilities
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub confidence: Option<f32>,
+}
+
+/// Caps the number of stored segments, returning whether any were dropped
+fn cap_segments(mut segments: Vec<HistorySegment>) -> (Vec<HistorySegment>, bool) {
+    let truncated = segments.len() > MAX_STORED_SEGMENTS;
+    if truncated {
+        log::warn!(
+            "Storing only the first {} of {} segments",
+            MAX_STORED_SEGMENTS,
+            segments.len()
+        );
+        segments.truncate(MAX_STORED_SEGMENTS);
+    }
+    (segments, truncated)
+}
+
+/// File format for exporting history
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ExportFormat {
+    /// Comma-separated values with a header row
+    Csv,
+    /// One `[local datetime] text` line per record
+    PlainText,
+    /// One JSON object per line
+    JsonLines,
+    /// A single JSON document shaped like the history file
+    Json,
+}
+
+/// Column header of CSV exports
+const CSV_HEADER: &str = "id,timestamp,duration_ms,model,word_count,text";
+
+/// Quotes a CSV field if it contains a separator, quote or line break
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Format of exported datetimes, readable by people and spreadsheets alike
+const EXPORT_DATETIME_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+
+/// Formats a record timestamp (milliseconds since the epoch) as a datetime in
+/// the local time zone, e.g. "2023-11-14 23:13:20"
+fn local_datetime(timestamp_ms: i64) -> String {
+    chrono::DateTime::from_timestamp_millis(timestamp_ms)
+        .map(|datetime| {
+            datetime
+                .with_timezone(&chrono::Local)
+                .format(EXPORT_DATETIME_FORMAT)
+                .to_string()
+        })
+        .unwrap_or_else(|| timestamp_ms.to_string())
+}
+
+/// Writes records in the given export format
+fn write_export<W: Write>(
+    records: &[&TranscriptionRecord],
+    format: ExportFormat,
+    mut out: W,
+) -> std::io::Result<()> {
+    match format {
+        ExportFormat::Csv => {
+            writeln!(out, "{}", CSV_HEADER)?;
+            for record in records {
+                writeln!(
+                    out,
+                    "{},{},{},{},{},{}",
+                    csv_field(&record.id),
+                    local_datetime(record.timestamp),
+                    record
+                        .duration_ms
+                        .map(|d| d.to_string())
+                        .unwrap_or_default(),
+                    csv_field(record.model.as_deref().unwrap_or_default()),
+                    count_words(&record.text),
+                    csv_field(&record.text)
+                )?;
+            }
+        }
+        ExportFormat::PlainText => {
+            for record in records {
+                writeln!(
+                    out,
+                    "[{}] {}",
+                    local_datetime(record.timestamp),
+                    record.text
+                )?;
+            }
+        }
+        ExportFormat::JsonLines => {
+            for record in records {
+                serde_json::to_writer(&mut out, record)?;
+                writeln!(out)?;
+            }
+        }
+        ExportFormat::Json => {
+            let storage = HistoryStorage {
+                records: records.iter().map(|record| (*record).clone()).collect(),
+            };
+            serde_json::to_writer_pretty(&mut out, &storage)?;
+            writeln!(out)?;
+        }
+    }
+    out.flush()
+}
+
+/// Selects the records whose timestamp lies within the inclusive range, if any
+fn records_in_range(
+    records: &[TranscriptionRecord],
+    date_range: Option<(i64, i64)>,
+) -> Vec<&TranscriptionRecord> {
+    records
+        .iter()
+        .filter(|record| match date_range {
+            Some((start, end)) => (start..=end).contains(&record.timestamp),
+            None => true,
+        })
+        .collect()
+}
+
+/// Counts the whitespace-separated words of a text
+fn count_words(text: &str) -> u32 {
+    text.split_whitespace().count() as u32
+}
+
+/// Replaces a record's text after the user corrected it
+fn apply_text_edit(record: &mut TranscriptionRecord, new_text: String) {
+    record.word_count = count_words(&new_text);
+    record.text = new_text;
+    record.edited = true;
+}
+
+/// Adds tags to a list, trimming them and skipping blanks and duplicates
+fn merge_tags(existing: &mut Vec<String>, tags: Vec<String>) {
+    for tag in tags {
+        let tag = tag.trim();
+        if !tag.is_empty() && !existing.iter().any(|t| t == tag) {
+            existing.push(tag.to_string());
+        }
+    }
+}
+
+/// Removes tags from a list
+fn remove_tags(existing: &mut Vec<String>, tags: &[String]) {
+    existing.retain(|t| !tags.iter().any(|tag| tag.trim() == t));
+}
+
+/// Keeps the records carrying a tag
+fn records_with_tag(records: Vec<TranscriptionRecord>, tag: &str) -> Vec<TranscriptionRecord> {
+    let tag = tag.trim();
+    records
+        .into_iter()
+        .filter(|record| record.tags.iter().any(|t| t == tag))
+        .collect()
+}
+
+/// Returns the union of all tags, sorted alphabetically
+fn collect_tags(records: &[TranscriptionRecord]) -> Vec<String> {
+    records
+        .iter()
+        .flat_map(|record| record.tags.iter().cloned())
+        .collect::<std::collections::BTreeSet<_>>()
+        .into_iter()
+        .collect()
+}
+
+/// Aggregate statistics over the whole history
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct HistoryStats {
+    pub total_records: usize,
+    pub total_words: u64,
+    /// Sum of the recording durations; records without a duration count as 0
+    pub total_duration_ms: u64,
+    /// Models that produced at least one record, sorted
+    pub unique_models_used: Vec<String>,
+    /// UTC date (`YYYY-MM-DD`) with the most records; ties go to the most recent day
+    pub most_active_day: Option<String>,
+    pub avg_words_per_record: f32,
+}
+
+/// Words in a record, counting the text of records stored before word counts were kept
+fn record_word_count(record: &TranscriptionRecord) -> u32 {
+    if record.word_count == 0 {
+        count_words(&record.text)
+    } else {
+        record.word_count
+    }
+}
+
+/// Computes the statistics of a set of records
+fn compute_history_stats(records: &[TranscriptionRecord]) -> HistoryStats {
+    let total_words: u64 = records
+        .iter()
+        .map(|record| u64::from(record_word_count(record)))
+        .sum();
+
+    let mut records_per_day: std::collections::BTreeMap<String, usize> = Default::default();
+    for record in records {
+        if let Some(datetime) = chrono::DateTime::from_timestamp_millis(record.timestamp) {
+            *records_per_day
+                .entry(datetime.format("%Y-%m-%d").to_string())
+                .or_default() += 1;
+        }
+    }
+
+    HistoryStats {
+        total_records: records.len(),
+        total_words,
+        total_duration_ms: records.iter().filter_map(|record| record.duration_ms).sum(),
+        unique_models_used: records
+            .iter()
+            .filter_map(|record| record.model.clone())
+            .collect::<std::collections::BTreeSet<_>>()
+            .into_iter()
+            .collect(),
+        // max_by_key keeps the last maximum, i.e. the latest of the tied days
+        most_active_day: records_per_day
+            .into_iter()
+            .max_by_key(|(_, count)| *count)
+            .map(|(day, _)| day),
+        avg_words_per_record: if records.is_empty() {
+            0.0
+        } else {
+            total_words as f32 / records.len() as f32
+        },
+    }
 }
 
 /// History storage structure
@@ -20,6 +340,96 @@ struct HistoryStorage {
     records: Vec<TranscriptionRecord>,
 }
 
+/// Inverted index over the words of all history records
+#[derive(Debug, Default)]
+struct HistoryIndex {
+    /// Lowercase word -> IDs of the records containing it, sorted so words
+    /// starting with a term can be looked up as a range
+    words: BTreeMap<String, HashSet<String>>,
+    /// Whether the index reflects the current history file
+    built: bool,
+}
+
+impl HistoryIndex {
+    /// Builds the index for the given records
+    fn build(records: &[TranscriptionRecord]) -> Self {
+        let mut words: BTreeMap<String, HashSet<String>> = BTreeMap::new();
+        for record in records {
+            for word in tokenize(&record.text) {
+                words.entry(word).or_default().insert(record.id.clone());
+            }
+        }
+        Self { words, built: true }
+    }
+
+    /// Returns the IDs of records containing every term, each as the start of one of their words
+    fn matching_ids(&self, terms: &[String]) -> HashSet<String> {
+        let mut result: Option<HashSet<String>> = None;
+        for term in terms {
+            let ids: HashSet<String> = self
+                .words
+                .range::<str, _>((Bound::Included(term.as_str()), Bound::Unbounded))
+                .take_while(|(word, _)| word.starts_with(term.as_str()))
+                .flat_map(|(_, ids)| ids.iter().cloned())
+                .collect();
+            let ids = match result {
+                Some(previous) => previous.intersection(&ids).cloned().collect(),
+                None => ids,
+            };
+            if ids.is_empty() {
+                return ids;
+            }
+            result = Some(ids);
+        }
+        result.unwrap_or_default()
+    }
+}
+
+/// Search index, rebuilt lazily after the history changes
+static HISTORY_INDEX: Lazy<Mutex<HistoryIndex>> = Lazy::new(|| Mutex::new(HistoryIndex::default()));
+
+/// Marks the search index stale so the next search rebuilds it
+fn invalidate_history_index() {
+    *HISTORY_INDEX.lock() = HistoryIndex::default();
+}
+
+/// Statistics of the current history, computed on first request after a change
+static HISTORY_STATS: Lazy<Mutex<Option<HistoryStats>>> = Lazy::new(|| Mutex::new(None));
+
+/// Splits text into lowercase words for indexing and searching
+fn tokenize(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
+
+/// Searches records for a query
+///
+/// Every word of the query must occur (case-insensitively, as the start of a word)
+/// in a record's text for it to match. An empty query matches every record.
+fn search_records(
+    records: &[TranscriptionRecord],
+    index: &HistoryIndex,
+    query: &str,
+    limit: Option<usize>,
+) -> Vec<TranscriptionRecord> {
+    let terms = tokenize(query);
+    let limit = limit.unwrap_or(usize::MAX);
+
+    if terms.is_empty() {
+        return records.iter().take(limit).cloned().collect();
+    }
+
+    let ids = index.matching_ids(&terms);
+    records
+        .iter()
+        .filter(|record| ids.contains(&record.id))
+        .take(limit)
+        .cloned()
+        .collect()
+}
+
 /// Get the path to the history file
 fn get_history_path() -> PathBuf {
     let config_dir = dirs::config_dir()
@@ -32,77 +442,648 @@ fn get_history_path() -> PathBuf {
     config_dir.join("history.json")
 }
 
+/// Get the store backing the history file.
+/// Version 0 files (written before the versioned envelope) share the current layout.
+fn history_store() -> JsonStore<HistoryStorage> {
+    history_store_at(get_history_path())
+}
+
+/// Get a store for a history file at any path, such as a backup
+fn history_store_at(path: PathBuf) -> JsonStore<HistoryStorage> {
+    JsonStore::new(path, HISTORY_VERSION, no_migration)
+}
+
 /// Load history from file
-fn load_history() -> HistoryStorage {
-    let path = get_history_path();
+///
+/// # Returns
+/// * `Ok(HistoryStorage)` with the stored history, empty if there is none yet
+/// * `Err(String)` if the file could not be read or was written by a newer
+///   version; callers must not save then, or the file would be overwritten
+fn load_history() -> Result<HistoryStorage, String> {
+    history_store()
+        .load()
+        .map_err(|e| format!("Failed to load history: {}", e))
+}
+
+/// Save history to file
+fn save_history(storage: &HistoryStorage) -> Result<(), String> {
+    let result = history_store()
+        .save(storage)
+        .map_err(|e| format!("Failed to write history file: {}", e));
+    invalidate_history_caches();
+    result
+}
+
+/// Drops the search index and statistics so they are rebuilt from the history file
+fn invalidate_history_caches() {
+    invalidate_history_index();
+    *HISTORY_STATS.lock() = None;
+}
 
-    if path.exists() {
-        match fs::read_to_string(&path) {
-            Ok(content) => serde_json::from_str(&content).unwrap_or_default(),
-            Err(_) => HistoryStorage::default(),
+/// Merges restored records into the current ones
+///
+/// Records are matched by ID; of two records with the same ID the one with the newer
+/// timestamp wins, the current one on a tie. The result is ordered most recent first.
+fn merge_records(
+    current: Vec<TranscriptionRecord>,
+    restored: Vec<TranscriptionRecord>,
+) -> Vec<TranscriptionRecord> {
+    let mut merged: Vec<TranscriptionRecord> = Vec::with_capacity(current.len() + restored.len());
+    let mut positions: HashMap<String, usize> = HashMap::new();
+
+    for record in current.into_iter().chain(restored) {
+        match positions.get(&record.id) {
+            Some(&position) => {
+                if record.timestamp > merged[position].timestamp {
+                    merged[position] = record;
+                }
+            }
+            None => {
+                positions.insert(record.id.clone(), merged.len());
+                merged.push(record);
+            }
         }
+    }
+
+    // Stable, so split parts sharing a timestamp stay in spoken order
+    merged.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
+    merged
+}
+
+/// Copies a history file to `dest`, checking both copies are valid history files
+fn backup_history_file(history_path: &Path, dest: &Path) -> Result<u32, String> {
+    let storage = if history_path.exists() {
+        history_store_at(history_path.to_path_buf())
+            .load_strict()
+            .map_err(|e| format!("Current history is not valid: {:#}", e))?
     } else {
         HistoryStorage::default()
+    };
+
+    if let Some(parent) = dest.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("Failed to create directory: {}", e))?;
+    }
+    if history_path.exists() {
+        fs::copy(history_path, dest).map_err(|e| format!("Failed to copy history: {}", e))?;
+    } else {
+        history_store_at(dest.to_path_buf())
+            .save(&storage)
+            .map_err(|e| format!("Failed to write backup: {}", e))?;
     }
+
+    let backup = history_store_at(dest.to_path_buf())
+        .load_strict()
+        .map_err(|e| format!("Backup could not be read back: {:#}", e))?;
+    Ok(backup.records.len() as u32)
 }
 
-/// Save history to file
-fn save_history(storage: &HistoryStorage) -> Result<(), String> {
-    let path = get_history_path();
-    let content = serde_json::to_string_pretty(storage)
-        .map_err(|e| format!("Failed to serialize history: {}", e))?;
+/// Restores a backup into the history file, merging or replacing the current records
+fn restore_history_file(history_path: &Path, src: &Path, merge: bool) -> Result<u32, String> {
+    let backup = history_store_at(src.to_path_buf())
+        .load_strict()
+        .map_err(|e| format!("Not a valid history backup: {:#}", e))?;
 
-    fs::write(&path, content).map_err(|e| format!("Failed to write history file: {}", e))?;
+    let store = history_store_at(history_path.to_path_buf());
+    let records = if merge {
+        let current = store
+            .load()
+            .map_err(|e| format!("Failed to load current history: {}", e))?;
+        merge_records(current.records, backup.records)
+    } else {
+        backup.records
+    };
 
-    Ok(())
+    let count = records.len() as u32;
+    store
+        .save(&HistoryStorage { records })
+        .map_err(|e| format!("Failed to write history file: {}", e))?;
+    Ok(count)
+}
+
+/// Number of records returned by `get_history` when no limit is given
+const DEFAULT_HISTORY_PAGE_SIZE: usize = 100;
+
+/// A page of history records
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PaginatedHistory {
+    /// Records on this page, most recent first
+    pub records: Vec<TranscriptionRecord>,
+    /// Total number of records in the history
+    pub total: usize,
+    /// Whether there are records after this page
+    pub has_more: bool,
+}
+
+/// Payload of the `history-truncated` event
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryOverLimit {
+    /// Number of records currently in the history
+    pub record_count: usize,
+    /// Configured maximum number of records
+    pub limit: usize,
 }
 
-/// Get all transcription history records
+/// Reports whether there are more records than the limit allows, without removing any;
+/// a limit of 0 allows any number
+fn over_limit(records: &[TranscriptionRecord], limit: usize) -> Option<HistoryOverLimit> {
+    (limit > 0 && records.len() > limit).then_some(HistoryOverLimit {
+        record_count: records.len(),
+        limit,
+    })
+}
+
+/// Drops the oldest records beyond the limit, returning how many were removed;
+/// a limit of 0 keeps every record
+fn trim_records(records: &mut Vec<TranscriptionRecord>, limit: usize) -> usize {
+    if limit == 0 {
+        return 0;
+    }
+    let removed = records.len().saturating_sub(limit);
+    records.truncate(limit);
+    removed
+}
+
+/// Adds new records to the beginning of the history (most recent first),
+/// keeping split parts in spoken order
+///
+/// The history may grow past `history_max_entries` unless the user opted into
+/// `trim_history_on_add`; otherwise only `trim_history_to_limit` removes records.
+fn insert_records(
+    history: &mut Vec<TranscriptionRecord>,
+    records: Vec<TranscriptionRecord>,
+    settings: &Settings,
+) {
+    history.splice(0..0, records);
+
+    if settings.trim_history_on_add {
+        trim_records(history, settings.history_max_entries);
+    }
+}
+
+/// Returns true if changing the history size from `previous` to `limit` can
+/// leave more records than allowed; 0 means unlimited
+pub fn is_lower_limit(previous: usize, limit: usize) -> bool {
+    limit > 0 && (previous == 0 || limit < previous)
+}
+
+/// Emits `history-truncated` if the history exceeds a newly lowered limit
+///
+/// Records are left in place; the user removes them with `trim_history_to_limit`,
+/// or the next transcription does if `trim_history_on_add` is on.
+///
+/// # Arguments
+/// * `app` - Application handle used to emit the event
+/// * `limit` - The new maximum number of records
+pub fn notify_if_over_limit(app: &AppHandle, limit: usize) {
+    let storage = match load_history() {
+        Ok(storage) => storage,
+        Err(e) => {
+            log::error!("{}", e);
+            return;
+        }
+    };
+    if let Some(excess) = over_limit(&storage.records, limit) {
+        log::info!(
+            "History has {} records, more than the new limit of {}",
+            excess.record_count,
+            excess.limit
+        );
+        let _ = app.emit(HISTORY_TRUNCATED_EVENT, &excess);
+    }
+}
+
+/// Cuts a page out of the records; offsets past the end give an empty page
+fn paginate(records: Vec<TranscriptionRecord>, offset: usize, limit: usize) -> PaginatedHistory {
+    let total = records.len();
+    let records: Vec<TranscriptionRecord> = records.into_iter().skip(offset).take(limit).collect();
+    let has_more = offset.saturating_add(records.len()) < total;
+    PaginatedHistory {
+        records,
+        total,
+        has_more,
+    }
+}
+
+/// Get a page of transcription history records
+///
+/// # Arguments
+/// * `offset` - Number of records to skip (default 0)
+/// * `limit` - Maximum number of records to return (default 100)
+///
+/// # Returns
+/// The requested records, most recent first, with the total record count
 #[tauri::command]
-pub fn get_history() -> Result<Vec<TranscriptionRecord>, String> {
+pub fn get_history(
+    offset: Option<usize>,
+    limit: Option<usize>,
+) -> Result<PaginatedHistory, String> {
     log::info!("Getting transcription history");
-    let storage = load_history();
-    Ok(storage.records)
+    let storage = load_history()?;
+    Ok(paginate(
+        storage.records,
+        offset.unwrap_or(0),
+        limit.unwrap_or(DEFAULT_HISTORY_PAGE_SIZE),
+    ))
+}
+
+/// Get the number of records in the transcription history
+#[tauri::command]
+pub fn get_history_count() -> Result<usize, String> {
+    Ok(load_history()?.records.len())
+}
+
+/// Get aggregate statistics of the transcription history
+///
+/// # Returns
+/// Record, word and duration totals, the models used and the most active day
+#[tauri::command]
+pub fn get_history_stats() -> Result<HistoryStats, String> {
+    // Compute while holding the lock so a concurrent save cannot leave stale stats cached
+    let mut stats = HISTORY_STATS.lock();
+    if let Some(stats) = stats.as_ref() {
+        return Ok(stats.clone());
+    }
+    let computed = compute_history_stats(&load_history()?.records);
+    Ok(stats.insert(computed).clone())
+}
+
+/// Search transcription history
+///
+/// # Arguments
+/// * `query` - Words to look for; every word must start a word of a record (case-insensitive)
+/// * `limit` - Maximum number of records to return
+///
+/// # Returns
+/// Matching records, most recent first. An empty query returns all records.
+#[tauri::command]
+pub fn search_history(
+    query: String,
+    limit: Option<usize>,
+) -> Result<Vec<TranscriptionRecord>, String> {
+    log::info!("Searching history for '{}'", query);
+
+    // Load while holding the lock so a concurrent save cannot leave a stale index marked built
+    let mut index = HISTORY_INDEX.lock();
+    let storage = load_history()?;
+    if !index.built {
+        *index = HistoryIndex::build(&storage.records);
+    }
+
+    Ok(search_records(&storage.records, &index, &query, limit))
+}
+
+/// Export transcription history to a file
+///
+/// # Arguments
+/// * `format` - Export file format
+/// * `dest_path` - File to write; missing parent directories are created
+/// * `date_range` - Optional inclusive `(start, end)` range of timestamps in milliseconds
+///
+/// # Returns
+/// * `Ok(u32)` with the number of records written
+/// * `Err(String)` if the file could not be written
+#[tauri::command]
+pub fn export_history(
+    format: ExportFormat,
+    dest_path: String,
+    date_range: Option<(i64, i64)>,
+) -> Result<u32, String> {
+    log::info!("Exporting history as {:?} to {}", format, dest_path);
+
+    let storage = load_history()?;
+    let records = records_in_range(&storage.records, date_range);
+
+    let dest_path = Path::new(&dest_path);
+    if let Some(parent) = dest_path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create export directory: {}", e))?;
+    }
+    let file =
+        fs::File::create(dest_path).map_err(|e| format!("Failed to create export file: {}", e))?;
+    write_export(&records, format, BufWriter::new(file))
+        .map_err(|e| format!("Failed to write export file: {}", e))?;
+
+    Ok(records.len() as u32)
+}
+
+/// Applies a change to one history record and saves the history
+///
+/// # Returns
+/// * `Ok(TranscriptionRecord)` with the updated record
+/// * `Err(String)` if no record has that ID or the history could not be saved
+fn modify_history_entry(
+    id: &str,
+    change: impl FnOnce(&mut TranscriptionRecord),
+) -> Result<TranscriptionRecord, String> {
+    let mut storage = load_history()?;
+    let record = storage
+        .records
+        .iter_mut()
+        .find(|r| r.id == id)
+        .ok_or_else(|| format!("History entry '{}' not found", id))?;
+    change(record);
+    let record = record.clone();
+
+    save_history(&storage)?;
+    Ok(record)
+}
+
+/// Replace the text of a history entry, e.g. to correct a transcription mistake
+///
+/// # Arguments
+/// * `id` - ID of the history entry
+/// * `new_text` - Corrected text
+///
+/// # Returns
+/// * `Ok(TranscriptionRecord)` with the updated record, marked as edited
+/// * `Err(String)` if no record has that ID or the history could not be saved
+#[tauri::command]
+pub fn update_history_entry(id: String, new_text: String) -> Result<TranscriptionRecord, String> {
+    log::info!("Updating history entry {}: {} chars", id, new_text.len());
+    modify_history_entry(&id, |record| apply_text_edit(record, new_text))
+}
+
+/// Add tags to a history entry, keeping the tags it already has
+///
+/// # Arguments
+/// * `id` - ID of the history entry
+/// * `tags` - Tags to add; blank and duplicate tags are ignored
+#[tauri::command]
+pub fn tag_history_entry(id: String, tags: Vec<String>) -> Result<(), String> {
+    log::info!("Tagging history entry {}: {:?}", id, tags);
+    modify_history_entry(&id, |record| merge_tags(&mut record.tags, tags)).map(|_| ())
+}
+
+/// Remove tags from a history entry
+///
+/// # Arguments
+/// * `id` - ID of the history entry
+/// * `tags` - Tags to remove; tags the entry doesn't have are ignored
+#[tauri::command]
+pub fn untag_history_entry(id: String, tags: Vec<String>) -> Result<(), String> {
+    log::info!("Untagging history entry {}: {:?}", id, tags);
+    modify_history_entry(&id, |record| remove_tags(&mut record.tags, &tags)).map(|_| ())
+}
+
+/// Get the history records carrying a tag, most recent first
+#[tauri::command]
+pub fn get_history_by_tag(tag: String) -> Result<Vec<TranscriptionRecord>, String> {
+    Ok(records_with_tag(load_history()?.records, &tag))
+}
+
+/// List every tag used in the history, sorted alphabetically
+#[tauri::command]
+pub fn list_all_tags() -> Result<Vec<String>, String> {
+    Ok(collect_tags(&load_history()?.records))
+}
+
+/// Finds a history record by ID
+///
+/// # Returns
+/// The record, or `None` if no record has that ID
+pub fn find_history_entry(id: &str) -> Option<TranscriptionRecord> {
+    load_history()
+        .ok()?
+        .records
+        .into_iter()
+        .find(|r| r.id == id)
+}
+
+/// Returns the text of the record with the given ID
+///
+/// # Returns
+/// * `Ok(String)` with the record's text
+/// * `Err(String)` if no record has that ID
+fn entry_text(records: &[TranscriptionRecord], id: &str) -> Result<String, String> {
+    find_entry(records, id).map(|r| r.text.clone())
+}
+
+/// Looks up a history entry by ID
+fn find_entry<'a>(
+    records: &'a [TranscriptionRecord],
+    id: &str,
+) -> Result<&'a TranscriptionRecord, String> {
+    records
+        .iter()
+        .find(|r| r.id == id)
+        .ok_or_else(|| format!("History entry '{}' not found", id))
+}
+
+/// Copy the text of a history entry to the clipboard again, without pasting it
+///
+/// # Arguments
+/// * `app` - Application handle used to report missing clipboard tools
+/// * `id` - ID of the history entry
+///
+/// # Returns
+/// * `Ok(())` if the text was copied
+/// * `Err(String)` if no record has that ID or copying failed
+#[tauri::command]
+pub fn copy_history_entry(app: AppHandle, id: String) -> Result<(), String> {
+    let text = entry_text(&load_history()?.records, &id)?;
+    log::info!("Copying history entry {} to the clipboard", id);
+    copy_to_clipboard(app, text)
+}
+
+/// Paste the text of a history entry again, delivered like a new transcription
+/// (paste format, post-processing command and output mode apply). The split
+/// phrase is not applied again, since entries were split when saved.
+///
+/// # Arguments
+/// * `app` - Application handle used to report paste failures
+/// * `id` - ID of the history entry
+///
+/// # Returns
+/// * `Ok(())` if the text was delivered
+/// * `Err(String)` if no record has that ID or pasting failed
+#[tauri::command]
+pub async fn paste_history_entry(app: AppHandle, id: String) -> Result<(), String> {
+    let storage = load_history()?;
+    let record = find_entry(&storage.records, &id)?;
+    log::info!("Pasting history entry {}", id);
+    deliver_text(
+        &app,
+        record.text.clone(),
+        TextOrigin::History,
+        record.language.as_deref(),
+    )
+    .await
 }
 
 /// Add a new transcription record to history
+///
+/// If a split phrase is configured and occurs in the text, every part is stored
+/// as its own record sharing a group id, and the record of the first part is returned.
 #[tauri::command]
 pub fn add_history(
     text: String,
     duration_ms: Option<u64>,
     model: Option<String>,
+    language: Option<String>,
+    translated: Option<bool>,
+    segments: Option<Vec<HistorySegment>>,
+    confidence: Option<f32>,
 ) -> Result<TranscriptionRecord, String> {
     log::info!("Adding transcription to history: {} chars", text.len());
 
-    let mut storage = load_history();
-
-    let record = TranscriptionRecord {
-        id: uuid::Uuid::new_v4().to_string(),
-        text,
-        timestamp: chrono::Utc::now().timestamp_millis(),
-        duration_ms,
-        model,
-    };
+    // Derive the confidence from the segments if the caller didn't pass it
+    let confidence = confidence.or_else(|| {
+        let segments: Vec<Segment> = segments.as_ref()?.iter().map(Segment::from).collect();
+        overall_confidence(&segments)
+    });
 
-    // Add to beginning of list (most recent first)
-    storage.records.insert(0, record.clone());
+    let mut storage = load_history()?;
+    let settings = current_settings().unwrap_or_default();
 
-    // Keep only last 100 records
-    if storage.records.len() > 100 {
-        storage.records.truncate(100);
+    let mut parts = split_transcription(&text, &settings);
+    if parts.is_empty() {
+        // Only the split phrase was spoken; keep the raw text rather than nothing
+        parts.push(text);
     }
+    let is_split = parts.len() > 1;
+    if is_split {
+        log::info!("Split transcription into {} history entries", parts.len());
+    }
+
+    // Segments are only kept when the user opted in, and only for unsplit text
+    let (segments, segments_truncated) =
+        match segments.filter(|_| settings.store_segments && !is_split) {
+            Some(segments) => {
+                let (segments, truncated) = cap_segments(segments);
+                (Some(segments), truncated)
+            }
+            None => (None, false),
+        };
+
+    let timestamp = chrono::Utc::now().timestamp_millis();
+    let group_id = is_split.then(|| uuid::Uuid::new_v4().to_string());
+
+    let records: Vec<TranscriptionRecord> = parts
+        .into_iter()
+        .map(|part| TranscriptionRecord {
+            id: uuid::Uuid::new_v4().to_string(),
+            word_count: count_words(&part),
+            text: part,
+            timestamp,
+            duration_ms,
+            model: model.clone(),
+            group_id: group_id.clone(),
+            language: language.clone(),
+            translated: translated.unwrap_or(false),
+            segments: segments.clone(),
+            segments_truncated,
+            tags: Vec::new(),
+            edited: false,
+            confidence,
+        })
+        .collect();
+    let record = records[0].clone();
+
+    insert_records(&mut storage.records, records, &settings);
 
     save_history(&storage)?;
 
     Ok(record)
 }
 
+/// Returns true if a finished transcription should be saved to history:
+/// the user didn't opt out and something was actually said
+fn should_save_transcription(text: &str, settings: &Settings) -> bool {
+    settings.save_history && !text.trim().is_empty()
+}
+
+/// Saves a finished transcription to history with its duration, model,
+/// language and segments, unless it is empty or saving is turned off
+///
+/// # Arguments
+/// * `result` - Transcription to save
+/// * `settings` - Settings with the `save_history` opt-out
+///
+/// # Returns
+/// * `Ok(Some(String))` with the ID of the (first) stored record
+/// * `Ok(None)` if nothing was stored
+/// * `Err(String)` if the history could not be saved
+pub fn save_transcription(
+    result: &TranscriptionResult,
+    settings: &Settings,
+) -> Result<Option<String>, String> {
+    if !should_save_transcription(&result.text, settings) {
+        return Ok(None);
+    }
+
+    add_history(
+        result.text.clone(),
+        Some(result.duration_ms),
+        Some(result.model.clone()),
+        result.language.clone(),
+        Some(result.translated),
+        Some(result.segments.iter().map(HistorySegment::from).collect()),
+        result.confidence,
+    )
+    .map(|record| Some(record.id))
+}
+
+/// Removes the oldest records beyond the configured history size
+///
+/// Lowering `historyMaxEntries` doesn't remove records by itself; this command does.
+///
+/// # Returns
+/// * `Ok(usize)` with the number of records removed
+/// * `Err(String)` if the settings could not be read or the history could not be saved
+#[tauri::command]
+pub fn trim_history_to_limit() -> Result<usize, String> {
+    let limit = current_settings()?.history_max_entries;
+
+    let mut storage = load_history()?;
+    let removed = trim_records(&mut storage.records, limit);
+    if removed > 0 {
+        log::info!("Trimmed {} records to the limit of {}", removed, limit);
+        save_history(&storage)?;
+    }
+
+    Ok(removed)
+}
+
+/// Back up the transcription history to a file
+///
+/// # Arguments
+/// * `dest_path` - File to write; missing parent directories are created
+///
+/// # Returns
+/// * `Ok(u32)` with the number of records backed up
+/// * `Err(String)` if the history is invalid or the backup could not be written
+#[tauri::command]
+pub fn backup_history(dest_path: String) -> Result<u32, String> {
+    log::info!("Backing up history to {}", dest_path);
+    backup_history_file(&get_history_path(), Path::new(&dest_path))
+}
+
+/// Restore the transcription history from a backup
+///
+/// # Arguments
+/// * `src_path` - Backup file written by `backup_history`
+/// * `merge` - Keep current records and add the backup's, preferring the newer
+///   version of records present in both; otherwise replace the history entirely
+///
+/// # Returns
+/// * `Ok(u32)` with the number of records in the history after restoring
+/// * `Err(String)` if the backup is not a valid history file or could not be applied
+#[tauri::command]
+pub fn restore_history(src_path: String, merge: bool) -> Result<u32, String> {
+    log::info!("Restoring history from {} (merge: {})", src_path, merge);
+    let result = restore_history_file(&get_history_path(), Path::new(&src_path), merge);
+    invalidate_history_caches();
+    result
+}
+
 /// Delete a specific history entry by ID
 #[tauri::command]
 pub fn delete_history_entry(id: String) -> Result<(), String> {
     log::info!("Deleting history entry: {}", id);
 
-    let mut storage = load_history();
+    let mut storage = load_history()?;
     storage.records.retain(|r| r.id != id);
     save_history(&storage)?;
 
@@ -114,6 +1095,8 @@ pub fn delete_history_entry(id: String) -> Result<(), String> {
 pub fn clear_history() -> Result<(), String> {
     log::info!("Clearing all history");
 
+    // Don't replace a history file this version can't read
+    load_history()?;
     let storage = HistoryStorage::default();
     save_history(&storage)?;
 
@@ -129,4 +1112,690 @@ mod tests {
         let path = get_history_path();
         assert!(path.ends_with("history.json"));
     }
+
+    fn segment(index: i64) -> HistorySegment {
+        HistorySegment {
+            start_ms: index * 1000,
+            end_ms: index * 1000 + 900,
+            text: format!("segment {}", index),
+            confidence: None,
+        }
+    }
+
+    #[test]
+    fn test_only_non_empty_transcriptions_are_saved() {
+        let settings = Settings::default();
+        assert!(settings.save_history);
+
+        assert!(should_save_transcription("Hello world", &settings));
+        // Silent or too short audio transcribes to an empty string
+        assert!(!should_save_transcription("", &settings));
+        assert!(!should_save_transcription("  \n", &settings));
+
+        let opted_out = Settings {
+            save_history: false,
+            ..Default::default()
+        };
+        assert!(!should_save_transcription("Hello world", &opted_out));
+    }
+
+    #[test]
+    fn test_old_record_without_segments_is_readable() {
+        let json = r#"{ "id": "1", "text": "hello", "timestamp": 0 }"#;
+        let record: TranscriptionRecord = serde_json::from_str(json).unwrap();
+
+        assert!(record.language.is_none());
+        assert!(!record.translated);
+        assert!(record.segments.is_none());
+        assert!(!record.segments_truncated);
+    }
+
+    #[test]
+    fn test_record_with_segments_round_trip() {
+        let record = TranscriptionRecord {
+            id: "1".to_string(),
+            text: "segment 0 segment 1".to_string(),
+            word_count: 4,
+            timestamp: 0,
+            duration_ms: None,
+            model: None,
+            group_id: None,
+            language: Some("de".to_string()),
+            translated: true,
+            segments: Some(vec![segment(0), segment(1)]),
+            segments_truncated: false,
+            tags: Vec::new(),
+            edited: false,
+            confidence: None,
+        };
+
+        let json = serde_json::to_string(&record).unwrap();
+        assert!(!json.contains("segments_truncated"));
+
+        let loaded: TranscriptionRecord = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded.language, record.language);
+        assert!(loaded.translated);
+        assert_eq!(loaded.segments, record.segments);
+    }
+
+    fn record(id: &str, text: &str) -> TranscriptionRecord {
+        TranscriptionRecord {
+            id: id.to_string(),
+            text: text.to_string(),
+            word_count: count_words(text),
+            timestamp: 0,
+            duration_ms: None,
+            model: None,
+            group_id: None,
+            language: None,
+            translated: false,
+            segments: None,
+            segments_truncated: false,
+            tags: Vec::new(),
+            edited: false,
+            confidence: None,
+        }
+    }
+
+    fn search(records: &[TranscriptionRecord], query: &str) -> Vec<String> {
+        let index = HistoryIndex::build(records);
+        search_records(records, &index, query, None)
+            .into_iter()
+            .map(|r| r.id)
+            .collect()
+    }
+
+    fn search_fixture() -> Vec<TranscriptionRecord> {
+        vec![
+            record("3", "Buy milk and eggs."),
+            record("2", "Call Mom about the weekend"),
+            record("1", "Milkshake recipe: bananas, milk, honey"),
+        ]
+    }
+
+    #[test]
+    fn test_entry_text_finds_record_or_fails() {
+        let records = search_fixture();
+
+        assert_eq!(
+            entry_text(&records, "2"),
+            Ok("Call Mom about the weekend".to_string())
+        );
+        assert_eq!(
+            entry_text(&records, "missing"),
+            Err("History entry 'missing' not found".to_string())
+        );
+    }
+
+    #[test]
+    fn test_search_empty_query_returns_all() {
+        let records = search_fixture();
+
+        assert_eq!(search(&records, ""), vec!["3", "2", "1"]);
+        assert_eq!(search(&records, "  ,. "), vec!["3", "2", "1"]);
+
+        let index = HistoryIndex::build(&records);
+        assert_eq!(search_records(&records, &index, "", Some(2)).len(), 2);
+    }
+
+    #[test]
+    fn test_search_single_word() {
+        let records = search_fixture();
+
+        assert_eq!(search(&records, "milk"), vec!["3", "1"]);
+        assert_eq!(search(&records, "week"), vec!["2"]);
+        assert!(search(&records, "coffee").is_empty());
+    }
+
+    #[test]
+    fn test_search_matches_word_prefixes_only() {
+        let records = search_fixture();
+
+        assert_eq!(search(&records, "bana"), vec!["1"]);
+        assert_eq!(search(&records, "milks"), vec!["1"]);
+        // Terms are looked up from the start of a word, not anywhere inside it
+        assert!(search(&records, "shake").is_empty());
+        assert!(search(&records, "end").is_empty());
+    }
+
+    #[test]
+    fn test_search_multiple_words_require_all() {
+        let records = search_fixture();
+
+        assert_eq!(search(&records, "milk honey"), vec!["1"]);
+        assert_eq!(search(&records, "eggs milk"), vec!["3"]);
+        assert!(search(&records, "milk mom").is_empty());
+    }
+
+    #[test]
+    fn test_search_is_case_insensitive() {
+        let records = search_fixture();
+
+        assert_eq!(search(&records, "MOM"), vec!["2"]);
+        assert_eq!(search(&records, "Bananas"), vec!["1"]);
+    }
+
+    fn export(records: &[TranscriptionRecord], format: ExportFormat) -> String {
+        let records: Vec<&TranscriptionRecord> = records.iter().collect();
+        let mut out = Vec::new();
+        write_export(&records, format, &mut out).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn test_export_csv() {
+        let mut first = record("a", "Hello, \"world\"");
+        first.timestamp = 1_700_000_000_000;
+        first.duration_ms = Some(1500);
+        first.model = Some("base".to_string());
+
+        let csv = export(&[first, record("b", "plain text")], ExportFormat::Csv);
+        let lines: Vec<&str> = csv.lines().collect();
+
+        assert_eq!(lines[0], CSV_HEADER);
+        assert_eq!(
+            lines[1],
+            format!(
+                "a,{},1500,base,2,\"Hello, \"\"world\"\"\"",
+                local_datetime(1_700_000_000_000)
+            )
+        );
+        assert_eq!(lines[2], format!("b,{},,,2,plain text", local_datetime(0)));
+    }
+
+    #[test]
+    fn test_export_csv_escapes_commas_quotes_and_newlines() {
+        assert_eq!(csv_field("plain"), "plain");
+        assert_eq!(csv_field("milk, eggs"), "\"milk, eggs\"");
+        assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+        assert_eq!(csv_field("line one\nline two"), "\"line one\nline two\"");
+        assert_eq!(csv_field("windows\r\nbreak"), "\"windows\r\nbreak\"");
+
+        let mut tricky = record("a", "First line, \"quoted\"\nsecond line");
+        tricky.model = Some("my,model".to_string());
+        let csv = export(&[tricky], ExportFormat::Csv);
+
+        assert_eq!(
+            csv,
+            format!(
+                "{}\na,{},,\"my,model\",5,\"First line, \"\"quoted\"\"\nsecond line\"\n",
+                CSV_HEADER,
+                local_datetime(0)
+            )
+        );
+    }
+
+    #[test]
+    fn test_local_datetime_round_trips() {
+        let timestamp = 1_700_000_000_000;
+        let formatted = local_datetime(timestamp);
+
+        let parsed = chrono::NaiveDateTime::parse_from_str(&formatted, EXPORT_DATETIME_FORMAT)
+            .unwrap()
+            .and_local_timezone(chrono::Local)
+            .earliest()
+            .unwrap();
+        assert_eq!(parsed.timestamp_millis(), timestamp);
+        // Out-of-range timestamps fall back to the raw number
+        assert_eq!(local_datetime(i64::MAX), i64::MAX.to_string());
+    }
+
+    #[test]
+    fn test_export_plain_text() {
+        let mut first = record("a", "Buy milk");
+        first.timestamp = 1_700_000_000_000;
+
+        assert_eq!(
+            export(&[first], ExportFormat::PlainText),
+            format!("[{}] Buy milk\n", local_datetime(1_700_000_000_000))
+        );
+    }
+
+    #[test]
+    fn test_export_json_lines_parse() {
+        let jsonl = export(&search_fixture(), ExportFormat::JsonLines);
+
+        let parsed: Vec<TranscriptionRecord> = jsonl
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(parsed.len(), 3);
+        assert_eq!(parsed[1].text, "Call Mom about the weekend");
+    }
+
+    #[test]
+    fn test_export_json_is_shaped_like_history_file() {
+        let json = export(&search_fixture(), ExportFormat::Json);
+
+        let storage: HistoryStorage = serde_json::from_str(&json).unwrap();
+        let ids: Vec<&str> = storage.records.iter().map(|r| r.id.as_str()).collect();
+        assert_eq!(ids, vec!["3", "2", "1"]);
+    }
+
+    #[test]
+    fn test_export_honors_date_range() {
+        let records: Vec<TranscriptionRecord> = (0..5)
+            .map(|i| {
+                let mut record = record(&i.to_string(), "text");
+                record.timestamp = i * 1000;
+                record
+            })
+            .collect();
+
+        let ids: Vec<&str> = records_in_range(&records, Some((1000, 3000)))
+            .iter()
+            .map(|r| r.id.as_str())
+            .collect();
+        assert_eq!(ids, vec!["1", "2", "3"]);
+        assert_eq!(records_in_range(&records, None).len(), 5);
+        assert!(records_in_range(&records, Some((3000, 1000))).is_empty());
+    }
+
+    fn tags(values: &[&str]) -> Vec<String> {
+        values.iter().map(|v| v.to_string()).collect()
+    }
+
+    #[test]
+    fn test_merge_tags_skips_duplicates() {
+        let mut existing = tags(&["meeting"]);
+        merge_tags(&mut existing, tags(&["todo", " meeting ", "", "todo"]));
+
+        assert_eq!(existing, tags(&["meeting", "todo"]));
+    }
+
+    #[test]
+    fn test_remove_tags() {
+        let mut existing = tags(&["meeting", "todo", "idea"]);
+        remove_tags(&mut existing, &tags(&["todo", "missing"]));
+
+        assert_eq!(existing, tags(&["meeting", "idea"]));
+    }
+
+    #[test]
+    fn test_collect_tags_sorted_union() {
+        let mut first = record("1", "a");
+        first.tags = tags(&["todo", "meeting"]);
+        let mut second = record("2", "b");
+        second.tags = tags(&["idea", "todo"]);
+
+        assert_eq!(
+            collect_tags(&[first, second, record("3", "c")]),
+            tags(&["idea", "meeting", "todo"])
+        );
+    }
+
+    #[test]
+    fn test_records_with_tag() {
+        let mut first = record("1", "a");
+        first.tags = tags(&["todo"]);
+        let mut second = record("2", "b");
+        second.tags = tags(&["idea"]);
+
+        let ids: Vec<String> = records_with_tag(vec![first, second, record("3", "c")], " todo")
+            .into_iter()
+            .map(|r| r.id)
+            .collect();
+        assert_eq!(ids, vec!["1"]);
+    }
+
+    #[test]
+    fn test_tags_omitted_when_empty() {
+        let json = serde_json::to_string(&record("1", "a")).unwrap();
+        assert!(!json.contains("tags"));
+
+        let mut tagged = record("1", "a");
+        tagged.tags = tags(&["idea"]);
+        let loaded: TranscriptionRecord =
+            serde_json::from_str(&serde_json::to_string(&tagged).unwrap()).unwrap();
+        assert_eq!(loaded.tags, tags(&["idea"]));
+    }
+
+    #[test]
+    fn test_text_edit_marks_record_edited() {
+        let mut record = record("1", "Call mom at five");
+        assert_eq!(record.word_count, 4);
+        assert!(!record.edited);
+
+        apply_text_edit(&mut record, "Call Tom at five thirty".to_string());
+
+        assert_eq!(record.text, "Call Tom at five thirty");
+        assert_eq!(record.word_count, 5);
+        assert!(record.edited);
+    }
+
+    #[test]
+    fn test_old_record_defaults_word_count_and_edited() {
+        let json = r#"{ "id": "1", "text": "hello", "timestamp": 0 }"#;
+        let record: TranscriptionRecord = serde_json::from_str(json).unwrap();
+
+        assert_eq!(record.word_count, 0);
+        assert!(!record.edited);
+    }
+
+    fn numbered_records(count: usize) -> Vec<TranscriptionRecord> {
+        (0..count).map(|i| record(&i.to_string(), "text")).collect()
+    }
+
+    #[test]
+    fn test_paginate_has_more_at_boundaries() {
+        let page = paginate(numbered_records(5), 0, 2);
+        assert_eq!(page.records.len(), 2);
+        assert_eq!(page.total, 5);
+        assert!(page.has_more);
+
+        // The page ends exactly at the last record
+        let page = paginate(numbered_records(5), 3, 2);
+        assert_eq!(page.records[0].id, "3");
+        assert!(!page.has_more);
+
+        let page = paginate(numbered_records(5), 0, 5);
+        assert!(!page.has_more);
+    }
+
+    #[test]
+    fn test_paginate_past_the_end() {
+        let page = paginate(numbered_records(3), 2, 10);
+        assert_eq!(page.records.len(), 1);
+        assert!(!page.has_more);
+
+        let page = paginate(numbered_records(3), 10, 10);
+        assert!(page.records.is_empty());
+        assert_eq!(page.total, 3);
+        assert!(!page.has_more);
+
+        let page = paginate(numbered_records(3), usize::MAX, usize::MAX);
+        assert!(page.records.is_empty());
+    }
+
+    /// 20 records over three days and three models, with a known word count and duration each
+    fn stats_fixture() -> Vec<TranscriptionRecord> {
+        const DAY_MS: i64 = 24 * 60 * 60 * 1000;
+        // 2024-03-01T10:00:00Z
+        const START: i64 = 1_709_287_200_000;
+
+        (0..20)
+            .map(|i| {
+                let mut record = record(&i.to_string(), &"word ".repeat(i % 4 + 1));
+                // Days get 5, 8 and 7 records
+                let day = match i {
+                    0..=4 => 0,
+                    5..=12 => 1,
+                    _ => 2,
+                };
+                record.timestamp = START + day * DAY_MS + i as i64 * 60_000;
+                record.duration_ms = if i == 19 { None } else { Some(1000) };
+                record.model = match i % 3 {
+                    0 => Some("base".to_string()),
+                    1 => Some("small.en".to_string()),
+                    _ if i == 2 => None,
+                    _ => Some("large-v3".to_string()),
+                };
+                record
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_history_stats() {
+        let stats = compute_history_stats(&stats_fixture());
+
+        assert_eq!(stats.total_records, 20);
+        // Word counts cycle 1, 2, 3, 4 five times
+        assert_eq!(stats.total_words, 50);
+        assert_eq!(stats.total_duration_ms, 19_000);
+        assert_eq!(
+            stats.unique_models_used,
+            vec!["base", "large-v3", "small.en"]
+        );
+        assert_eq!(stats.most_active_day.as_deref(), Some("2024-03-02"));
+        assert_eq!(stats.avg_words_per_record, 2.5);
+    }
+
+    #[test]
+    fn test_history_stats_empty_and_legacy_records() {
+        let stats = compute_history_stats(&[]);
+        assert_eq!(stats.total_records, 0);
+        assert_eq!(stats.most_active_day, None);
+        assert_eq!(stats.avg_words_per_record, 0.0);
+
+        // Records stored before word counts were kept are counted from their text
+        let mut legacy = record("1", "three little words");
+        legacy.word_count = 0;
+        assert_eq!(compute_history_stats(&[legacy]).total_words, 3);
+    }
+
+    #[test]
+    fn test_history_stats_tie_goes_to_latest_day() {
+        let mut first = record("1", "a");
+        first.timestamp = 1_709_287_200_000;
+        let mut second = record("2", "b");
+        second.timestamp = 1_709_287_200_000 + 24 * 60 * 60 * 1000;
+
+        let stats = compute_history_stats(&[second, first]);
+        assert_eq!(stats.most_active_day.as_deref(), Some("2024-03-02"));
+    }
+
+    #[test]
+    fn test_over_limit_leaves_records_in_place() {
+        let records = numbered_records(5);
+
+        assert_eq!(
+            over_limit(&records, 3),
+            Some(HistoryOverLimit {
+                record_count: 5,
+                limit: 3
+            })
+        );
+        assert_eq!(over_limit(&records, 5), None);
+        // Only reporting: nothing is removed until the user trims
+        assert_eq!(records.len(), 5);
+    }
+
+    #[test]
+    fn test_trim_records_keeps_most_recent() {
+        let mut records = numbered_records(5);
+
+        assert_eq!(trim_records(&mut records, 3), 2);
+        let ids: Vec<_> = records.iter().map(|r| r.id.as_str()).collect();
+        assert_eq!(ids, vec!["0", "1", "2"]);
+
+        assert_eq!(trim_records(&mut records, 10), 0);
+        assert_eq!(records.len(), 3);
+    }
+
+    #[test]
+    fn test_trim_records_respects_custom_limit() {
+        let mut records = numbered_records(300);
+
+        // Unlimited keeps everything
+        assert_eq!(trim_records(&mut records, 0), 0);
+        assert_eq!(over_limit(&records, 0), None);
+        assert_eq!(records.len(), 300);
+
+        // Raised above the default
+        assert_eq!(trim_records(&mut records, 250), 50);
+        assert_eq!(records.len(), 250);
+
+        // Lowered again, the next trim cuts down to the new cap
+        assert_eq!(trim_records(&mut records, 7), 243);
+        let ids: Vec<_> = records.iter().map(|r| r.id.as_str()).collect();
+        assert_eq!(ids, vec!["0", "1", "2", "3", "4", "5", "6"]);
+    }
+
+    #[test]
+    fn test_adding_records_does_not_truncate() {
+        let mut history = numbered_records(3);
+        let limit = 3;
+        let settings = Settings {
+            history_max_entries: limit,
+            ..Settings::default()
+        };
+
+        insert_records(&mut history, vec![record("new", "new")], &settings);
+
+        // The history stays over the limit until the user trims it
+        assert_eq!(history.len(), 4);
+        assert_eq!(history[0].id, "new");
+        assert_eq!(
+            over_limit(&history, limit),
+            Some(HistoryOverLimit {
+                record_count: 4,
+                limit
+            })
+        );
+
+        assert_eq!(trim_records(&mut history, limit), 1);
+        let ids: Vec<_> = history.iter().map(|r| r.id.as_str()).collect();
+        assert_eq!(ids, vec!["new", "0", "1"]);
+    }
+
+    #[test]
+    fn test_adding_records_trims_when_opted_in() {
+        let mut history = numbered_records(3);
+        let settings = Settings {
+            history_max_entries: 3,
+            trim_history_on_add: true,
+            ..Settings::default()
+        };
+
+        insert_records(&mut history, vec![record("new", "new")], &settings);
+
+        let ids: Vec<_> = history.iter().map(|r| r.id.as_str()).collect();
+        assert_eq!(ids, vec!["new", "0", "1"]);
+    }
+
+    #[test]
+    fn test_is_lower_limit_treats_zero_as_unlimited() {
+        assert!(is_lower_limit(100, 50));
+        assert!(is_lower_limit(0, 50));
+        assert!(!is_lower_limit(50, 100));
+        assert!(!is_lower_limit(50, 50));
+        assert!(!is_lower_limit(50, 0));
+        assert!(!is_lower_limit(0, 0));
+    }
+
+    fn timed_record(id: &str, text: &str, timestamp: i64) -> TranscriptionRecord {
+        TranscriptionRecord {
+            timestamp,
+            ..record(id, text)
+        }
+    }
+
+    fn ids(records: &[TranscriptionRecord]) -> Vec<&str> {
+        records.iter().map(|r| r.id.as_str()).collect()
+    }
+
+    #[test]
+    fn test_merge_keeps_newer_duplicate() {
+        let current = vec![
+            timed_record("b", "current b", 20),
+            timed_record("a", "current a", 10),
+        ];
+        let restored = vec![
+            timed_record("c", "restored c", 30),
+            timed_record("b", "restored b", 5),
+            timed_record("a", "restored a", 15),
+        ];
+
+        let merged = merge_records(current, restored);
+
+        assert_eq!(ids(&merged), vec!["c", "b", "a"]);
+        assert_eq!(merged[1].text, "current b");
+        assert_eq!(merged[2].text, "restored a");
+    }
+
+    #[test]
+    fn test_merge_tie_keeps_current() {
+        let merged = merge_records(
+            vec![timed_record("a", "current", 10)],
+            vec![timed_record("a", "restored", 10)],
+        );
+        assert_eq!(merged.len(), 1);
+        assert_eq!(merged[0].text, "current");
+    }
+
+    fn create_backup_test_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "rustler_history_backup_{}_{}",
+            name,
+            std::process::id()
+        ));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn write_history(path: &Path, records: Vec<TranscriptionRecord>) {
+        history_store_at(path.to_path_buf())
+            .save(&HistoryStorage { records })
+            .unwrap();
+    }
+
+    #[test]
+    fn test_backup_and_restore_replace() {
+        let dir = create_backup_test_dir("replace");
+        let history = dir.join("history.json");
+        let backup = dir.join("backups").join("history-backup.json");
+        write_history(&history, vec![timed_record("a", "a", 10)]);
+
+        assert_eq!(backup_history_file(&history, &backup).unwrap(), 1);
+
+        write_history(
+            &history,
+            vec![timed_record("b", "b", 20), timed_record("c", "c", 30)],
+        );
+        assert_eq!(restore_history_file(&history, &backup, false).unwrap(), 1);
+
+        let restored = history_store_at(history.clone()).load_strict().unwrap();
+        assert_eq!(ids(&restored.records), vec!["a"]);
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_restore_merge_deduplicates() {
+        let dir = create_backup_test_dir("merge");
+        let history = dir.join("history.json");
+        let backup = dir.join("backup.json");
+        write_history(
+            &backup,
+            vec![timed_record("b", "old b", 5), timed_record("a", "a", 1)],
+        );
+        write_history(&history, vec![timed_record("b", "new b", 20)]);
+
+        assert_eq!(restore_history_file(&history, &backup, true).unwrap(), 2);
+
+        let merged = history_store_at(history.clone()).load_strict().unwrap();
+        assert_eq!(ids(&merged.records), vec!["b", "a"]);
+        assert_eq!(merged.records[0].text, "new b");
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_restore_rejects_invalid_backup() {
+        let dir = create_backup_test_dir("invalid");
+        let history = dir.join("history.json");
+        let backup = dir.join("backup.json");
+        write_history(&history, vec![timed_record("a", "a", 10)]);
+        fs::write(&backup, r#"{ "records": "not a list" }"#).unwrap();
+
+        assert!(restore_history_file(&history, &backup, false).is_err());
+        assert!(restore_history_file(&history, &dir.join("missing.json"), true).is_err());
+
+        // The current history is untouched
+        let current = history_store_at(history.clone()).load_strict().unwrap();
+        assert_eq!(ids(&current.records), vec!["a"]);
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_cap_segments() {
+        let (segments, truncated) = cap_segments(vec![segment(0), segment(1)]);
+        assert_eq!(segments.len(), 2);
+        assert!(!truncated);
+
+        let many: Vec<HistorySegment> = (0..MAX_STORED_SEGMENTS as i64 + 5).map(segment).collect();
+        let (segments, truncated) = cap_segments(many);
+        assert_eq!(segments.len(), MAX_STORED_SEGMENTS);
+        assert!(truncated);
+    }
 }
@@ -1,8 +1,19 @@
+use crate::commands::settings::current_settings;
+use crate::hotkey::conflict::{
+    check_conflict, HotkeyConflictResult, ShortcutProbe, SYSTEM_SHORTCUTS,
+};
+use crate::hotkey::{HotkeyAction, HotkeyDebouncer};
+use crate::AppState;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
 use tauri::{AppHandle, Emitter, Manager};
-use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutState};
+use tauri_plugin_global_shortcut::{GlobalShortcut, GlobalShortcutExt, Shortcut};
 
 #[cfg(target_os = "linux")]
-use crate::hotkey::wayland::{clear_stored_shortcuts, reset_portal_state, WaylandHotkeyManager};
+use crate::hotkey::wayland::{
+    clear_stored_shortcuts, probe_portal, reset_portal_state, ShortcutBinding, WaylandHotkeyManager,
+};
 #[cfg(target_os = "linux")]
 use std::sync::OnceLock;
 
@@ -26,109 +37,293 @@ fn is_wayland() -> bool {
     false
 }
 
-/// Registers a global hotkey for triggering recording
+/// Emits the event of a hotkey action to the main window
+pub(crate) fn emit_hotkey_event(app: &AppHandle, action: HotkeyAction) {
+    if let Some(window) = app.get_webview_window("main") {
+        log::info!("Emitting {} event to window", action.event());
+        let _ = window.emit(action.event(), ());
+    } else {
+        log::warn!("Could not find main window!");
+    }
+}
+
+/// Collects the hotkeys to register: every binding from settings, with the
+/// toggle bound to `shortcut`
+fn collect_bindings(shortcut: String) -> HashMap<HotkeyAction, String> {
+    let mut bindings = current_settings().unwrap_or_default().hotkey_bindings();
+    bindings.insert(HotkeyAction::ToggleRecording, shortcut);
+    bindings
+}
+
+/// Minimum time between two triggers of the same hotkey, from settings
+fn hotkey_debounce() -> Duration {
+    Duration::from_millis(current_settings().unwrap_or_default().hotkey_debounce_ms)
+}
+
+/// Records the hotkeys bound through the Wayland portal in the app state's hotkey manager
+#[cfg(target_os = "linux")]
+fn track_bindings(app: &AppHandle, bindings: &HashMap<HotkeyAction, String>) {
+    let state = app.state::<Arc<AppState>>();
+    let _ = state.hotkey_manager.unregister();
+    for (action, shortcut) in bindings {
+        state.hotkey_manager.track_action(*action, shortcut.clone());
+    }
+}
+
+/// Registers the global hotkeys of all actions
 ///
 /// # Arguments
 /// * `app` - Tauri app handle
-/// * `shortcut` - The keyboard shortcut string (e.g., "Alt+R", "Ctrl+Shift+Space")
+/// * `shortcut` - The keyboard shortcut for toggling recording (e.g., "Alt+R", "Ctrl+Shift+Space");
+///   the other actions use the bindings from settings
 ///
 /// # Returns
-/// * `Ok(())` if the hotkey was registered successfully
+/// * `Ok(())` if the hotkeys were registered successfully
 /// * `Err(String)` if registration failed
 #[tauri::command]
 pub async fn register_hotkey(app: AppHandle, shortcut: String) -> Result<(), String> {
     log::info!("Registering hotkey: {}", shortcut);
+    let bindings = collect_bindings(shortcut);
 
     // On Linux with Wayland, use xdg-desktop-portal
     #[cfg(target_os = "linux")]
     if is_wayland() {
         log::info!("Detected Wayland session, using xdg-desktop-portal for global shortcuts");
-        register_hotkey_wayland(app, shortcut).await?;
+        register_hotkeys_wayland(app, bindings).await?;
         return Ok(());
     }
 
     // Use tauri-plugin-global-shortcut for X11/macOS/Windows
-    register_hotkey_native(app, shortcut)
+    register_hotkeys_native(app, bindings)
 }
 
-/// Register hotkey using Wayland portal (Linux only)
-/// Returns the actual trigger description from the GNOME dialog if available
+/// Register hotkeys using Wayland portal (Linux only)
+/// Returns the actual trigger description of the toggle from the GNOME dialog if available
 #[cfg(target_os = "linux")]
-async fn register_hotkey_wayland(
+async fn register_hotkeys_wayland(
     app: AppHandle,
-    shortcut: String,
+    bindings: HashMap<HotkeyAction, String>,
 ) -> Result<Option<String>, String> {
     let manager = get_wayland_manager();
 
-    // Create callback that emits event to frontend
-    let callback = move || {
-        log::info!("Wayland hotkey triggered!");
-        if let Some(window) = app.get_webview_window("main") {
-            log::info!("Emitting hotkey-triggered event to window");
-            let _ = window.emit("hotkey-triggered", ());
-        } else {
-            log::warn!("Could not find main window!");
+    let shortcut_bindings: Vec<ShortcutBinding> = HotkeyAction::ALL
+        .into_iter()
+        .filter_map(|action| {
+            bindings.get(&action).map(|shortcut| ShortcutBinding {
+                id: action.shortcut_id().to_string(),
+                description: action.description().to_string(),
+                preferred_trigger: shortcut.clone(),
+            })
+        })
+        .collect();
+
+    // Create callback that emits the action's event to frontend
+    let app_handle = app.clone();
+    let debounce = hotkey_debounce();
+    let debouncers: HashMap<HotkeyAction, HotkeyDebouncer> = bindings
+        .keys()
+        .map(|action| (*action, HotkeyDebouncer::new(debounce)))
+        .collect();
+    let callback = move |shortcut_id: &str| {
+        log::info!("Wayland hotkey '{}' triggered!", shortcut_id);
+        let Some(action) = HotkeyAction::from_shortcut_id(shortcut_id) else {
+            return;
+        };
+        if debouncers.get(&action).is_some_and(|d| !d.try_trigger()) {
+            log::info!(
+                "Ignoring repeated trigger of {:?} within debounce window",
+                action
+            );
+            return;
         }
+        emit_hotkey_event(&app_handle, action);
     };
 
-    // Register the shortcut — returns the actual trigger from the GNOME dialog
-    let actual_trigger = manager
-        .register("record-toggle", "Toggle Recording", &shortcut, callback)
-        .await?;
+    // Register all shortcuts in one dialog — returns the actual triggers from the GNOME dialog
+    let mut actual_triggers = manager.register(&shortcut_bindings, callback).await?;
+    track_bindings(&app, &bindings);
 
-    log::info!("Wayland hotkey registered successfully: {}", shortcut);
-    Ok(actual_trigger)
+    log::info!(
+        "Wayland hotkeys registered successfully: {} shortcut(s)",
+        shortcut_bindings.len()
+    );
+    Ok(actual_triggers.remove(HotkeyAction::ToggleRecording.shortcut_id()))
 }
 
-/// Register hotkey using native tauri plugin (X11/macOS/Windows)
-fn register_hotkey_native(app: AppHandle, shortcut: String) -> Result<(), String> {
-    let shortcut_manager = app.global_shortcut();
+/// Register hotkeys through the app state's hotkey manager, which grabs them
+/// with tauri-plugin-global-shortcut (X11/macOS/Windows)
+///
+/// Registration is all or nothing: if a binding fails, the ones registered
+/// before it are released again.
+fn register_hotkeys_native(
+    app: AppHandle,
+    bindings: HashMap<HotkeyAction, String>,
+) -> Result<(), String> {
+    let state = app.state::<Arc<AppState>>();
 
     // Unregister all existing shortcuts first
-    shortcut_manager
-        .unregister_all()
-        .map_err(|e| format!("Failed to unregister existing hotkeys: {}", e))?;
+    state
+        .hotkey_manager
+        .unregister()
+        .map_err(|e| format!("Failed to unregister existing hotkeys: {:#}", e))?;
 
-    // Parse the shortcut string
-    let parsed_shortcut: Shortcut = shortcut
-        .parse()
-        .map_err(|e| format!("Invalid shortcut format '{}': {}", shortcut, e))?;
+    let debounce = hotkey_debounce();
 
-    // Clone app handle for the callback
-    let app_handle = app.clone();
+    for (action, shortcut) in &bindings {
+        // Clone app handle for the callback
+        let app_handle = app.clone();
+        let action = *action;
+        let debouncer = HotkeyDebouncer::new(debounce);
 
-    // Register the new shortcut
-    shortcut_manager
-        .on_shortcut(parsed_shortcut, move |_app, shortcut, event| {
-            log::info!(
-                "Shortcut callback fired! shortcut={:?}, state={:?}",
-                shortcut,
-                event.state
-            );
-            // Only trigger on key press, not release
-            if event.state == ShortcutState::Pressed {
-                log::info!("Hotkey triggered (Pressed)!");
-                // Emit event to frontend
-                if let Some(window) = app_handle.get_webview_window("main") {
-                    log::info!("Emitting hotkey-triggered event to window");
-                    let _ = window.emit("hotkey-triggered", ());
-                } else {
-                    log::warn!("Could not find main window!");
-                }
+        let registered =
+            state
+                .hotkey_manager
+                .register_action(action, shortcut.clone(), move || {
+                    if !debouncer.try_trigger() {
+                        log::info!(
+                            "Ignoring repeated trigger of {:?} within debounce window",
+                            action
+                        );
+                        return;
+                    }
+                    log::info!("Hotkey for {:?} triggered (Pressed)!", action);
+                    emit_hotkey_event(&app_handle, action);
+                });
+
+        if let Err(e) = registered {
+            // Don't leave the bindings registered so far active on their own
+            if let Err(cleanup) = state.hotkey_manager.unregister() {
+                log::warn!(
+                    "Failed to release partially registered hotkeys: {:#}",
+                    cleanup
+                );
             }
-        })
-        .map_err(|e| format!("Failed to register hotkey: {}", e))?;
+            return Err(format!("{:#}", e));
+        }
+
+        log::info!("Native hotkey registered successfully: {}", shortcut);
+    }
 
-    log::info!("Native hotkey registered successfully: {}", shortcut);
     Ok(())
 }
 
+/// Unregisters the global hotkey of a single action, keeping the others
+///
+/// # Arguments
+/// * `app` - Tauri app handle
+/// * `action` - Action whose hotkey is removed
+///
+/// # Returns
+/// * `Ok(())` if the hotkey was unregistered or the action had none
+/// * `Err(String)` if unregistration failed
+#[tauri::command]
+pub async fn unregister_hotkey(app: AppHandle, action: HotkeyAction) -> Result<(), String> {
+    log::info!("Unregistering hotkey for {:?}", action);
+
+    // Releases native hotkeys; Wayland bindings are only tracked by the manager
+    let state = app.state::<Arc<AppState>>();
+    let removed = state
+        .hotkey_manager
+        .unregister_action(action)
+        .map_err(|e| format!("{:#}", e))?;
+    if removed.is_none() {
+        return Ok(());
+    }
+
+    #[cfg(target_os = "linux")]
+    if is_wayland() {
+        get_wayland_manager().disable(action.shortcut_id());
+    }
+
+    log::info!("Hotkey for {:?} unregistered", action);
+    Ok(())
+}
+
+/// Probes shortcuts through the global shortcut plugin, which grabs the key
+/// with the platform API (e.g., `XGrabKey` on X11, `RegisterHotKey` on Windows)
+struct PluginProbe<'a> {
+    shortcuts: &'a GlobalShortcut<tauri::Wry>,
+}
+
+impl ShortcutProbe for PluginProbe<'_> {
+    fn probe(&self, shortcut: Shortcut) -> Result<(), String> {
+        self.shortcuts
+            .register(shortcut)
+            .map_err(|e| e.to_string())?;
+        self.shortcuts
+            .unregister(shortcut)
+            .map_err(|e| e.to_string())
+    }
+}
+
+/// Probe used where shortcuts can't be grabbed in test mode (Wayland portal)
+struct UnavailableProbe;
+
+impl ShortcutProbe for UnavailableProbe {
+    fn probe(&self, _shortcut: Shortcut) -> Result<(), String> {
+        Ok(())
+    }
+}
+
+/// Checks whether a shortcut is already taken before registering it
+///
+/// Known system shortcuts and our own hotkeys are reported with a description.
+/// Other applications are detected by registering the shortcut in test mode and
+/// unregistering it right away; this isn't possible through the Wayland portal.
+///
+/// # Arguments
+/// * `app` - Tauri app handle
+/// * `shortcut` - The keyboard shortcut string (e.g., "Alt+R", "Ctrl+Shift+Space")
+///
+/// # Returns
+/// * `Ok(HotkeyConflictResult)` with `conflict` set if the shortcut is taken
+/// * `Err(String)` if the shortcut cannot be parsed
+#[tauri::command]
+pub fn check_hotkey_conflict(
+    app: AppHandle,
+    shortcut: String,
+) -> Result<HotkeyConflictResult, String> {
+    let own_bindings = app
+        .state::<Arc<AppState>>()
+        .hotkey_manager
+        .get_current_all();
+
+    if is_wayland() {
+        return check_conflict(
+            &shortcut,
+            &own_bindings,
+            SYSTEM_SHORTCUTS,
+            &UnavailableProbe,
+        );
+    }
+
+    let probe = PluginProbe {
+        shortcuts: app.global_shortcut(),
+    };
+    check_conflict(&shortcut, &own_bindings, SYSTEM_SHORTCUTS, &probe)
+}
+
 /// Check if we're running on Wayland (exposed to frontend)
 #[tauri::command]
 pub fn is_wayland_session() -> bool {
     is_wayland()
 }
 
+/// Checks whether global hotkeys can be registered, so the settings UI can
+/// disable hotkey configuration up front and point to the in-app button
+///
+/// On Wayland this probes the GlobalShortcuts portal with a short timeout,
+/// without binding anything. Elsewhere global hotkeys are always available.
+#[tauri::command]
+pub async fn probe_global_shortcuts_available() -> bool {
+    #[cfg(target_os = "linux")]
+    if is_wayland() {
+        return probe_portal().await;
+    }
+
+    true
+}
+
 /// Reset Wayland portal state and re-register hotkey
 /// This forces the xdg-desktop-portal dialog to appear again.
 /// Returns the actual trigger description from the GNOME dialog if available.
@@ -144,7 +339,7 @@ pub async fn reset_wayland_hotkey(
         // Clear stored shortcuts from dconf so GNOME shows the configuration
         // dialog again. Without this, bind_shortcuts auto-approves silently.
         clear_stored_shortcuts("com.rustler.desktop");
-        return register_hotkey_wayland(app, shortcut).await;
+        return register_hotkeys_wayland(app, collect_bindings(shortcut)).await;
     }
 
     // On non-Wayland, just do normal registration
@@ -162,15 +357,16 @@ pub async fn unregister_hotkeys(app: AppHandle) -> Result<(), String> {
     if is_wayland() {
         let manager = get_wayland_manager();
         manager.unregister();
+        let _ = app.state::<Arc<AppState>>().hotkey_manager.unregister();
         log::info!("Wayland hotkeys unregistered");
         return Ok(());
     }
 
     // Native unregister
-    let shortcut_manager = app.global_shortcut();
-    shortcut_manager
-        .unregister_all()
-        .map_err(|e| format!("Failed to unregister hotkeys: {}", e))?;
+    app.state::<Arc<AppState>>()
+        .hotkey_manager
+        .unregister()
+        .map_err(|e| format!("Failed to unregister hotkeys: {:#}", e))?;
 
     log::info!("All hotkeys unregistered");
     Ok(())
@@ -18,3 +18,9 @@ pub mod clipboard;
 
 /// Commands for transcription history
 pub mod history;
+
+/// Commands for subtitle export
+pub mod subtitles;
+
+/// Command running the whole dictation pipeline in the backend
+pub mod dictation;
@@ -1,6 +1,15 @@
+use crate::commands::settings::{current_settings, save_settings, CustomModelEntry};
+use crate::models::downloader::{
+    is_builtin_model, is_valid_model_id, DownloadHandle, ModelDownloader, BUILTIN_MODELS,
+};
+use crate::models::progress::DownloadProgress;
+use crate::whisper::context::WhisperContext;
+use crate::AppState;
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
-use tauri::{Emitter, Window};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, State, Window};
 
 /// Represents a Whisper model
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -13,6 +22,8 @@ pub struct WhisperModel {
     pub size: u64,
     /// Whether the model is downloaded locally
     pub downloaded: bool,
+    /// Whether the model was added by the user rather than built in
+    pub is_custom: bool,
 }
 
 /// Returns a list of available Whisper models
@@ -24,56 +35,154 @@ pub struct WhisperModel {
 pub async fn get_available_models() -> Result<Vec<WhisperModel>, String> {
     log::info!("Getting available models");
 
-    let downloader = crate::models::downloader::ModelDownloader::new();
-
-    let models = vec![
-        WhisperModel {
-            id: "tiny".to_string(),
-            name: "Tiny (75 MB)".to_string(),
-            size: 75,
-            downloaded: downloader.is_downloaded("tiny"),
-        },
-        WhisperModel {
-            id: "base".to_string(),
-            name: "Base (142 MB)".to_string(),
-            size: 142,
-            downloaded: downloader.is_downloaded("base"),
-        },
-        WhisperModel {
-            id: "small".to_string(),
-            name: "Small (466 MB)".to_string(),
-            size: 466,
-            downloaded: downloader.is_downloaded("small"),
-        },
-        WhisperModel {
-            id: "medium".to_string(),
-            name: "Medium (1.5 GB)".to_string(),
-            size: 1500,
-            downloaded: downloader.is_downloaded("medium"),
-        },
-        WhisperModel {
-            id: "large".to_string(),
-            name: "Large (2.9 GB)".to_string(),
-            size: 2900,
-            downloaded: downloader.is_downloaded("large"),
-        },
-        WhisperModel {
-            id: "turbo".to_string(),
-            name: "Turbo (809 MB)".to_string(),
-            size: 809,
-            downloaded: downloader.is_downloaded("turbo"),
-        },
-    ];
-
-    Ok(models)
+    let custom_models = current_settings()
+        .map(|s| s.custom_models)
+        .unwrap_or_default();
+    let downloader = ModelDownloader::with_custom_models(&custom_models);
+
+    Ok(model_catalog(&custom_models, &downloader))
+}
+
+/// Builds the merged model catalog: built-in models, custom models from
+/// settings and models imported from local files
+///
+/// # Arguments
+/// * `custom_models` - Custom models configured in settings
+/// * `downloader` - Downloader used to check which models are present locally
+///
+/// # Returns
+/// Every model that is downloaded or can be downloaded
+pub fn model_catalog(
+    custom_models: &[CustomModelEntry],
+    downloader: &ModelDownloader,
+) -> Vec<WhisperModel> {
+    let mut models: Vec<WhisperModel> = BUILTIN_MODELS
+        .iter()
+        .map(|model| WhisperModel {
+            id: model.id.to_string(),
+            name: model.name.to_string(),
+            size: model.size_mb,
+            downloaded: downloader.is_downloaded(model.id),
+            is_custom: false,
+        })
+        .collect();
+
+    // Custom models configured in settings
+    for entry in custom_models
+        .iter()
+        .filter(|entry| !is_builtin_model(&entry.id))
+    {
+        models.push(WhisperModel {
+            id: entry.id.clone(),
+            name: entry.name.clone(),
+            size: entry.size_mb,
+            downloaded: downloader.is_downloaded(&entry.id),
+            is_custom: true,
+        });
+    }
+
+    // Models imported from local files that aren't listed anywhere else
+    for id in downloader.list_local_model_ids() {
+        if models.iter().any(|m| m.id == id) {
+            continue;
+        }
+        let size = std::fs::metadata(downloader.get_model_path(&id))
+            .map(|m| m.len() / (1024 * 1024))
+            .unwrap_or(0);
+        models.push(WhisperModel {
+            name: id.clone(),
+            id,
+            size,
+            downloaded: true,
+            is_custom: true,
+        });
+    }
+
+    models
 }
 
+/// Minimum time between progress events when the percentage doesn't change,
+/// so speed and ETA stay current on slow connections
+const PROGRESS_EVENT_INTERVAL: Duration = Duration::from_secs(1);
+
 /// Download progress payload
 #[derive(Debug, Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
 struct DownloadProgressPayload {
     model_id: String,
+    /// Whole percentage, -1 if the total size is unknown
     percentage: f64,
+    downloaded_bytes: u64,
+    total_bytes: Option<u64>,
+    bytes_per_sec: f64,
+    eta_secs: Option<f64>,
+}
+
+/// Registers a control handle so a model download or import can be paused or
+/// cancelled
+///
+/// # Returns
+/// * `Ok(DownloadHandle)` with the handle to pass to the downloader
+/// * `Err(String)` if the model is already being downloaded or imported
+fn register_download(state: &AppState, model_id: &str) -> Result<DownloadHandle, String> {
+    let mut downloads = state.downloads.lock();
+    if downloads.contains_key(model_id) {
+        return Err(format!("Model '{}' is already downloading", model_id));
+    }
+    let handle = DownloadHandle::new();
+    downloads.insert(model_id.to_string(), handle.clone());
+    Ok(handle)
+}
+
+/// Returns a progress callback that emits `download-progress` events
+///
+/// Events are throttled to whole percentage changes, or once per interval so
+/// speed and ETA keep updating.
+fn progress_emitter(window: Window, model_id: String) -> impl FnMut(DownloadProgress) {
+    let mut last_reported: i32 = -1;
+    let mut last_emitted: Option<Instant> = None;
+
+    move |progress| {
+        let percentage = progress
+            .fraction()
+            .map_or(-1, |fraction| (fraction * 100.0) as i32);
+        let interval_elapsed = !matches!(
+            last_emitted,
+            Some(at) if at.elapsed() < PROGRESS_EVENT_INTERVAL
+        );
+        if percentage > last_reported || interval_elapsed {
+            last_reported = last_reported.max(percentage);
+            last_emitted = Some(Instant::now());
+            let payload = DownloadProgressPayload {
+                model_id: model_id.clone(),
+                percentage: percentage as f64,
+                downloaded_bytes: progress.downloaded_bytes,
+                total_bytes: progress.total_bytes,
+                bytes_per_sec: progress.bytes_per_sec,
+                eta_secs: progress.eta_secs,
+            };
+            let _ = window.emit("download-progress", payload);
+        }
+    }
+}
+
+/// Drops the control handle of a finished download or import and emits
+/// `download-cancelled` if it ended because it was cancelled
+fn finish_download<T>(
+    state: &AppState,
+    window: &Window,
+    model_id: &str,
+    handle: &DownloadHandle,
+    result: &anyhow::Result<T>,
+) {
+    state.downloads.lock().remove(model_id);
+
+    if result.is_err() && handle.is_cancelled() {
+        let _ = window.emit(
+            "download-cancelled",
+            serde_json::json!({ "modelId": model_id }),
+        );
+    }
 }
 
 /// Downloads a Whisper model from HuggingFace
@@ -81,38 +190,114 @@ struct DownloadProgressPayload {
 /// # Arguments
 /// * `model_id` - ID of the model to download (e.g., "base", "small")
 /// * `window` - Tauri window handle for emitting progress events
+/// * `state` - Application state holding the active download handles
 ///
 /// # Returns
 /// * `Ok(())` if download was successful
-/// * `Err(String)` if download failed
+/// * `Err(String)` if download failed or was cancelled
 #[tauri::command]
-pub async fn download_model(model_id: String, window: Window) -> Result<(), String> {
+pub async fn download_model(
+    model_id: String,
+    window: Window,
+    state: State<'_, Arc<AppState>>,
+) -> Result<(), String> {
     log::info!("Downloading model: {}", model_id);
 
-    let downloader = crate::models::downloader::ModelDownloader::new();
-    let model_id_clone = model_id.clone();
-    let mut last_reported: i32 = -1;
+    let handle = register_download(&state, &model_id)?;
+    let result = ModelDownloader::new()
+        .download(
+            &model_id,
+            Some(handle.clone()),
+            progress_emitter(window.clone(), model_id.clone()),
+        )
+        .await;
 
-    // Download with progress callback (throttled to only emit on whole percentage changes)
-    downloader
-        .download(&model_id, |progress| {
-            let percentage = (progress * 100.0) as i32;
-            if percentage > last_reported {
-                last_reported = percentage;
-                let payload = DownloadProgressPayload {
-                    model_id: model_id_clone.clone(),
-                    percentage: percentage as f64,
-                };
-                let _ = window.emit("download-progress", payload);
-            }
-        })
-        .await
-        .map_err(|e| e.to_string())?;
+    finish_download(&state, &window, &model_id, &handle, &result);
+    result.map_err(|e| e.to_string())?;
 
     log::info!("Model downloaded successfully: {}", model_id);
     Ok(())
 }
 
+/// Looks up the control handle of an in-flight download
+fn get_download_handle(state: &AppState, model_id: &str) -> Result<DownloadHandle, String> {
+    state
+        .downloads
+        .lock()
+        .get(model_id)
+        .cloned()
+        .ok_or_else(|| format!("No download in progress for model '{}'", model_id))
+}
+
+/// Pauses an in-flight model download
+///
+/// # Arguments
+/// * `model_id` - ID of the model being downloaded
+/// * `state` - Application state holding the active download handles
+///
+/// # Returns
+/// * `Ok(())` if the download was paused
+/// * `Err(String)` if no download is in progress for the model
+#[tauri::command]
+pub async fn pause_model_download(
+    model_id: String,
+    state: State<'_, Arc<AppState>>,
+) -> Result<(), String> {
+    log::info!("Pausing download: {}", model_id);
+    get_download_handle(&state, &model_id)?.pause();
+    Ok(())
+}
+
+/// Resumes a paused model download
+///
+/// # Arguments
+/// * `model_id` - ID of the model being downloaded
+/// * `state` - Application state holding the active download handles
+///
+/// # Returns
+/// * `Ok(())` if the download was resumed
+/// * `Err(String)` if no download is in progress for the model
+#[tauri::command]
+pub async fn resume_model_download(
+    model_id: String,
+    state: State<'_, Arc<AppState>>,
+) -> Result<(), String> {
+    log::info!("Resuming download: {}", model_id);
+    get_download_handle(&state, &model_id)?.resume();
+    Ok(())
+}
+
+/// Cancels an in-flight model download
+///
+/// The download task notices the cancellation on its next chunk, removes any
+/// partial file, emits `download-cancelled` and returns a "cancelled" error to
+/// the original caller.
+///
+/// # Arguments
+/// * `model_id` - ID of the model being downloaded
+/// * `state` - Application state holding the active download handles
+///
+/// # Returns
+/// `Ok(())` once cancellation was requested, or right away if the model
+/// isn't downloading (e.g., it finished in the meantime)
+#[tauri::command]
+pub async fn cancel_model_download(
+    model_id: String,
+    state: State<'_, Arc<AppState>>,
+) -> Result<(), String> {
+    match get_download_handle(&state, &model_id) {
+        Ok(handle) => {
+            log::info!("Cancelling download: {}", model_id);
+            handle.cancel();
+        }
+        Err(_) => log::info!(
+            "No download in progress for '{}', nothing to cancel",
+            model_id
+        ),
+    }
+    Ok(())
+}
+
 /// Deletes a downloaded Whisper model
 ///
 /// # Arguments
@@ -125,7 +310,7 @@ pub async fn download_model(model_id: String, window: Window) -> Result<(), Stri
 pub async fn delete_model(model_id: String) -> Result<(), String> {
     log::info!("Deleting model: {}", model_id);
 
-    let downloader = crate::models::downloader::ModelDownloader::new();
+    let downloader = ModelDownloader::new();
     let model_path = downloader.get_model_path(&model_id);
 
     if !model_path.exists() {
@@ -138,11 +323,179 @@ pub async fn delete_model(model_id: String) -> Result<(), String> {
     Ok(())
 }
 
+/// Checks that a custom model can be added next to the existing ones
+///
+/// # Returns
+/// * `Ok(())` if the entry is usable
+/// * `Err(String)` if its ID is invalid, shadows a built-in model or is already taken
+fn check_custom_model_entry(
+    entry: &CustomModelEntry,
+    existing: &[CustomModelEntry],
+) -> Result<(), String> {
+    if !is_valid_model_id(&entry.id) {
+        return Err(format!(
+            "Invalid model ID '{}'. Use letters, digits, '-', '_' and '.'",
+            entry.id
+        ));
+    }
+    if is_builtin_model(&entry.id) {
+        return Err(format!(
+            "Model ID '{}' is already used by a built-in model",
+            entry.id
+        ));
+    }
+    if existing.iter().any(|e| e.id == entry.id) {
+        return Err(format!(
+            "A custom model with ID '{}' already exists",
+            entry.id
+        ));
+    }
+    if entry.url_or_path.trim().is_empty() {
+        return Err("A custom model needs a URL or a local path".to_string());
+    }
+    Ok(())
+}
+
+/// Adds a custom model (a download URL or a local path) to settings
+///
+/// # Arguments
+/// * `entry` - Model to add
+///
+/// # Returns
+/// * `Ok(())` if the model was added and settings were saved
+/// * `Err(String)` if the entry is invalid or clashes with an existing model
+#[tauri::command]
+pub async fn add_custom_model(app: AppHandle, entry: CustomModelEntry) -> Result<(), String> {
+    log::info!("Adding custom model '{}'", entry.id);
+
+    let mut settings = current_settings()?;
+    check_custom_model_entry(&entry, &settings.custom_models)?;
+    settings.custom_models.push(entry);
+
+    save_settings(app, settings).await
+}
+
+/// Imports a local Whisper model file into the models directory
+///
+/// The copy reports `download-progress` events and can be paused or cancelled
+/// like a download. The copied file is loaded once to make sure it is a usable
+/// model; files that fail to load are removed again.
+///
+/// # Arguments
+/// * `source_path` - Path of the `.bin` model file to import
+/// * `model_id` - ID to store the model under
+/// * `window` - Tauri window handle for emitting progress events
+/// * `state` - Application state holding the active download handles
+///
+/// # Returns
+/// * `Ok(())` if the model was imported
+/// * `Err(String)` if the source is invalid, is not a loadable Whisper model
+///   or a model with that ID already exists
+#[tauri::command]
+pub async fn import_model(
+    source_path: String,
+    model_id: String,
+    window: Window,
+    state: State<'_, Arc<AppState>>,
+) -> Result<(), String> {
+    log::info!("Importing model '{}' from {}", model_id, source_path);
+
+    if is_builtin_model(&model_id) {
+        log::warn!(
+            "Importing '{}' under the ID of a built-in model; it will be used in place of the download",
+            model_id
+        );
+    }
+
+    let handle = register_download(&state, &model_id)?;
+    let result = ModelDownloader::new()
+        .import(
+            Path::new(&source_path),
+            &model_id,
+            Some(handle.clone()),
+            progress_emitter(window.clone(), model_id.clone()),
+        )
+        .await;
+
+    finish_download(&state, &window, &model_id, &handle, &result);
+    let model_path = result.map_err(|e| format!("Failed to import model: {}", e))?;
+
+    // Loading a model is slow and blocking, so keep it off the async runtime
+    let check_path = model_path.clone();
+    let loaded = tokio::task::spawn_blocking(move || WhisperContext::new(check_path).map(drop))
+        .await
+        .map_err(|e| format!("Failed to validate model: {}", e))?;
+
+    if let Err(e) = loaded {
+        let _ = std::fs::remove_file(&model_path);
+        return Err(format!(
+            "'{}' is not a valid Whisper model: {}",
+            source_path, e
+        ));
+    }
+
+    Ok(())
+}
+
+/// Verifies a downloaded model against its known SHA-256 checksum
+///
+/// # Arguments
+/// * `model_id` - ID of the model to verify
+///
+/// # Returns
+/// * `Ok(true)` if the model file is intact, `Ok(false)` if it is corrupted
+/// * `Err(String)` if the model is not downloaded or has no known checksum
+#[tauri::command]
+pub async fn verify_model(model_id: String) -> Result<bool, String> {
+    log::info!("Verifying model: {}", model_id);
+
+    ModelDownloader::new()
+        .verify_model(&model_id)
+        .await
+        .map_err(|e| format!("Failed to verify model: {}", e))
+}
+
 /// Returns the path to the models directory
 ///
 /// # Returns
 /// The absolute path to the directory where models are stored
 #[tauri::command]
 pub async fn get_models_dir() -> PathBuf {
-    crate::models::downloader::ModelDownloader::new().get_models_dir()
+    ModelDownloader::new().get_models_dir()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(id: &str) -> CustomModelEntry {
+        CustomModelEntry {
+            id: id.to_string(),
+            name: id.to_string(),
+            url_or_path: format!("https://example.com/ggml-{}.bin", id),
+            size_mb: 100,
+        }
+    }
+
+    #[test]
+    fn test_custom_model_entry_accepted() {
+        assert!(check_custom_model_entry(&entry("my-finetune"), &[]).is_ok());
+    }
+
+    #[test]
+    fn test_custom_model_cannot_shadow_builtin() {
+        let err = check_custom_model_entry(&entry("base"), &[]).unwrap_err();
+        assert!(err.contains("built-in"));
+    }
+
+    #[test]
+    fn test_custom_model_rejects_duplicates_and_invalid_ids() {
+        let existing = vec![entry("my-finetune")];
+        assert!(check_custom_model_entry(&entry("my-finetune"), &existing).is_err());
+        assert!(check_custom_model_entry(&entry("../evil"), &[]).is_err());
+
+        let mut no_source = entry("other");
+        no_source.url_or_path = "  ".to_string();
+        assert!(check_custom_model_entry(&no_source, &[]).is_err());
+    }
 }
@@ -1,12 +1,152 @@
+use crate::audio::filter::{normalize_peak, reduce_noise};
+use crate::audio::recorder::LiveAudioFeed;
+use crate::audio::ringbuffer::PreRollListener;
+use crate::audio::wav::{write_wav, SAMPLE_RATE};
+use crate::commands::settings::{current_settings, Settings};
+use crate::commands::transcription::spawn_live_transcription;
+use crate::tray::{update_tray_menu, TRAY_ID};
+use crate::whisper::live::{LIVE_INTERVAL_SECS, LIVE_WINDOW_SECS};
 use crate::{audio::recorder::AudioRecorder, AppState};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::AtomicBool;
+use std::sync::mpsc::sync_channel;
 use std::sync::Arc;
+use std::time::{Duration, SystemTime};
 use tauri::{image::Image, AppHandle, Emitter, State};
 use tauri_plugin_notification::NotificationExt;
 
-/// Tray icon ID used to look up the tray for icon swaps
-const TRAY_ID: &str = "main-tray";
+/// File name prefix of recordings written to the temp directory
+const RECORDING_FILE_PREFIX: &str = "whispr_recording_";
 
-/// Swap the system tray icon to indicate recording state
+/// Recordings older than this are removed by the startup sweep and by
+/// `cleanup_temp_files` unless it is given another age (1 hour)
+pub const STALE_RECORDING_AGE: Duration = Duration::from_secs(60 * 60);
+
+/// Minimum number of captured samples for a recording to be usable.
+/// At 16kHz, this is 100 ms (1600 samples).
+const MIN_CAPTURE_SAMPLES: usize = 1600;
+
+/// Upper bound for the pre-roll length, keeping the idle buffer small
+pub const MAX_PRE_ROLL_MS: u32 = 2000;
+
+/// Starts, restarts or stops the pre-roll idle stream to match `pre_roll_ms` in settings
+///
+/// Pre-roll is off by default so the microphone is only open while recording
+/// unless the user explicitly opts in.
+pub fn sync_pre_roll(state: &AppState) {
+    let pre_roll_ms = current_settings()
+        .map(|s| s.pre_roll_ms.min(MAX_PRE_ROLL_MS))
+        .unwrap_or(0);
+
+    let mut pre_roll = state.pre_roll.lock();
+    let up_to_date = match pre_roll.as_ref() {
+        Some(listener) => listener.duration_ms() == pre_roll_ms,
+        None => pre_roll_ms == 0,
+    };
+    if up_to_date {
+        return;
+    }
+
+    // Dropping the listener stops its stream
+    *pre_roll = None;
+
+    if pre_roll_ms > 0 {
+        match PreRollListener::start(pre_roll_ms) {
+            Ok(listener) => *pre_roll = Some(listener),
+            Err(e) => log::warn!("Failed to start pre-roll listener: {}", e),
+        }
+    }
+}
+
+/// Returns true if the capture is empty or too short to contain any speech
+fn is_capture_too_short(samples: &[f32]) -> bool {
+    samples.len() < MIN_CAPTURE_SAMPLES
+}
+
+/// Returns true if the file name matches a recording written by `stop_recording`
+fn is_recording_file_name(name: &str) -> bool {
+    name.starts_with(RECORDING_FILE_PREFIX) && name.ends_with(".wav")
+}
+
+/// Removes recordings in `dir` whose last modification is older than `max_age`
+///
+/// Only files named like `whispr_recording_*.wav` are considered, so other
+/// applications' temp files are never touched.
+///
+/// # Arguments
+/// * `dir` - Directory to sweep (normally the system temp directory)
+/// * `max_age` - Minimum age of a recording before it is deleted
+///
+/// # Returns
+/// The number of files that were deleted
+pub fn sweep_stale_recordings(dir: &Path, max_age: Duration) -> usize {
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) => {
+            log::warn!("Failed to read temp directory {:?}: {}", dir, e);
+            return 0;
+        }
+    };
+
+    let now = SystemTime::now();
+    let mut removed = 0;
+
+    for entry in entries.flatten() {
+        let name = entry.file_name();
+        if !is_recording_file_name(&name.to_string_lossy()) {
+            continue;
+        }
+
+        let is_stale = entry
+            .metadata()
+            .and_then(|m| m.modified())
+            .map(|modified| now.duration_since(modified).unwrap_or_default() > max_age)
+            .unwrap_or(false);
+
+        if is_stale {
+            match std::fs::remove_file(entry.path()) {
+                Ok(()) => removed += 1,
+                Err(e) => log::warn!("Failed to remove stale recording {:?}: {}", name, e),
+            }
+        }
+    }
+
+    if removed > 0 {
+        log::info!("Removed {} stale recording(s) from {:?}", removed, dir);
+    }
+
+    removed
+}
+
+/// Directory recordings are written to: `temp_dir` from settings, or the
+/// system temp directory if unset
+pub fn recordings_dir(settings: &Settings) -> PathBuf {
+    match settings.temp_dir.as_deref().map(str::trim) {
+        Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+        _ => std::env::temp_dir(),
+    }
+}
+
+/// Deletes recordings left behind in the recordings directory
+///
+/// # Arguments
+/// * `max_age_secs` - Minimum age of a recording before it is deleted, in
+///   seconds; defaults to one hour
+///
+/// # Returns
+/// * `Ok(u32)` with the number of recordings deleted
+/// * `Err(String)` if the settings could not be loaded
+#[tauri::command]
+pub fn cleanup_temp_files(max_age_secs: Option<u64>) -> Result<u32, String> {
+    let settings = current_settings()?;
+    let max_age = max_age_secs
+        .map(Duration::from_secs)
+        .unwrap_or(STALE_RECORDING_AGE);
+    let removed = sweep_stale_recordings(&recordings_dir(&settings), max_age);
+    Ok(removed as u32)
+}
+
+/// Swap the system tray icon and menu to indicate recording state
 fn set_tray_recording(app: &AppHandle, recording: bool) {
     if let Some(tray) = app.tray_by_id(TRAY_ID) {
         let icon_bytes: &[u8] = if recording {
@@ -18,6 +158,7 @@ fn set_tray_recording(app: &AppHandle, recording: bool) {
             let _ = tray.set_icon(Some(icon));
         }
     }
+    update_tray_menu(app, recording);
 }
 
 /// Starts audio recording
@@ -42,9 +183,41 @@ pub async fn start_recording(
         return Err("Recording already in progress".to_string());
     }
 
+    // Pick up pre-roll setting changes, then grab the audio from just before the hotkey press
+    sync_pre_roll(&state);
+    let pre_roll = state
+        .pre_roll
+        .lock()
+        .as_ref()
+        .map(PreRollListener::snapshot)
+        .unwrap_or_default();
+
+    let settings = current_settings().unwrap_or_default();
+
+    // Transcribe the latest audio in the background while recording, if enabled.
+    // The channel holds a single window, so a slow machine skips passes instead
+    // of falling behind.
+    let live = settings.live_transcription.then(|| {
+        let (sender, windows) = sync_channel(1);
+        let stopped = Arc::new(AtomicBool::new(false));
+        spawn_live_transcription(app.clone(), windows, Arc::clone(&stopped));
+        LiveAudioFeed {
+            sender,
+            interval: (LIVE_INTERVAL_SECS * SAMPLE_RATE) as usize,
+            window: (LIVE_WINDOW_SECS * SAMPLE_RATE) as usize,
+            stopped,
+        }
+    });
+
     // Start recording and get handle
-    let handle = AudioRecorder::start_recording()
-        .map_err(|e| format!("Failed to start recording: {}", e))?;
+    let handle = AudioRecorder::start_recording(
+        &settings.input_device_priority,
+        pre_roll,
+        settings.fill_xrun_gaps,
+        live,
+    )
+    .map_err(|e| format!("Failed to start recording: {}", e))?;
+    let device_name = handle.device_name();
 
     *recording = Some(handle);
 
@@ -62,7 +235,7 @@ pub async fn start_recording(
     // Emit recording status to all windows
     let _ = app.emit(
         "recording-status",
-        serde_json::json!({ "isRecording": true }),
+        serde_json::json!({ "isRecording": true, "device": device_name }),
     );
 
     log::info!("Audio recording started successfully");
@@ -82,6 +255,18 @@ pub async fn stop_recording(
     app: AppHandle,
     state: State<'_, Arc<AppState>>,
 ) -> Result<String, String> {
+    stop_and_save_recording(&app, &state)
+}
+
+/// Stops the recording in progress and writes it to a WAV file in the temp directory
+///
+/// Shared by `stop_recording` and the dictation pipeline. Emits `recording-status`,
+/// plus `recording-quality-warning` or `recording-error` when something is off.
+///
+/// # Returns
+/// * `Ok(String)` with the path to the recorded audio file
+/// * `Err(String)` if no recording is in progress or stopping failed
+pub fn stop_and_save_recording(app: &AppHandle, state: &AppState) -> Result<String, String> {
     log::info!("Stopping audio recording");
 
     let mut recording = state.recording.lock();
@@ -92,7 +277,7 @@ pub async fn stop_recording(
         .ok_or_else(|| "No recording in progress".to_string())?;
 
     // Swap tray icon back to normal
-    set_tray_recording(&app, false);
+    set_tray_recording(app, false);
 
     // Send system notification
     let _ = app
@@ -109,42 +294,212 @@ pub async fn stop_recording(
     );
 
     // Stop recording and get audio data
-    let audio_data = handle
+    let settings = current_settings().unwrap_or_default();
+    let device_name = handle.device_name();
+    let xruns = handle.xruns();
+    let mut audio_data = handle
         .stop()
         .map_err(|e| format!("Failed to stop recording: {}", e))?;
 
+    // Gaps between audio callbacks make recordings sound choppy; let the user know why
+    if xruns > 0 {
+        log::warn!(
+            "{} audio callback gaps (xruns) while recording from '{}'",
+            xruns,
+            device_name
+        );
+        let _ = app.emit(
+            "recording-quality-warning",
+            serde_json::json!({ "xruns": xruns, "device": device_name }),
+        );
+    }
+
+    // An empty capture usually means the audio source was suspended
+    if is_capture_too_short(&audio_data) {
+        log::warn!(
+            "Only {} samples captured from device '{}' (16kHz mono), skipping WAV write",
+            audio_data.len(),
+            device_name
+        );
+        let message = format!("No audio was captured from {}", device_name);
+        let _ = app.emit("recording-error", serde_json::json!({ "message": message }));
+        return Err(message);
+    }
+
+    // Filter out hum and background noise before Whisper hears it, then bring
+    // quiet microphones up to a healthy level, if enabled. Normalizing last
+    // keeps hum from setting the peak.
+    if settings.noise_reduction {
+        reduce_noise(&mut audio_data);
+    }
+    if settings.auto_gain {
+        let gain = normalize_peak(&mut audio_data);
+        log::debug!("Normalized recording with gain {:.2}", gain);
+    }
+
     // Save audio data to temporary file
-    let temp_dir = std::env::temp_dir();
+    let temp_dir = recordings_dir(&settings);
+    std::fs::create_dir_all(&temp_dir).map_err(|e| {
+        format!(
+            "Failed to create recordings directory '{}': {}",
+            temp_dir.display(),
+            e
+        )
+    })?;
     let timestamp = std::time::SystemTime::now()
         .duration_since(std::time::UNIX_EPOCH)
         .unwrap()
         .as_secs();
-    let audio_path = temp_dir.join(format!("whispr_recording_{}.wav", timestamp));
-
-    // Write WAV file
-    let spec = hound::WavSpec {
-        channels: 1,
-        sample_rate: 16000,
-        bits_per_sample: 16,
-        sample_format: hound::SampleFormat::Int,
-    };
+    let audio_path = temp_dir.join(format!("{}{}.wav", RECORDING_FILE_PREFIX, timestamp));
 
-    let mut writer = hound::WavWriter::create(&audio_path, spec)
-        .map_err(|e| format!("Failed to create WAV file: {}", e))?;
+    // Write WAV file, clamping overshooting samples instead of letting them wrap
+    let stats = write_wav(&audio_path, &audio_data).map_err(|e| format!("{:#}", e))?;
 
-    for sample in audio_data {
-        let amplitude = (sample * i16::MAX as f32) as i16;
-        writer
-            .write_sample(amplitude)
-            .map_err(|e| format!("Failed to write audio sample: {}", e))?;
+    if stats.has_issues() {
+        let _ = app.emit(
+            "recording-quality-warning",
+            serde_json::json!({
+                "clampedSamples": stats.clamped,
+                "nanSamples": stats.nan,
+                "device": device_name,
+            }),
+        );
     }
 
-    writer
-        .finalize()
-        .map_err(|e| format!("Failed to finalize WAV file: {}", e))?;
-
     let path_str = audio_path.to_string_lossy().to_string();
     log::info!("Audio recording stopped and saved to: {}", path_str);
 
     Ok(path_str)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    /// Helper to create a temporary directory acting as the temp dir fixture
+    fn create_test_dir() -> PathBuf {
+        use std::time::UNIX_EPOCH;
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos())
+            .unwrap_or(0);
+        let test_dir = std::env::temp_dir().join(format!(
+            "rustler_recording_test_{}_{}",
+            nanos,
+            std::process::id()
+        ));
+        fs::create_dir_all(&test_dir).unwrap();
+        test_dir
+    }
+
+    /// Helper to clean up test directory
+    fn cleanup_test_dir(path: &Path) {
+        if path.exists() {
+            fs::remove_dir_all(path).ok();
+        }
+    }
+
+    #[test]
+    fn test_empty_capture_is_too_short() {
+        assert!(is_capture_too_short(&[]));
+    }
+
+    #[test]
+    fn test_capture_length_threshold() {
+        assert!(is_capture_too_short(&vec![0.1; MIN_CAPTURE_SAMPLES - 1]));
+        assert!(!is_capture_too_short(&vec![0.1; MIN_CAPTURE_SAMPLES]));
+    }
+
+    #[test]
+    fn test_is_recording_file_name() {
+        assert!(is_recording_file_name("whispr_recording_1700000000.wav"));
+        assert!(!is_recording_file_name("whispr_recording_1700000000.txt"));
+        assert!(!is_recording_file_name("other_1700000000.wav"));
+    }
+
+    #[test]
+    fn test_sweep_removes_only_stale_recordings() {
+        let test_dir = create_test_dir();
+        let recording = test_dir.join("whispr_recording_1.wav");
+        let unrelated = test_dir.join("unrelated.wav");
+        fs::write(&recording, b"wav").unwrap();
+        fs::write(&unrelated, b"wav").unwrap();
+
+        // A zero max age makes every matching file stale
+        std::thread::sleep(Duration::from_millis(10));
+        let removed = sweep_stale_recordings(&test_dir, Duration::ZERO);
+
+        assert_eq!(removed, 1);
+        assert!(!recording.exists());
+        assert!(unrelated.exists());
+
+        cleanup_test_dir(&test_dir);
+    }
+
+    #[test]
+    fn test_sweep_keeps_recent_recordings() {
+        let test_dir = create_test_dir();
+        let recording = test_dir.join("whispr_recording_2.wav");
+        fs::write(&recording, b"wav").unwrap();
+
+        let removed = sweep_stale_recordings(&test_dir, STALE_RECORDING_AGE);
+
+        assert_eq!(removed, 0);
+        assert!(recording.exists());
+
+        cleanup_test_dir(&test_dir);
+    }
+
+    #[test]
+    fn test_sweep_filters_by_modification_time() {
+        let test_dir = create_test_dir();
+        let now = SystemTime::now();
+        let aged = |name: &str, age: Duration| {
+            let path = test_dir.join(name);
+            let file = fs::File::create(&path).unwrap();
+            file.set_modified(now - age).unwrap();
+            path
+        };
+        let two_hours = aged("whispr_recording_1.wav", Duration::from_secs(2 * 60 * 60));
+        let ten_minutes = aged("whispr_recording_2.wav", Duration::from_secs(10 * 60));
+        let old_unrelated = aged("notes.wav", Duration::from_secs(2 * 60 * 60));
+
+        assert_eq!(sweep_stale_recordings(&test_dir, STALE_RECORDING_AGE), 1);
+        assert!(!two_hours.exists());
+        assert!(ten_minutes.exists());
+        assert!(old_unrelated.exists());
+
+        // A shorter age catches the newer recording too
+        assert_eq!(
+            sweep_stale_recordings(&test_dir, Duration::from_secs(5 * 60)),
+            1
+        );
+        assert!(!ten_minutes.exists());
+
+        cleanup_test_dir(&test_dir);
+    }
+
+    #[test]
+    fn test_recordings_dir_falls_back_to_system_temp() {
+        assert_eq!(recordings_dir(&Settings::default()), std::env::temp_dir());
+
+        let blank = Settings {
+            temp_dir: Some("  ".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(recordings_dir(&blank), std::env::temp_dir());
+
+        let custom = Settings {
+            temp_dir: Some("/var/tmp/rustler".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(recordings_dir(&custom), PathBuf::from("/var/tmp/rustler"));
+    }
+
+    #[test]
+    fn test_sweep_missing_directory_is_noop() {
+        let missing = std::env::temp_dir().join("rustler_recording_test_missing_dir");
+        assert_eq!(sweep_stale_recordings(&missing, Duration::ZERO), 0);
+    }
+}
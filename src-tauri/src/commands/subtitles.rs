@@ -0,0 +1,112 @@
+use crate::commands::history::find_history_entry;
+use crate::commands::settings::{get_app_config_dir, get_settings};
+use crate::commands::transcription::run_transcription;
+use crate::whisper::result::Segment;
+use crate::whisper::subtitles::{render_subtitles, SubtitleFormat};
+use crate::AppState;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tauri::{AppHandle, State};
+
+/// Directory for subtitles exported from history entries
+fn subtitles_dir() -> Result<PathBuf, String> {
+    let dir = get_app_config_dir()?.join("subtitles");
+    std::fs::create_dir_all(&dir)
+        .map_err(|e| format!("Failed to create subtitles directory: {}", e))?;
+    Ok(dir)
+}
+
+/// Path the subtitles of an audio file are written to when no output path is given:
+/// next to the audio file, with the subtitle extension
+fn default_audio_output_path(audio_path: &Path, format: SubtitleFormat) -> PathBuf {
+    audio_path.with_extension(format.extension())
+}
+
+/// Exports the transcription of an audio file or a history entry as subtitles
+///
+/// Exactly one of `audio_path` and `history_id` must be given. Audio files are
+/// transcribed with the configured model; history entries use their stored
+/// segments, so they need segment storage to have been enabled when recorded.
+///
+/// # Arguments
+/// * `audio_path` - Audio file to transcribe
+/// * `history_id` - ID of a history entry with stored segments
+/// * `format` - `"srt"` or `"vtt"`
+/// * `output_path` - Where to write the file; defaults to next to the audio file,
+///   or the `subtitles` folder in the config directory for history entries
+/// * `state` - Application state
+///
+/// # Returns
+/// * `Ok(String)` with the path of the written subtitle file
+/// * `Err(String)` if the source is missing or ambiguous, transcription failed,
+///   or the file could not be written
+#[tauri::command]
+pub async fn export_subtitles(
+    app: AppHandle,
+    audio_path: Option<String>,
+    history_id: Option<String>,
+    format: SubtitleFormat,
+    output_path: Option<String>,
+    state: State<'_, Arc<AppState>>,
+) -> Result<String, String> {
+    let (segments, default_path) = match (audio_path, history_id) {
+        (Some(audio_path), None) => {
+            log::info!(
+                "Exporting {:?} subtitles for audio file: {}",
+                format,
+                audio_path
+            );
+            let model = get_settings()
+                .await
+                .map_err(|e| format!("Failed to get settings: {}", e))?
+                .model;
+            let default_path = default_audio_output_path(Path::new(&audio_path), format);
+            let result = run_transcription(app, audio_path, model, None, &state).await?;
+            (result.segments, default_path)
+        }
+        (None, Some(history_id)) => {
+            log::info!(
+                "Exporting {:?} subtitles for history entry: {}",
+                format,
+                history_id
+            );
+            let record = find_history_entry(&history_id)
+                .ok_or_else(|| format!("History entry '{}' not found", history_id))?;
+            let segments = record.segments.ok_or_else(|| {
+                "This history entry has no stored segments. Enable storing segments to export subtitles from history".to_string()
+            })?;
+            let default_path =
+                subtitles_dir()?.join(format!("{}.{}", record.id, format.extension()));
+            (segments.iter().map(Segment::from).collect(), default_path)
+        }
+        _ => return Err("Specify either an audio file or a history entry".to_string()),
+    };
+
+    let output_path = output_path.map(PathBuf::from).unwrap_or(default_path);
+    std::fs::write(&output_path, render_subtitles(&segments, format))
+        .map_err(|e| format!("Failed to write subtitle file: {}", e))?;
+
+    log::info!(
+        "Wrote {} segments to {}",
+        segments.len(),
+        output_path.display()
+    );
+    Ok(output_path.to_string_lossy().into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_audio_output_path() {
+        assert_eq!(
+            default_audio_output_path(Path::new("/tmp/rec/take1.wav"), SubtitleFormat::Srt),
+            PathBuf::from("/tmp/rec/take1.srt")
+        );
+        assert_eq!(
+            default_audio_output_path(Path::new("take1"), SubtitleFormat::Vtt),
+            PathBuf::from("take1.vtt")
+        );
+    }
+}
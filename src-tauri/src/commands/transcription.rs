@@ -1,22 +1,237 @@
-use crate::commands::settings::get_settings;
-use crate::{whisper::cache::get_model_cache, AppState};
-use std::path::PathBuf;
+use crate::audio::recorder::LiveAudio;
+use crate::audio::vad::{speech_threshold, split_speech};
+use crate::audio::wav::{is_wav_file, read_wav, SAMPLE_RATE};
+use crate::commands::history::save_transcription;
+use crate::commands::settings::{current_settings, get_settings, Settings};
+use crate::tray::TranscribingTray;
+use crate::whisper::cache::{get_model_cache, CacheInfo};
+use crate::whisper::chunking::{plan_chunks, stitch_texts, Chunk};
+use crate::whisper::hallucination::SegmentFilter;
+use crate::whisper::language::{language_code, resolve_language, AUTO_DETECT_LANGUAGE};
+use crate::whisper::live::merge_live_text;
+use crate::whisper::postprocess::TextPipeline;
+use crate::whisper::result::{
+    centiseconds_to_ms, join_segments, token_confidence, Segment, TranscriptionResult,
+};
+use crate::whisper::sampling::sampling_strategy;
+use crate::AppState;
+use parking_lot::Mutex;
+use serde::Serialize;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::Receiver;
 use std::sync::Arc;
-use tauri::{AppHandle, Emitter, State};
-use whisper_rs::{FullParams, SamplingStrategy};
+use tauri::{AppHandle, Emitter, Manager, State};
+use whisper_rs::{FullParams, SamplingStrategy, SegmentCallbackData};
 
-/// Minimum RMS threshold for audio to be considered non-silent.
+/// Default minimum RMS threshold for audio to be considered non-silent.
 /// Audio below this threshold will be skipped without transcription.
 /// 0.001 is a conservative threshold that catches near-silence while allowing quiet speech.
-const SILENCE_RMS_THRESHOLD: f32 = 0.001;
+pub const SILENCE_RMS_THRESHOLD: f32 = 0.001;
 
-/// Minimum duration in samples for audio to be worth transcribing.
-/// At 16kHz, this is 0.25 seconds (4000 samples).
-const MIN_AUDIO_SAMPLES: usize = 4000;
+/// Default minimum duration in milliseconds for audio to be worth transcribing.
+/// At 16kHz, this is 4000 samples.
+pub const DEFAULT_MIN_AUDIO_MS: u32 = 250;
+
+/// Upper bound for the configurable minimum audio duration, in milliseconds
+pub const MAX_MIN_AUDIO_MS: u32 = 10_000;
+
+/// Error returned by `transcribe_audio` when the transcription was cancelled
+const TRANSCRIPTION_CANCELLED: &str = "cancelled";
+
+/// Builds the error reported when a worker thread dropped its result sender,
+/// which only happens if it panicked
+///
+/// # Arguments
+/// * `task` - What the thread was doing, e.g. "transcription"
+fn worker_crashed_error(task: &str) -> String {
+    format!(
+        "The {} thread crashed before returning a result. The audio file may be corrupt; see the logs for details",
+        task
+    )
+}
+
+/// Default number of threads used for Whisper inference
+pub const DEFAULT_TRANSCRIPTION_THREADS: usize = 4;
+
+/// Number of partial segments buffered for the frontend before new ones are dropped
+const PARTIAL_SEGMENT_BUFFER: usize = 64;
+
+/// Options for a single transcription, resolved from settings
+#[derive(Clone)]
+struct TranscriptionOptions {
+    use_gpu: bool,
+    /// Language code, or `None` to let Whisper detect it
+    language: Option<String>,
+    /// Whether to translate the speech into English
+    translate: bool,
+    initial_prompt: Option<String>,
+    /// Audio quieter than this RMS level is skipped
+    silence_rms_threshold: f32,
+    /// Audio shorter than this many milliseconds is skipped
+    min_audio_ms: u32,
+    /// Number of threads used for inference
+    threads: usize,
+    /// Decoding strategy
+    sampling: SamplingStrategy,
+    /// Whether Whisper is kept from starting a segment with a blank
+    suppress_blank: bool,
+    /// Whether Whisper is kept from producing non-speech tokens
+    suppress_non_speech_tokens: bool,
+    /// No-speech probability above which Whisper treats a segment as silent
+    no_speech_threshold: f32,
+    /// Drops silent and hallucinated segments from the result
+    segment_filter: SegmentFilter,
+    /// Recordings longer than this many seconds are transcribed in chunks, 0 to never chunk
+    chunk_length_secs: u32,
+    /// Seconds of audio shared by consecutive chunks
+    chunk_overlap_secs: u32,
+    /// Whether only the speech is transcribed, split at pauses
+    vad_enabled: bool,
+    /// Shortest pause in milliseconds that splits the speech
+    vad_min_silence_ms: u32,
+}
+
+impl TranscriptionOptions {
+    /// Resolves the options from settings
+    ///
+    /// # Arguments
+    /// * `settings` - Current settings
+    /// * `initial_prompt` - Prompt overriding the one from settings, if any
+    ///
+    /// # Returns
+    /// * `Ok(TranscriptionOptions)` for the settings
+    /// * `Err(String)` if the configured language is invalid
+    fn from_settings(settings: &Settings, initial_prompt: Option<String>) -> Result<Self, String> {
+        Ok(Self {
+            use_gpu: settings.use_gpu,
+            language: resolve_language(&settings.language).map_err(|e| e.to_string())?,
+            translate: settings.translate,
+            initial_prompt: resolve_initial_prompt(initial_prompt, settings.initial_prompt.clone()),
+            silence_rms_threshold: settings.silence_rms_threshold,
+            min_audio_ms: settings.min_audio_ms,
+            threads: settings.transcription_threads.max(1),
+            sampling: sampling_strategy(settings.sampling_strategy, settings.beam_size),
+            suppress_blank: settings.suppress_blank,
+            suppress_non_speech_tokens: settings.suppress_non_speech_tokens,
+            no_speech_threshold: settings.no_speech_threshold,
+            segment_filter: SegmentFilter::new(
+                settings.no_speech_threshold,
+                &settings.hallucination_blocklist,
+            ),
+            chunk_length_secs: settings.chunk_length_secs,
+            chunk_overlap_secs: settings.chunk_overlap_secs,
+            vad_enabled: settings.vad_enabled,
+            vad_min_silence_ms: settings.vad_min_silence_ms,
+        })
+    }
+}
+
+/// Progress of a transcription, reported from Whisper's progress callback
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct TranscriptionProgress {
+    /// Progress of the whole transcription (0-100)
+    percentage: i32,
+    /// Chunk being transcribed, counting from 0
+    chunk: usize,
+    /// Number of chunks the recording was split into
+    chunk_count: usize,
+}
+
+impl TranscriptionProgress {
+    /// Progress of the whole transcription from the progress of one chunk
+    ///
+    /// # Arguments
+    /// * `chunk` - Chunk being transcribed, counting from 0
+    /// * `chunk_count` - Number of chunks
+    /// * `chunk_percentage` - Progress of the chunk as reported by Whisper
+    fn new(chunk: usize, chunk_count: usize, chunk_percentage: i32) -> Self {
+        let chunk_count = chunk_count.max(1);
+        let done = chunk as i32 * 100 + chunk_percentage.clamp(0, 100);
+        Self {
+            percentage: done / chunk_count as i32,
+            chunk,
+            chunk_count,
+        }
+    }
+}
+
+/// Most likely spoken language of an audio file
+#[derive(Debug, Clone, Serialize)]
+pub struct DetectedLanguage {
+    /// Whisper language code (e.g., "en", "de")
+    pub language: String,
+    /// Probability of the detected language (0.0 - 1.0)
+    pub probability: f32,
+}
+
+/// Drops progress reports that would not change the displayed whole percentage
+#[derive(Debug)]
+struct ProgressThrottle {
+    /// Last percentage that was let through
+    last_reported: i32,
+}
+
+impl Default for ProgressThrottle {
+    fn default() -> Self {
+        Self { last_reported: -1 }
+    }
+}
+
+impl ProgressThrottle {
+    /// Returns the percentage (clamped to 0-100) to report, or `None` if it was already reported
+    fn update(&mut self, percentage: i32) -> Option<i32> {
+        let percentage = percentage.clamp(0, 100);
+        if percentage > self.last_reported {
+            self.last_reported = percentage;
+            Some(percentage)
+        } else {
+            None
+        }
+    }
+}
+
+/// Maximum length of the initial prompt in characters.
+/// Whisper only uses the last ~224 tokens of the prompt, so anything longer is wasted.
+const MAX_INITIAL_PROMPT_CHARS: usize = 800;
+
+/// Picks the initial prompt to use for a transcription.
+/// A non-empty per-call override wins over the prompt from settings.
+/// Whitespace-only prompts are ignored, NUL characters (which Whisper cannot
+/// accept) are dropped and long prompts are capped to `MAX_INITIAL_PROMPT_CHARS`,
+/// keeping the end of the prompt.
+fn resolve_initial_prompt(
+    override_prompt: Option<String>,
+    settings_prompt: Option<String>,
+) -> Option<String> {
+    let prompt = [override_prompt, settings_prompt]
+        .into_iter()
+        .flatten()
+        .map(|p| p.replace('\0', "").trim().to_string())
+        .find(|p| !p.is_empty())?;
+
+    let char_count = prompt.chars().count();
+    if char_count > MAX_INITIAL_PROMPT_CHARS {
+        log::warn!(
+            "Initial prompt is {} characters, keeping the last {}",
+            char_count,
+            MAX_INITIAL_PROMPT_CHARS
+        );
+        return Some(
+            prompt
+                .chars()
+                .skip(char_count - MAX_INITIAL_PROMPT_CHARS)
+                .collect(),
+        );
+    }
+
+    Some(prompt)
+}
 
 /// Calculates the Root Mean Square (RMS) of audio samples.
 /// RMS is a good measure of the overall energy/loudness of the audio signal.
-fn calculate_rms(samples: &[f32]) -> f32 {
+pub(crate) fn calculate_rms(samples: &[f32]) -> f32 {
     if samples.is_empty() {
         return 0.0;
     }
@@ -24,26 +239,117 @@ fn calculate_rms(samples: &[f32]) -> f32 {
     (sum_of_squares / samples.len() as f32).sqrt()
 }
 
+/// Number of 16kHz samples in a duration given in milliseconds
+fn ms_to_samples(ms: u32) -> usize {
+    ms as usize * SAMPLE_RATE as usize / 1000
+}
+
+/// Length of the windows compared against the silence threshold when trimming (20 ms)
+const TRIM_WINDOW_SAMPLES: usize = SAMPLE_RATE as usize / 50;
+
+/// Quiet audio kept around the speech when trimming, so soft word onsets and
+/// endings aren't clipped (250 ms)
+const TRIM_GUARD_SAMPLES: usize = SAMPLE_RATE as usize / 4;
+
+/// Finds the audio left after trimming leading and trailing silence
+///
+/// Silence is any 20 ms window quieter than the threshold; a guard margin of
+/// quiet audio is kept on both sides of the speech. Windows and guard are whole
+/// centiseconds, so the trimmed audio starts on one and Whisper's timestamps
+/// can be offset exactly.
+///
+/// # Arguments
+/// * `samples` - 16kHz mono samples
+/// * `threshold` - Windows with a lower RMS level are silent
+///
+/// # Returns
+/// The range of samples to transcribe, empty if the audio is silent throughout
+fn trim_silence(samples: &[f32], threshold: f32) -> Range<usize> {
+    let is_loud = |window: &[f32]| calculate_rms(window) >= threshold;
+    let mut windows = samples.chunks(TRIM_WINDOW_SAMPLES);
+    let Some(first) = windows.position(is_loud) else {
+        return 0..0;
+    };
+    let last = samples
+        .chunks(TRIM_WINDOW_SAMPLES)
+        .rposition(is_loud)
+        .unwrap_or(first);
+
+    let start = (first * TRIM_WINDOW_SAMPLES).saturating_sub(TRIM_GUARD_SAMPLES);
+    let end = ((last + 1) * TRIM_WINDOW_SAMPLES + TRIM_GUARD_SAMPLES).min(samples.len());
+    start..end
+}
+
+/// Finds the parts of a recording worth transcribing
+///
+/// # Arguments
+/// * `samples` - 16kHz mono samples
+/// * `silence_rms_threshold` - Audio with a lower RMS level is silent
+/// * `vad_min_silence_ms` - Shortest pause splitting the speech, `None` to only
+///   trim leading and trailing silence
+///
+/// # Returns
+/// The sample ranges to transcribe in order
+fn speech_ranges(
+    samples: &[f32],
+    silence_rms_threshold: f32,
+    vad_min_silence_ms: Option<u32>,
+) -> Vec<Range<usize>> {
+    if let Some(min_silence_ms) = vad_min_silence_ms {
+        let threshold = speech_threshold(samples, silence_rms_threshold);
+        let stretches = split_speech(samples, threshold, ms_to_samples(min_silence_ms));
+        // Steady noise never rises above its own floor; transcribe it as a whole
+        if !stretches.is_empty() {
+            log::info!(
+                "Voice activity detection found {} stretches of speech",
+                stretches.len()
+            );
+            return stretches;
+        }
+    }
+
+    let speech = trim_silence(samples, silence_rms_threshold);
+    if speech.len() < samples.len() {
+        log::debug!(
+            "Trimmed silence: transcribing samples {}..{} of {}",
+            speech.start,
+            speech.end,
+            samples.len()
+        );
+    }
+    vec![speech]
+}
+
 /// Checks if audio samples are effectively silent or too short to transcribe.
 /// Returns true if the audio should be skipped.
-fn is_audio_silent_or_too_short(samples: &[f32]) -> bool {
+///
+/// # Arguments
+/// * `samples` - 16kHz mono samples
+/// * `silence_rms_threshold` - Audio with a lower RMS level is silent
+/// * `min_audio_ms` - Audio shorter than this is too short
+fn is_audio_silent_or_too_short(
+    samples: &[f32],
+    silence_rms_threshold: f32,
+    min_audio_ms: u32,
+) -> bool {
     // Check if audio is too short
-    if samples.len() < MIN_AUDIO_SAMPLES {
+    let min_samples = ms_to_samples(min_audio_ms);
+    if samples.len() < min_samples {
         log::info!(
             "Audio too short ({} samples, minimum {}), skipping transcription",
             samples.len(),
-            MIN_AUDIO_SAMPLES
+            min_samples
         );
         return true;
     }
 
     // Check RMS level
     let rms = calculate_rms(samples);
-    if rms < SILENCE_RMS_THRESHOLD {
+    if rms < silence_rms_threshold {
         log::info!(
             "Audio is silent (RMS: {:.6}, threshold: {}), skipping transcription",
             rms,
-            SILENCE_RMS_THRESHOLD
+            silence_rms_threshold
         );
         return true;
     }
@@ -54,32 +360,308 @@ fn is_audio_silent_or_too_short(samples: &[f32]) -> bool {
 
 /// Transcribes audio file to text using the specified Whisper model
 ///
+/// Non-empty transcriptions are saved to history with their duration and
+/// model unless `save_history` is turned off in settings. Failing to save
+/// them is only logged.
+///
 /// # Arguments
 /// * `audio_path` - Path to the audio file to transcribe
 /// * `model` - Name of the Whisper model to use (e.g., "base", "small")
+/// * `initial_prompt` - Optional prompt overriding the one from settings
 /// * `state` - Application state
 ///
 /// # Returns
 /// * `Ok(String)` with the transcribed text
-/// * `Err(String)` with error message if transcription failed
+/// * `Err(String)` with error message if transcription failed,
+///   or `"cancelled"` if it was aborted with `cancel_transcription`
 #[tauri::command]
 pub async fn transcribe_audio(
     app: AppHandle,
     audio_path: String,
     model: String,
-    _state: State<'_, Arc<AppState>>,
+    initial_prompt: Option<String>,
+    state: State<'_, Arc<AppState>>,
 ) -> Result<String, String> {
+    let result = run_transcription(app, audio_path, model, initial_prompt, &state).await?;
+
+    let settings = current_settings().unwrap_or_default();
+    if let Err(e) = save_transcription(&result, &settings) {
+        log::warn!("Failed to save transcription to history: {}", e);
+    }
+
+    Ok(result.text)
+}
+
+/// Transcribes an audio file and returns the full result with segment timestamps
+///
+/// # Arguments
+/// * `audio_path` - Path to the audio file to transcribe
+/// * `model` - Name of the Whisper model to use (e.g., "base", "small")
+/// * `initial_prompt` - Optional prompt overriding the one from settings
+/// * `state` - Application state
+///
+/// # Returns
+/// * `Ok(TranscriptionResult)` with the text, segments, model and audio duration
+/// * `Err(String)` with error message if transcription failed,
+///   or `"cancelled"` if it was aborted with `cancel_transcription`
+#[tauri::command]
+pub async fn transcribe_audio_detailed(
+    app: AppHandle,
+    audio_path: String,
+    model: String,
+    initial_prompt: Option<String>,
+    state: State<'_, Arc<AppState>>,
+) -> Result<TranscriptionResult, String> {
+    run_transcription(app, audio_path, model, initial_prompt, &state).await
+}
+
+/// Transcribes an existing audio file, e.g. a meeting recording or voice memo dropped onto the app
+///
+/// Unlike recordings made by the app, the file may use any common WAV format:
+/// 8/16/24/32-bit integer or float samples, several channels and any sample rate
+/// are converted to 16kHz mono before transcription. With the `compressed-audio`
+/// feature, MP3, Ogg Vorbis, FLAC and AAC/M4A files are decoded as well.
+///
+/// # Arguments
+/// * `path` - Path to the audio file
+/// * `model` - Whisper model to use; defaults to the model from settings
+/// * `state` - Application state
+///
+/// # Returns
+/// * `Ok(TranscriptionResult)` with the text, segments, model and audio duration
+/// * `Err(String)` if the file format is not supported or transcription failed
+#[tauri::command]
+pub async fn transcribe_file(
+    app: AppHandle,
+    path: String,
+    model: Option<String>,
+    state: State<'_, Arc<AppState>>,
+) -> Result<TranscriptionResult, String> {
+    log::info!("Transcribing file: {}", path);
+
+    check_audio_format(Path::new(&path))?;
+    let model = model_or_default(model).await?;
+
+    run_transcription(app, path, model, None, &state).await
+}
+
+/// Returns the given model, or the model from settings if none was given
+async fn model_or_default(model: Option<String>) -> Result<String, String> {
+    match model {
+        Some(model) => Ok(model),
+        None => Ok(get_settings()
+            .await
+            .map_err(|e| format!("Failed to get settings: {}", e))?
+            .model),
+    }
+}
+
+/// State of a file in a batch transcription
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum BatchFileStatus {
+    /// The file is being transcribed
+    Processing,
+    /// The file was transcribed
+    Done,
+    /// The file could not be read or transcribed
+    Failed,
+    /// The batch was cancelled before reaching the file
+    Skipped,
+}
+
+/// Payload of the `batch-progress` event
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct BatchProgress {
+    /// Position of the file in the batch, starting at 0
+    pub index: usize,
+    pub total: usize,
+    pub path: String,
+    pub status: BatchFileStatus,
+}
+
+/// Outcome of one file of a batch transcription
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchFileResult {
+    pub path: String,
+    pub status: BatchFileStatus,
+    /// Set when the file was transcribed
+    pub result: Option<TranscriptionResult>,
+    /// Set when the file failed
+    pub error: Option<String>,
+}
+
+/// Transcribes files one after another, collecting a result for every file
+///
+/// A failing file is recorded and the batch moves on. Once `cancel_flag` is set,
+/// or a file's transcription was cancelled, the remaining files are skipped.
+async fn run_batch<F, Fut>(
+    paths: Vec<String>,
+    cancel_flag: &AtomicBool,
+    mut transcribe: F,
+    mut report: impl FnMut(BatchProgress),
+) -> Vec<BatchFileResult>
+where
+    F: FnMut(String) -> Fut,
+    Fut: std::future::Future<Output = Result<TranscriptionResult, String>>,
+{
+    let total = paths.len();
+    let mut results = Vec::with_capacity(total);
+    let mut cancelled = false;
+
+    for (index, path) in paths.into_iter().enumerate() {
+        cancelled = cancelled || cancel_flag.load(Ordering::SeqCst);
+        let mut progress = |status| {
+            report(BatchProgress {
+                index,
+                total,
+                path: path.clone(),
+                status,
+            })
+        };
+
+        let (status, result, error) = if cancelled {
+            (BatchFileStatus::Skipped, None, None)
+        } else {
+            progress(BatchFileStatus::Processing);
+            match transcribe(path.clone()).await {
+                Ok(result) => (BatchFileStatus::Done, Some(result), None),
+                Err(e) => {
+                    log::warn!("Batch transcription of {} failed: {}", path, e);
+                    cancelled = e == TRANSCRIPTION_CANCELLED;
+                    (BatchFileStatus::Failed, None, Some(e))
+                }
+            }
+        };
+        progress(status);
+
+        results.push(BatchFileResult {
+            path,
+            status,
+            result,
+            error,
+        });
+    }
+
+    results
+}
+
+/// Transcribes several audio files one after another, e.g. a folder of voice memos
+///
+/// Files are processed sequentially so the model is loaded once and reused from the
+/// model cache. A `batch-progress` event (`{ index, total, path, status }`) is emitted
+/// when a file starts and when it finishes. A file that cannot be transcribed does
+/// not stop the batch; its error is reported in the results.
+///
+/// # Arguments
+/// * `paths` - Audio files to transcribe, in order
+/// * `model` - Whisper model to use; defaults to the model from settings
+/// * `state` - Application state
+///
+/// # Returns
+/// * `Ok(Vec<BatchFileResult>)` with one entry per path, in the same order
+/// * `Err(String)` if the settings could not be read
+#[tauri::command]
+pub async fn transcribe_batch(
+    app: AppHandle,
+    paths: Vec<String>,
+    model: Option<String>,
+    state: State<'_, Arc<AppState>>,
+) -> Result<Vec<BatchFileResult>, String> {
+    log::info!("Transcribing batch of {} files", paths.len());
+    let model = model_or_default(model).await?;
+
+    let cancel_flag = Arc::clone(&state.batch_cancelled);
+    cancel_flag.store(false, Ordering::SeqCst);
+
+    let progress_app = app.clone();
+    let state: &AppState = &state;
+    let results = run_batch(
+        paths,
+        &cancel_flag,
+        |path| {
+            let app = app.clone();
+            let model = model.clone();
+            async move {
+                check_audio_format(Path::new(&path))?;
+                run_transcription(app, path, model, None, state).await
+            }
+        },
+        |progress| {
+            let _ = progress_app.emit("batch-progress", &progress);
+        },
+    )
+    .await;
+
+    let failed = results
+        .iter()
+        .filter(|result| result.status == BatchFileStatus::Failed)
+        .count();
+    log::info!("Batch finished: {} files, {} failed", results.len(), failed);
+    Ok(results)
+}
+
+/// Stops a batch transcription after the file currently being transcribed
+///
+/// # Arguments
+/// * `state` - Application state holding the batch cancellation flag
+#[tauri::command]
+pub fn cancel_batch(state: State<'_, Arc<AppState>>) {
+    log::info!("Cancelling batch transcription");
+    state.batch_cancelled.store(true, Ordering::SeqCst);
+}
+
+/// Runs a transcription and emits its status, progress and result events
+///
+/// Transcriptions run one at a time; one started while another is running waits
+/// for its turn after emitting `transcription-queued` with the number of jobs
+/// ahead of it.
+pub async fn run_transcription(
+    app: AppHandle,
+    audio_path: String,
+    model: String,
+    initial_prompt: Option<String>,
+    state: &AppState,
+) -> Result<TranscriptionResult, String> {
+    let turn = state
+        .transcription_queue
+        .wait_for_turn(|position| {
+            let _ = app.emit(
+                "transcription-queued",
+                serde_json::json!({ "position": position }),
+            );
+        })
+        .await
+        .ok_or_else(|| TRANSCRIPTION_CANCELLED.to_string())?;
+
+    // Clear any cancellation left over from an earlier request. A cancel that
+    // came in after this job was queued is still seen through the queue, even
+    // if it landed just before the flag was cleared.
+    let cancel_flag = Arc::clone(&state.transcription_cancelled);
+    cancel_flag.store(false, Ordering::SeqCst);
+    if turn.is_cancelled() {
+        log::info!("Transcription was cancelled before it started");
+        return Err(TRANSCRIPTION_CANCELLED.to_string());
+    }
+    let _tray = TranscribingTray::start(&app);
+
     // Get settings to check GPU preference
     let settings = get_settings()
         .await
         .map_err(|e| format!("Failed to get settings: {}", e))?;
-    let use_gpu = settings.use_gpu;
+    let options = TranscriptionOptions::from_settings(&settings, initial_prompt)?;
+
+    // Apply the configured number of models to keep loaded
+    get_model_cache().set_max_models(settings.max_cached_models);
 
     log::info!(
-        "Transcribing audio file: {} with model: {} (GPU: {})",
+        "Transcribing audio file: {} with model: {} (GPU: {}, language: {}, translate: {})",
         audio_path,
         model,
-        use_gpu
+        options.use_gpu,
+        options.language.as_deref().unwrap_or(AUTO_DETECT_LANGUAGE),
+        options.translate
     );
 
     // Emit processing started
@@ -108,141 +690,870 @@ pub async fn transcribe_audio(
     let audio_path_clone = audio_path.clone();
     let model_clone = model.clone();
     let app_clone = app.clone();
+    let progress_app = app.clone();
+
+    // Whisper reports progress from inside its FFI callback; only forward it from there.
+    // Sending never panics, even once the receiver is gone.
+    let (progress_tx, mut progress_rx) =
+        tokio::sync::mpsc::unbounded_channel::<TranscriptionProgress>();
+    let on_progress = move |progress: TranscriptionProgress| {
+        let _ = progress_tx.send(progress);
+    };
+
+    // Report progress to the frontend (throttled to only emit on whole percentage changes).
+    // Long recordings are transcribed in chunks; each started chunk is announced too.
+    let progress_task = tokio::spawn(async move {
+        let mut throttle = ProgressThrottle::default();
+        let mut current_chunk = None;
+        while let Some(progress) = progress_rx.recv().await {
+            if progress.chunk_count > 1 && current_chunk != Some(progress.chunk) {
+                current_chunk = Some(progress.chunk);
+                let _ = progress_app.emit(
+                    "transcription-chunk",
+                    serde_json::json!({
+                        "chunk": progress.chunk + 1,
+                        "totalChunks": progress.chunk_count,
+                    }),
+                );
+            }
+            if let Some(percentage) = throttle.update(progress.percentage) {
+                let _ = progress_app.emit(
+                    "transcription-progress",
+                    serde_json::json!({ "percentage": percentage }),
+                );
+            }
+        }
+    });
+
+    // Segments are forwarded the same way, but through a bounded channel so a slow
+    // frontend can never block or grow memory on the Whisper thread
+    let (partial_tx, mut partial_rx) =
+        tokio::sync::mpsc::channel::<Segment>(PARTIAL_SEGMENT_BUFFER);
+    let on_segment = move |data: SegmentCallbackData| {
+        send_partial_segment(
+            &partial_tx,
+            data.start_timestamp,
+            data.end_timestamp,
+            &data.text,
+        );
+    };
+
+    // Show segments in the frontend while decoding continues
+    let partial_app = app.clone();
+    let partial_task = tokio::spawn(async move {
+        while let Some(segment) = partial_rx.recv().await {
+            let _ = partial_app.emit("transcription-partial", &segment);
+        }
+    });
 
     // Run the CPU-intensive transcription in a separate thread using oneshot channel
     let (tx, rx) = tokio::sync::oneshot::channel();
 
     std::thread::spawn(move || {
-        let result = transcribe_blocking(audio_path_clone, model_clone, model_path, use_gpu);
+        let result = transcribe_blocking(
+            audio_path_clone,
+            model_clone,
+            model_path,
+            options,
+            on_progress,
+            on_segment,
+            cancel_flag,
+        );
         let _ = tx.send(result);
     });
 
-    let text = rx
+    let mut result = rx
         .await
-        .map_err(|e| {
+        .map_err(|_| {
+            // The sender is only dropped without a result if the thread panicked
+            log::error!("Transcription thread exited without sending a result");
             let _ = app_clone.emit(
                 "processing-status",
                 serde_json::json!({ "isProcessing": false }),
             );
-            format!("Channel receive error: {}", e)
+            worker_crashed_error("transcription")
         })?
-        .inspect_err(|_e| {
+        .inspect_err(|e| {
             let _ = app.emit(
                 "processing-status",
-                serde_json::json!({ "isProcessing": false }),
+                serde_json::json!({
+                    "isProcessing": false,
+                    "cancelled": e == TRANSCRIPTION_CANCELLED,
+                }),
             );
         })?;
 
-    log::info!("Transcription completed: {} characters", text.len());
+    // The progress and segment senders are dropped before the result is sent, so these
+    // finish promptly and every progress and partial event is emitted before the completion event
+    let _ = progress_task.await;
+    let _ = partial_task.await;
+
+    log::info!(
+        "Transcription completed: {} characters in {} segments (language: {})",
+        result.text.len(),
+        result.segment_count,
+        result.language.as_deref().unwrap_or("unknown")
+    );
+
+    // Clean up the final text; segments keep what Whisper produced
+    let text_language = if result.translated {
+        Some("en")
+    } else {
+        result.language.as_deref()
+    };
+    let pipeline = TextPipeline::from_settings(&settings, text_language);
+    if pipeline.is_enabled() {
+        result.text = pipeline.apply(&result.text);
+    }
 
     // Emit processing completed with transcription
     let _ = app.emit(
         "processing-status",
         serde_json::json!({ "isProcessing": false }),
     );
-    let _ = app.emit(
-        "transcription-complete",
-        serde_json::json!({ "text": text }),
-    );
+    let _ = app.emit("transcription-complete", &result);
+
+    Ok(result)
+}
+
+/// Queues a segment Whisper just finished for the frontend
+///
+/// Never blocks: when the buffer is full the segment is dropped, since the
+/// completion event carries the full text anyway.
+///
+/// # Returns
+/// Whether the segment was queued
+fn send_partial_segment(
+    sender: &tokio::sync::mpsc::Sender<Segment>,
+    start: i64,
+    end: i64,
+    text: &str,
+) -> bool {
+    let segment = Segment::from_whisper(start, end, text);
+    if segment.text.is_empty() {
+        return false;
+    }
+    match sender.try_send(segment) {
+        Ok(()) => true,
+        Err(tokio::sync::mpsc::error::TrySendError::Full(_)) => {
+            log::debug!("Partial segment buffer full, dropping segment");
+            false
+        }
+        Err(tokio::sync::mpsc::error::TrySendError::Closed(_)) => false,
+    }
+}
+
+/// Transcribes audio handed over by the recorder while it is still recording
+///
+/// Runs on its own thread until the recorder stops sending audio. Each pass
+/// transcribes the latest window with the model from settings, merges its text
+/// into what was shown so far and emits the result as `transcription-live`
+/// with `{ text }`. Passes take a turn in the transcription queue, and are
+/// skipped while another transcription is running or waiting. Stopping the
+/// recording sets `stopped`, which aborts a pass in progress so the final
+/// transcription doesn't wait for it. Live passes never chunk, skip the text
+/// clean-up and don't touch history; the final transcription of the whole
+/// recording replaces their text.
+///
+/// # Arguments
+/// * `app` - App handle used to emit events
+/// * `windows` - Audio windows sent by the recorder
+/// * `stopped` - Set by the recorder once the recording stopped
+pub(crate) fn spawn_live_transcription(
+    app: AppHandle,
+    windows: Receiver<LiveAudio>,
+    stopped: Arc<AtomicBool>,
+) {
+    std::thread::spawn(move || {
+        let settings = current_settings().unwrap_or_default();
+        let model_path =
+            crate::models::downloader::ModelDownloader::new().get_model_path(&settings.model);
+        if !model_path.exists() {
+            log::warn!(
+                "Model '{}' not found, skipping live transcription",
+                settings.model
+            );
+            return;
+        }
+        let options = match TranscriptionOptions::from_settings(&settings, None) {
+            Ok(options) => TranscriptionOptions {
+                chunk_length_secs: 0,
+                ..options
+            },
+            Err(e) => {
+                log::warn!("Skipping live transcription: {}", e);
+                return;
+            }
+        };
+
+        let state = Arc::clone(&app.state::<Arc<AppState>>());
+        let mut live_text = String::new();
+        while let Ok(window) = windows.recv() {
+            let Some(_turn) = state.transcription_queue.try_turn() else {
+                log::debug!("Transcription queue busy, skipping live pass");
+                continue;
+            };
+            let pass = transcribe_samples(
+                &window.samples,
+                settings.model.clone(),
+                model_path.clone(),
+                options.clone(),
+                |_| {},
+                |_| {},
+                Arc::clone(&stopped),
+            );
+            let pass = match pass {
+                Ok(pass) => pass,
+                Err(e) if e == TRANSCRIPTION_CANCELLED => break,
+                Err(e) => {
+                    log::warn!("Live transcription failed: {}", e);
+                    break;
+                }
+            };
 
-    Ok(text)
+            let text = merge_live_text(&live_text, &pass.text, window.from_start);
+            if text != live_text {
+                live_text = text;
+                let _ = app.emit(
+                    "transcription-live",
+                    serde_json::json!({ "text": live_text }),
+                );
+            }
+        }
+        log::debug!("Live transcription finished");
+    });
 }
 
 /// Blocking transcription function to be run in a separate thread
-fn transcribe_blocking(
+///
+/// Reads the audio file and transcribes it with `transcribe_samples`.
+fn transcribe_blocking<F, S>(
     audio_path: String,
     model: String,
     model_path: PathBuf,
-    use_gpu: bool,
-) -> Result<String, String> {
-    // Load audio file
-    let mut reader = hound::WavReader::open(&audio_path)
-        .map_err(|e| format!("Failed to open audio file: {}", e))?;
-
-    // Convert audio to f32 samples
-    let audio_data: Vec<f32> = reader
-        .samples::<i16>()
-        .map(|s| s.unwrap() as f32 / i16::MAX as f32)
-        .collect();
+    options: TranscriptionOptions,
+    on_progress: F,
+    on_segment: S,
+    cancel_flag: Arc<AtomicBool>,
+) -> Result<TranscriptionResult, String>
+where
+    F: FnMut(TranscriptionProgress) + Send + 'static,
+    S: FnMut(SegmentCallbackData) + Send + 'static,
+{
+    let audio_data = load_audio_samples(&audio_path)?;
+    transcribe_samples(
+        &audio_data,
+        model,
+        model_path,
+        options,
+        on_progress,
+        on_segment,
+        cancel_flag,
+    )
+}
+
+/// Transcribes 16kHz mono samples with the cached model, blocking until done
+///
+/// `on_progress` is called with the progress of the whole transcription, and
+/// `on_segment` with every segment as soon as it is decoded.
+///
+/// Recordings longer than the configured chunk length are transcribed chunk by
+/// chunk through the same cached model, so Whisper's memory use stays bounded and
+/// progress covers the whole recording. Segments in the overlap between two
+/// chunks are kept from one chunk only, and the chunk texts are stitched so the
+/// overlap isn't repeated.
+fn transcribe_samples<F, S>(
+    audio_data: &[f32],
+    model: String,
+    model_path: PathBuf,
+    options: TranscriptionOptions,
+    on_progress: F,
+    on_segment: S,
+    cancel_flag: Arc<AtomicBool>,
+) -> Result<TranscriptionResult, String>
+where
+    F: FnMut(TranscriptionProgress) + Send + 'static,
+    S: FnMut(SegmentCallbackData) + Send + 'static,
+{
+    let TranscriptionOptions {
+        use_gpu,
+        language,
+        translate,
+        initial_prompt,
+        silence_rms_threshold,
+        min_audio_ms,
+        threads,
+        sampling,
+        suppress_blank,
+        suppress_non_speech_tokens,
+        no_speech_threshold,
+        segment_filter,
+        chunk_length_secs,
+        chunk_overlap_secs,
+        vad_enabled,
+        vad_min_silence_ms,
+    } = options;
 
     // Check if audio is silent or too short - skip expensive transcription
-    if is_audio_silent_or_too_short(&audio_data) {
-        return Ok(String::new());
+    if is_audio_silent_or_too_short(audio_data, silence_rms_threshold, min_audio_ms) {
+        return Ok(TranscriptionResult::new(
+            Vec::new(),
+            model,
+            audio_data.len(),
+            language,
+            translate,
+        ));
+    }
+
+    // Whisper only hears the speech; silence wastes time and tempts it into
+    // making up words. With VAD, pauses between sentences are skipped too.
+    // Chunks are planned for each stretch of speech, then moved back to their
+    // place in the recording so timestamps stay relative to its start.
+    let speech = speech_ranges(
+        audio_data,
+        silence_rms_threshold,
+        vad_enabled.then_some(vad_min_silence_ms),
+    );
+    let chunks: Vec<Chunk> = speech
+        .into_iter()
+        .flat_map(|speech| {
+            plan_chunks(
+                &audio_data[speech.clone()],
+                ms_to_samples(chunk_length_secs.saturating_mul(1000)),
+                ms_to_samples(chunk_overlap_secs.saturating_mul(1000)),
+            )
+            .into_iter()
+            .map(move |chunk| chunk.shifted(speech.start))
+        })
+        .collect();
+    if chunks.len() > 1 {
+        log::info!(
+            "Transcribing in {} chunks of up to {} s",
+            chunks.len(),
+            chunk_length_secs
+        );
+    }
+
+    // Loading a large model takes seconds; don't start if the user already gave up
+    if cancel_flag.load(Ordering::SeqCst) {
+        log::info!("Transcription cancelled before loading the model");
+        return Err(TRANSCRIPTION_CANCELLED.to_string());
     }
 
     // Get or load model from cache (stays loaded for 5 minutes after last use)
     // Pass the use_gpu setting - if it changes, the model will be reloaded
     let cache = get_model_cache();
-    let _guard = cache
+    let guard = cache
         .get_or_load(&model, model_path, use_gpu)
         .map_err(|e| format!("Failed to load model: {}", e))?;
 
+    // Each chunk's Whisper run gets its own callbacks, all reporting through these
+    let on_progress = Arc::new(Mutex::new(on_progress));
+    let on_segment = Arc::new(Mutex::new(on_segment));
+
     // Transcribe using cached model
-    let text = cache
-        .with_context(|context| {
+    let result = run_cancellable(&cancel_flag, || {
+        guard.with_context(|context| {
             log::info!("Transcribing {} audio samples", audio_data.len());
 
-            // Create transcription parameters
-            let mut params = FullParams::new(SamplingStrategy::Greedy { best_of: 1 });
-            params.set_n_threads(4);
-            params.set_translate(false);
-            params.set_language(Some("en"));
-            params.set_print_special(false);
-            params.set_print_progress(false);
-            params.set_print_realtime(false);
-            params.set_print_timestamps(false);
-
-            // Create state and run transcription
-            let mut state = context.create_state()?;
-            state.full(params, &audio_data)?;
-
-            // Extract transcribed text
-            let num_segments = state.full_n_segments();
-            let mut result = String::new();
-
-            for i in 0..num_segments {
-                if let Some(segment) = state.get_segment(i) {
-                    if let Ok(text) = segment.to_str() {
-                        result.push_str(text);
-                        if i < num_segments - 1 {
-                            result.push(' ');
-                        }
+            // Later chunks stick to the language detected in the first one
+            let mut language = language.clone();
+            let mut segments = Vec::new();
+            let mut no_speech_probabilities = Vec::new();
+            let mut text = String::new();
+
+            for (index, chunk) in chunks.iter().enumerate() {
+                // Create transcription parameters
+                let mut params = FullParams::new(sampling.clone());
+                params.set_n_threads(threads as i32);
+                params.set_translate(translate);
+                params.set_language(language.as_deref());
+                params.set_print_special(false);
+                params.set_print_progress(false);
+                params.set_print_realtime(false);
+                params.set_print_timestamps(false);
+                params.set_suppress_blank(suppress_blank);
+                params.set_suppress_nst(suppress_non_speech_tokens);
+                params.set_no_speech_thold(no_speech_threshold);
+
+                // Bias vocabulary towards the user's names and jargon
+                if let Some(ref prompt) = initial_prompt {
+                    log::info!("Applying initial prompt ({} characters)", prompt.len());
+                    params.set_initial_prompt(prompt);
+                }
+
+                let chunk_count = chunks.len();
+                (on_progress.lock())(TranscriptionProgress::new(index, chunk_count, 0));
+                let progress = Arc::clone(&on_progress);
+                params.set_progress_callback_safe(move |percentage: i32| {
+                    (progress.lock())(TranscriptionProgress::new(index, chunk_count, percentage));
+                });
+
+                // Timestamps are relative to the chunk; segments in the part of the
+                // overlap the neighbouring chunk owns are left to that chunk
+                let offset = chunk.offset_centiseconds();
+                let owned_ms = chunk.owned_ms.clone();
+                let segment_callback = Arc::clone(&on_segment);
+                params.set_segment_callback_safe(move |mut data: SegmentCallbackData| {
+                    data.start_timestamp += offset;
+                    data.end_timestamp += offset;
+                    if owned_ms.contains(&centiseconds_to_ms(data.start_timestamp)) {
+                        (segment_callback.lock())(data);
+                    }
+                });
+
+                // Let Whisper stop early once cancellation is requested
+                let abort_flag = Arc::clone(&cancel_flag);
+                params.set_abort_callback_safe(move || abort_flag.load(Ordering::SeqCst));
+
+                // Create state and run transcription
+                let mut state = context.create_state()?;
+                let full_result = state.full(params, &audio_data[chunk.samples.clone()]);
+                if cancel_flag.load(Ordering::SeqCst) {
+                    return Err(anyhow::anyhow!(TRANSCRIPTION_CANCELLED));
+                }
+                full_result?;
+
+                // Extract transcribed segments with their timestamps and confidence.
+                // Token IDs from end-of-text on are special and timestamp tokens, not text.
+                let first_special_token = context.token_eot();
+                let chunk_segments: Vec<(Segment, f32)> = (0..state.full_n_segments())
+                    .filter_map(|i| state.get_segment(i))
+                    .filter_map(|segment| {
+                        let text = segment.to_str().ok()?;
+                        let probabilities: Vec<f32> = (0..segment.n_tokens())
+                            .filter_map(|j| segment.get_token(j))
+                            .filter(|token| token.token_id() < first_special_token)
+                            .map(|token| token.token_probability())
+                            .collect();
+                        Some((
+                            Segment::from_whisper(
+                                segment.start_timestamp() + offset,
+                                segment.end_timestamp() + offset,
+                                text,
+                            )
+                            .with_confidence(token_confidence(&probabilities)),
+                            segment.no_speech_probability(),
+                        ))
+                    })
+                    .collect();
+                let (chunk_segments, chunk_no_speech): (Vec<Segment>, Vec<f32>) =
+                    segment_filter.apply(chunk_segments).into_iter().unzip();
+
+                // With auto-detection, report the language Whisper settled on
+                if language.is_none() {
+                    language = language_code(state.full_lang_id_from_state());
+                }
+
+                // Chunks of one stretch of speech overlap and share words;
+                // separate stretches of speech don't
+                let chunk_text = join_segments(&chunk_segments);
+                let overlaps_previous =
+                    index > 0 && chunks[index - 1].samples.end > chunk.samples.start;
+                text = if overlaps_previous {
+                    stitch_texts(&text, &chunk_text)
+                } else {
+                    [text.as_str(), chunk_text.as_str()]
+                        .iter()
+                        .filter(|text| !text.is_empty())
+                        .copied()
+                        .collect::<Vec<_>>()
+                        .join(" ")
+                };
+                for (segment, probability) in chunk_segments.into_iter().zip(chunk_no_speech) {
+                    if chunk.owned_ms.contains(&segment.start_ms) {
+                        segments.push(segment);
+                        no_speech_probabilities.push(probability);
                     }
                 }
             }
 
-            Ok(result.trim().to_string())
+            let mut result = TranscriptionResult::new(
+                segments,
+                model.clone(),
+                audio_data.len(),
+                language,
+                translate,
+            )
+            .with_no_speech_probabilities(&no_speech_probabilities);
+            // A single chunk's text is its segments' text already
+            if chunks.len() > 1 {
+                result.text = text;
+            }
+            Ok(result)
         })
-        .map_err(|e: anyhow::Error| format!("Failed to transcribe audio: {}", e))?;
+    })?;
 
-    Ok(text)
+    Ok(result)
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    /// Tests for RMS calculation
-    mod rms_tests {
-        use super::*;
+/// Runs the transcription work unless it was already cancelled, reporting any
+/// failure after cancellation as `TRANSCRIPTION_CANCELLED`
+///
+/// Whisper's abort callback makes a cancelled run fail with a generic error,
+/// so the flag decides how the failure is reported.
+fn run_cancellable<T>(
+    cancel_flag: &AtomicBool,
+    work: impl FnOnce() -> anyhow::Result<T>,
+) -> Result<T, String> {
+    if cancel_flag.load(Ordering::SeqCst) {
+        log::info!("Transcription cancelled before it started");
+        return Err(TRANSCRIPTION_CANCELLED.to_string());
+    }
 
-        #[test]
-        fn test_calculate_rms_empty() {
-            let samples: Vec<f32> = vec![];
-            assert_eq!(calculate_rms(&samples), 0.0);
+    work().map_err(|e| {
+        if cancel_flag.load(Ordering::SeqCst) {
+            log::info!("Transcription cancelled");
+            TRANSCRIPTION_CANCELLED.to_string()
+        } else {
+            format!("Failed to transcribe audio: {}", e)
         }
+    })
+}
 
-        #[test]
-        fn test_calculate_rms_silence() {
-            let samples: Vec<f32> = vec![0.0; 1000];
-            assert_eq!(calculate_rms(&samples), 0.0);
-        }
+/// Checks that a file is in a format `load_audio_samples` can read
+///
+/// WAV files are recognized by their header; compressed formats by their extension,
+/// since symphonia only finds out whether it can decode a file while probing it.
+/// Missing paths and directories are reported as such rather than as unreadable audio.
+fn check_audio_format(path: &Path) -> Result<(), String> {
+    let metadata = std::fs::metadata(path).map_err(|e| match e.kind() {
+        std::io::ErrorKind::NotFound => format!("'{}' does not exist", path.display()),
+        _ => format!("Cannot read '{}': {}", path.display(), e),
+    })?;
+    if !metadata.is_file() {
+        return Err(format!("'{}' is not a file", path.display()));
+    }
 
-        #[test]
-        fn test_calculate_rms_constant_signal() {
-            // RMS of constant signal equals the absolute value
-            let samples: Vec<f32> = vec![0.5; 1000];
-            let rms = calculate_rms(&samples);
+    if is_wav_file(path).map_err(|e| format!("{:#}", e))? {
+        return Ok(());
+    }
+
+    #[cfg(feature = "compressed-audio")]
+    if crate::audio::decode::is_supported_extension(path) {
+        return Ok(());
+    }
+
+    Err(unsupported_format_error(path))
+}
+
+#[cfg(feature = "compressed-audio")]
+fn unsupported_format_error(path: &Path) -> String {
+    format!(
+        "'{}' is not a supported audio file. Supported formats: wav, {}",
+        path.display(),
+        crate::audio::decode::SUPPORTED_EXTENSIONS.join(", ")
+    )
+}
+
+#[cfg(not(feature = "compressed-audio"))]
+fn unsupported_format_error(path: &Path) -> String {
+    format!(
+        "'{}' is not a WAV file. Convert it to WAV to transcribe it",
+        path.display()
+    )
+}
+
+/// Reads an audio file into 16kHz mono f32 samples, converting other formats
+///
+/// WAV files are read directly; anything else is decoded with symphonia when the
+/// `compressed-audio` feature is enabled.
+fn load_audio_samples(audio_path: &str) -> Result<Vec<f32>, String> {
+    let path = Path::new(audio_path);
+
+    #[cfg(feature = "compressed-audio")]
+    {
+        let is_wav =
+            is_wav_file(path).map_err(|e| format!("Failed to read audio file: {:#}", e))?;
+        if !is_wav {
+            return crate::audio::decode::decode_file(path).map_err(|e| e.to_string());
+        }
+    }
+
+    read_wav(path).map_err(|e| format!("Failed to read audio file: {:#}", e))
+}
+
+/// Detects the spoken language of an audio file using the configured model
+///
+/// # Arguments
+/// * `audio_path` - Path to the WAV audio file to analyze
+///
+/// # Returns
+/// * `Ok(DetectedLanguage)` with the most likely language and its probability
+/// * `Err(String)` if the audio could not be read or detection failed
+#[tauri::command]
+pub async fn detect_language(audio_path: String) -> Result<DetectedLanguage, String> {
+    let settings = get_settings()
+        .await
+        .map_err(|e| format!("Failed to get settings: {}", e))?;
+
+    log::info!(
+        "Detecting language of {} with model: {}",
+        audio_path,
+        settings.model
+    );
+
+    let model_path =
+        crate::models::downloader::ModelDownloader::new().get_model_path(&settings.model);
+    if !model_path.exists() {
+        return Err(format!(
+            "Model '{}' not found. Please download it first.",
+            settings.model
+        ));
+    }
+
+    // Run the CPU-intensive detection in a separate thread using oneshot channel
+    let (tx, rx) = tokio::sync::oneshot::channel();
+
+    std::thread::spawn(move || {
+        let result = detect_language_blocking(&audio_path, model_path, &settings);
+        let _ = tx.send(result);
+    });
+
+    rx.await.map_err(|_| {
+        log::error!("Language detection thread exited without sending a result");
+        worker_crashed_error("language detection")
+    })?
+}
+
+/// Loads a model in the background, emitting `model-preload-start` before
+/// and `model-preload-complete` after the load
+///
+/// # Arguments
+/// * `model_id` - Model being loaded
+/// * `model_path` - Path of the model file
+/// * `emit` - Emits an event with its payload
+/// * `load` - Loads the model into the cache; runs on a blocking thread
+///
+/// # Returns
+/// * `Ok(())` if the model is loaded
+/// * `Err(String)` if the file doesn't exist, without emitting any event, or if loading failed
+async fn preload_with(
+    model_id: &str,
+    model_path: &Path,
+    emit: impl Fn(&str, serde_json::Value),
+    load: impl FnOnce() -> Result<(), String> + Send + 'static,
+) -> Result<(), String> {
+    if !model_path.exists() {
+        return Err(format!(
+            "Model '{}' not found. Please download it first.",
+            model_id
+        ));
+    }
+
+    emit(
+        "model-preload-start",
+        serde_json::json!({ "modelId": model_id }),
+    );
+    let result = tokio::task::spawn_blocking(load)
+        .await
+        .unwrap_or_else(|_| Err(worker_crashed_error("model preload")));
+    emit(
+        "model-preload-complete",
+        serde_json::json!({
+            "modelId": model_id,
+            "success": result.is_ok(),
+            "error": result.as_ref().err(),
+        }),
+    );
+    result
+}
+
+/// Loads a model into the model cache without transcribing anything, so the
+/// next transcription with it starts right away
+///
+/// The model is loaded with the GPU setting from settings and stays cached
+/// like any other model, subject to the unload timeout. `model-preload-start`
+/// and `model-preload-complete` (`{ modelId, success, error }`) events are
+/// emitted around the load.
+///
+/// # Arguments
+/// * `app` - Application handle used to emit the preload events
+/// * `model_id` - Name of the Whisper model to load (e.g., "base", "small")
+///
+/// # Returns
+/// * `Ok(())` once the model is loaded
+/// * `Err(String)` if the model is not downloaded or failed to load
+#[tauri::command]
+pub async fn preload_model(app: AppHandle, model_id: String) -> Result<(), String> {
+    let use_gpu = get_settings()
+        .await
+        .map_err(|e| format!("Failed to get settings: {}", e))?
+        .use_gpu;
+    let model_path = crate::models::downloader::ModelDownloader::new().get_model_path(&model_id);
+
+    log::info!("Preloading model: {} (GPU: {})", model_id, use_gpu);
+    let load_id = model_id.clone();
+    let load_path = model_path.clone();
+    preload_with(
+        &model_id,
+        &model_path,
+        |event, payload| {
+            let _ = app.emit(event, payload);
+        },
+        move || {
+            get_model_cache()
+                .get_or_load(&load_id, load_path, use_gpu)
+                .map(drop)
+                .map_err(|e| format!("Failed to load model: {}", e))
+        },
+    )
+    .await
+}
+
+/// Returns true if a model is currently loaded in the model cache
+///
+/// # Arguments
+/// * `model_id` - Name of the Whisper model (e.g., "base")
+#[tauri::command]
+pub fn is_model_loaded(model_id: String) -> bool {
+    get_model_cache()
+        .get_cached_info()
+        .iter()
+        .any(|slot| slot.model_id == model_id)
+}
+
+/// Returns the models currently loaded in memory, most recently used first
+#[tauri::command]
+pub fn get_loaded_models() -> Vec<String> {
+    get_model_cache().loaded_models()
+}
+
+/// Returns which models are loaded, how long they have been idle and
+/// whether they run on the GPU
+#[tauri::command]
+pub fn get_cache_info() -> CacheInfo {
+    get_model_cache().cache_info()
+}
+
+/// Unloads every cached model to free its memory right away
+///
+/// Transcriptions that are running keep their model until they finish.
+/// Emits `model-unloaded` with the IDs of the unloaded models.
+#[tauri::command]
+pub fn unload_model(app: AppHandle) {
+    let cache = get_model_cache();
+    let models = cache.loaded_models();
+    cache.unload();
+    let _ = app.emit("model-unloaded", serde_json::json!({ "models": models }));
+}
+
+/// Returns how many seconds an unused model stays loaded (0 = never unloaded)
+///
+/// Changed through the `modelUnloadTimeoutSecs` setting; the new timeout
+/// applies from the next cleanup check on.
+#[tauri::command]
+pub fn get_cache_timeout() -> u64 {
+    get_model_cache().timeout_secs()
+}
+
+/// Returns true if models are loaded on the CPU because loading them on the GPU failed
+///
+/// The GPU is tried again after settings are saved.
+#[tauri::command]
+pub fn is_gpu_unavailable() -> bool {
+    get_model_cache().gpu_unavailable()
+}
+
+/// Blocking language detection function to be run in a separate thread
+fn detect_language_blocking(
+    audio_path: &str,
+    model_path: PathBuf,
+    settings: &Settings,
+) -> Result<DetectedLanguage, String> {
+    let threads = settings.transcription_threads.max(1);
+
+    let audio_data = load_audio_samples(audio_path)?;
+    if is_audio_silent_or_too_short(
+        &audio_data,
+        settings.silence_rms_threshold,
+        settings.min_audio_ms,
+    ) {
+        return Err("Audio is too short or silent to detect a language".to_string());
+    }
+
+    let cache = get_model_cache();
+    let guard = cache
+        .get_or_load(&settings.model, model_path, settings.use_gpu)
+        .map_err(|e| format!("Failed to load model: {}", e))?;
+
+    guard
+        .with_context(|context| {
+            let mut state = context.create_state()?;
+            state.pcm_to_mel(&audio_data, threads)?;
+            let (lang_id, probabilities) = state.lang_detect(0, threads)?;
+
+            let language = language_code(lang_id)
+                .ok_or_else(|| anyhow::anyhow!("Unknown language id {}", lang_id))?;
+            let probability = usize::try_from(lang_id)
+                .ok()
+                .and_then(|index| probabilities.get(index).copied())
+                .unwrap_or(0.0);
+
+            log::info!(
+                "Detected language: {} ({:.1}%)",
+                language,
+                probability * 100.0
+            );
+
+            Ok(DetectedLanguage {
+                language,
+                probability,
+            })
+        })
+        .map_err(|e| format!("Failed to detect language: {}", e))
+}
+
+/// Cancels the transcription currently in progress, if any, and every
+/// transcription queued behind it
+///
+/// Cancelling when nothing is running has no effect, since every
+/// transcription clears the flag when it starts.
+///
+/// # Arguments
+/// * `state` - Application state holding the cancellation flag
+#[tauri::command]
+pub fn cancel_transcription(state: State<'_, Arc<AppState>>) {
+    log::info!("Cancelling transcription");
+    state.transcription_cancelled.store(true, Ordering::SeqCst);
+    state.transcription_queue.cancel_waiting();
+}
+
+/// Returns the number of transcriptions waiting for their turn, not counting
+/// the one running
+///
+/// # Arguments
+/// * `state` - Application state holding the transcription queue
+#[tauri::command]
+pub fn get_transcription_queue_length(state: State<'_, Arc<AppState>>) -> usize {
+    state.transcription_queue.waiting()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Tests for RMS calculation
+    mod rms_tests {
+        use super::*;
+
+        #[test]
+        fn test_calculate_rms_empty() {
+            let samples: Vec<f32> = vec![];
+            assert_eq!(calculate_rms(&samples), 0.0);
+        }
+
+        #[test]
+        fn test_calculate_rms_silence() {
+            let samples: Vec<f32> = vec![0.0; 1000];
+            assert_eq!(calculate_rms(&samples), 0.0);
+        }
+
+        #[test]
+        fn test_calculate_rms_constant_signal() {
+            // RMS of constant signal equals the absolute value
+            let samples: Vec<f32> = vec![0.5; 1000];
+            let rms = calculate_rms(&samples);
             assert!((rms - 0.5).abs() < 0.0001);
         }
 
@@ -280,69 +1591,120 @@ mod tests {
     mod silence_detection_tests {
         use super::*;
 
+        /// (silence threshold, minimum duration) pairs every test runs with: the
+        /// defaults, a noisy condenser mic and a quiet setup allowing short clips
+        const CONFIGS: [(f32, u32); 3] = [
+            (SILENCE_RMS_THRESHOLD, DEFAULT_MIN_AUDIO_MS),
+            (0.02, 500),
+            (0.0001, 100),
+        ];
+
         #[test]
         fn test_empty_audio_is_silent() {
-            let samples: Vec<f32> = vec![];
-            assert!(is_audio_silent_or_too_short(&samples));
+            for (threshold, min_ms) in CONFIGS {
+                let samples: Vec<f32> = vec![];
+                assert!(is_audio_silent_or_too_short(&samples, threshold, min_ms));
+            }
         }
 
         #[test]
         fn test_short_audio_is_silent() {
-            // Less than MIN_AUDIO_SAMPLES
-            let samples: Vec<f32> = vec![0.5; MIN_AUDIO_SAMPLES - 1];
-            assert!(is_audio_silent_or_too_short(&samples));
+            for (threshold, min_ms) in CONFIGS {
+                // Less than the minimum duration
+                let samples: Vec<f32> = vec![0.5; ms_to_samples(min_ms) - 1];
+                assert!(is_audio_silent_or_too_short(&samples, threshold, min_ms));
+            }
         }
 
         #[test]
         fn test_silent_audio_detected() {
-            // Enough samples but all zeros
-            let samples: Vec<f32> = vec![0.0; MIN_AUDIO_SAMPLES + 1000];
-            assert!(is_audio_silent_or_too_short(&samples));
+            for (threshold, min_ms) in CONFIGS {
+                // Enough samples but all zeros
+                let samples: Vec<f32> = vec![0.0; ms_to_samples(min_ms) + 1000];
+                assert!(is_audio_silent_or_too_short(&samples, threshold, min_ms));
+            }
         }
 
         #[test]
         fn test_very_quiet_audio_detected() {
-            // Samples below threshold
-            let samples: Vec<f32> = vec![0.0001; MIN_AUDIO_SAMPLES + 1000];
-            assert!(is_audio_silent_or_too_short(&samples));
+            for (threshold, min_ms) in CONFIGS {
+                // Samples well below threshold
+                let samples: Vec<f32> = vec![threshold * 0.1; ms_to_samples(min_ms) + 1000];
+                assert!(is_audio_silent_or_too_short(&samples, threshold, min_ms));
+            }
         }
 
         #[test]
         fn test_normal_audio_not_silent() {
-            // Normal speech-like amplitude
-            let samples: Vec<f32> = vec![0.1; MIN_AUDIO_SAMPLES + 1000];
-            assert!(!is_audio_silent_or_too_short(&samples));
+            for (threshold, min_ms) in CONFIGS {
+                // Normal speech-like amplitude
+                let samples: Vec<f32> = vec![0.1; ms_to_samples(min_ms) + 1000];
+                assert!(!is_audio_silent_or_too_short(&samples, threshold, min_ms));
+            }
         }
 
         #[test]
         fn test_loud_audio_not_silent() {
-            // Loud signal
-            let samples: Vec<f32> = vec![0.5; MIN_AUDIO_SAMPLES + 1000];
-            assert!(!is_audio_silent_or_too_short(&samples));
+            for (threshold, min_ms) in CONFIGS {
+                // Loud signal
+                let samples: Vec<f32> = vec![0.5; ms_to_samples(min_ms) + 1000];
+                assert!(!is_audio_silent_or_too_short(&samples, threshold, min_ms));
+            }
         }
 
         #[test]
         fn test_threshold_boundary() {
-            // Just below threshold
-            let below_threshold: Vec<f32> =
-                vec![SILENCE_RMS_THRESHOLD * 0.5; MIN_AUDIO_SAMPLES + 100];
-            assert!(is_audio_silent_or_too_short(&below_threshold));
+            for (threshold, min_ms) in CONFIGS {
+                let len = ms_to_samples(min_ms) + 100;
+
+                // Just below threshold
+                let below_threshold: Vec<f32> = vec![threshold * 0.5; len];
+                assert!(is_audio_silent_or_too_short(
+                    &below_threshold,
+                    threshold,
+                    min_ms
+                ));
 
-            // Just above threshold
-            let above_threshold: Vec<f32> =
-                vec![SILENCE_RMS_THRESHOLD * 2.0; MIN_AUDIO_SAMPLES + 100];
-            assert!(!is_audio_silent_or_too_short(&above_threshold));
+                // Just above threshold
+                let above_threshold: Vec<f32> = vec![threshold * 2.0; len];
+                assert!(!is_audio_silent_or_too_short(
+                    &above_threshold,
+                    threshold,
+                    min_ms
+                ));
+            }
         }
 
         #[test]
         fn test_exact_minimum_samples() {
-            // Exactly MIN_AUDIO_SAMPLES passes the length check (we use < not <=)
-            let samples: Vec<f32> = vec![0.5; MIN_AUDIO_SAMPLES];
-            assert!(!is_audio_silent_or_too_short(&samples));
+            for (threshold, min_ms) in CONFIGS {
+                // Exactly the minimum passes the length check (we use < not <=)
+                let samples: Vec<f32> = vec![0.5; ms_to_samples(min_ms)];
+                assert!(!is_audio_silent_or_too_short(&samples, threshold, min_ms));
+
+                // One less than minimum should fail length check
+                let samples_minus_one: Vec<f32> = vec![0.5; ms_to_samples(min_ms) - 1];
+                assert!(is_audio_silent_or_too_short(
+                    &samples_minus_one,
+                    threshold,
+                    min_ms
+                ));
+            }
+        }
+
+        #[test]
+        fn test_zero_minimum_disables_length_check() {
+            let samples: Vec<f32> = vec![0.5; 10];
+            assert!(!is_audio_silent_or_too_short(
+                &samples,
+                SILENCE_RMS_THRESHOLD,
+                0
+            ));
+        }
 
-            // One less than minimum should fail length check
-            let samples_minus_one: Vec<f32> = vec![0.5; MIN_AUDIO_SAMPLES - 1];
-            assert!(is_audio_silent_or_too_short(&samples_minus_one));
+        #[test]
+        fn test_default_minimum_is_quarter_second() {
+            assert_eq!(ms_to_samples(DEFAULT_MIN_AUDIO_MS), 4000);
         }
 
         #[test]
@@ -361,7 +1723,572 @@ mod tests {
                 })
                 .collect();
 
-            assert!(!is_audio_silent_or_too_short(&samples));
+            for (threshold, min_ms) in CONFIGS {
+                assert!(!is_audio_silent_or_too_short(&samples, threshold, min_ms));
+            }
+        }
+    }
+
+    /// Tests for trimming silence and finding the speech to transcribe
+    mod trim_silence_tests {
+        use super::*;
+
+        const SECOND: usize = SAMPLE_RATE as usize;
+
+        #[test]
+        fn test_all_silence_trims_everything() {
+            let samples = vec![0.0; 3 * SECOND];
+            assert!(trim_silence(&samples, SILENCE_RMS_THRESHOLD).is_empty());
+            assert!(trim_silence(&[], SILENCE_RMS_THRESHOLD).is_empty());
+        }
+
+        #[test]
+        fn test_trims_silence_around_speech_with_guard() {
+            // 1 s of silence, 2 s of speech, 1.5 s of silence
+            let mut samples = vec![0.0; SECOND];
+            samples.extend(vec![0.3; 2 * SECOND]);
+            samples.extend(vec![0.0; 3 * SECOND / 2]);
+
+            let speech = trim_silence(&samples, SILENCE_RMS_THRESHOLD);
+
+            assert_eq!(
+                speech,
+                SECOND - TRIM_GUARD_SAMPLES..3 * SECOND + TRIM_GUARD_SAMPLES
+            );
+            // Starts on a whole centisecond
+            assert_eq!(speech.start % (SAMPLE_RATE as usize / 100), 0);
+        }
+
+        #[test]
+        fn test_guard_never_reaches_past_the_audio() {
+            // Speech right at both edges
+            let mut samples = vec![0.3; SECOND / 10];
+            samples.extend(vec![0.0; SECOND]);
+            samples.extend(vec![0.3; SECOND / 10]);
+
+            assert_eq!(
+                trim_silence(&samples, SILENCE_RMS_THRESHOLD),
+                0..samples.len()
+            );
+        }
+
+        #[test]
+        fn test_no_silence_keeps_everything() {
+            let samples: Vec<f32> = (0..2 * SECOND)
+                .map(|i| 0.5 * (i as f32 * 0.1).sin())
+                .collect();
+
+            assert_eq!(trim_silence(&samples, 0.02), 0..samples.len());
+        }
+
+        #[test]
+        fn test_speech_ranges_split_at_pauses_only_with_vad() {
+            // 1 s of speech, 1 s pause, 1 s of speech
+            let mut samples = vec![0.3; SECOND];
+            samples.extend(vec![0.0; SECOND]);
+            samples.extend(vec![0.3; SECOND]);
+
+            assert_eq!(
+                speech_ranges(&samples, SILENCE_RMS_THRESHOLD, None),
+                vec![0..samples.len()]
+            );
+            assert_eq!(
+                speech_ranges(&samples, SILENCE_RMS_THRESHOLD, Some(500)).len(),
+                2
+            );
+            // The pause is shorter than the minimum
+            assert_eq!(
+                speech_ranges(&samples, SILENCE_RMS_THRESHOLD, Some(1500)).len(),
+                1
+            );
+        }
+
+        #[test]
+        fn test_speech_ranges_fall_back_to_trimming_for_steady_audio() {
+            // A steady level never rises above its own noise floor
+            let samples = vec![0.3; 2 * SECOND];
+
+            assert_eq!(
+                speech_ranges(&samples, SILENCE_RMS_THRESHOLD, Some(500)),
+                vec![0..samples.len()]
+            );
+        }
+    }
+
+    /// Tests for initial prompt selection
+    mod initial_prompt_tests {
+        use super::*;
+
+        #[test]
+        fn test_no_prompt() {
+            assert_eq!(resolve_initial_prompt(None, None), None);
+        }
+
+        #[test]
+        fn test_settings_prompt_used_without_override() {
+            let prompt = resolve_initial_prompt(None, Some("Kubernetes".to_string()));
+            assert_eq!(prompt.as_deref(), Some("Kubernetes"));
+        }
+
+        #[test]
+        fn test_override_wins_over_settings() {
+            let prompt = resolve_initial_prompt(
+                Some("ibuprofen".to_string()),
+                Some("Kubernetes".to_string()),
+            );
+            assert_eq!(prompt.as_deref(), Some("ibuprofen"));
+        }
+
+        #[test]
+        fn test_blank_override_falls_back_to_settings() {
+            let prompt =
+                resolve_initial_prompt(Some("   ".to_string()), Some("Kubernetes".to_string()));
+            assert_eq!(prompt.as_deref(), Some("Kubernetes"));
+        }
+
+        #[test]
+        fn test_nul_characters_are_removed() {
+            let prompt = resolve_initial_prompt(Some("Kuber\0netes".to_string()), None);
+            assert_eq!(prompt.as_deref(), Some("Kubernetes"));
+        }
+
+        #[test]
+        fn test_long_prompt_is_capped() {
+            let long_prompt = "a".repeat(MAX_INITIAL_PROMPT_CHARS) + "tail";
+            let prompt = resolve_initial_prompt(Some(long_prompt), None).unwrap();
+            assert_eq!(prompt.chars().count(), MAX_INITIAL_PROMPT_CHARS);
+            assert!(prompt.ends_with("tail"));
+        }
+    }
+
+    /// Tests for cancellation handling
+    mod cancellation_tests {
+        use super::*;
+
+        #[test]
+        fn test_completed_run_returns_result() {
+            let flag = AtomicBool::new(false);
+            assert_eq!(run_cancellable(&flag, || Ok(42)), Ok(42));
+        }
+
+        #[test]
+        fn test_flag_set_before_run_skips_work() {
+            let flag = AtomicBool::new(true);
+            let mut ran = false;
+
+            let result = run_cancellable(&flag, || {
+                ran = true;
+                Ok(())
+            });
+
+            assert_eq!(result, Err(TRANSCRIPTION_CANCELLED.to_string()));
+            assert!(!ran);
+        }
+
+        #[test]
+        fn test_flag_set_during_run_reports_cancellation() {
+            let flag = AtomicBool::new(false);
+
+            // Mimics Whisper: the abort callback sees the flag and the run fails
+            let result: Result<(), String> = run_cancellable(&flag, || {
+                flag.store(true, Ordering::SeqCst);
+                Err(anyhow::anyhow!("whisper_full failed"))
+            });
+
+            assert_eq!(result, Err(TRANSCRIPTION_CANCELLED.to_string()));
+        }
+
+        #[test]
+        fn test_failure_without_cancellation_is_an_error() {
+            let flag = AtomicBool::new(false);
+
+            let result: Result<(), String> =
+                run_cancellable(&flag, || Err(anyhow::anyhow!("out of memory")));
+
+            assert_eq!(
+                result,
+                Err("Failed to transcribe audio: out of memory".to_string())
+            );
+        }
+
+        #[test]
+        fn test_cancelled_transcription_returns_before_loading_model() {
+            let samples: Vec<f32> = (0..SAMPLE_RATE as usize)
+                .map(|i| 0.5 * (i as f32 * 0.1).sin())
+                .collect();
+            let options = TranscriptionOptions::from_settings(&Settings::default(), None).unwrap();
+            let progress_calls = Arc::new(Mutex::new(0));
+            let counter = Arc::clone(&progress_calls);
+
+            // The model doesn't exist, so getting past the check would fail differently
+            let result = transcribe_samples(
+                &samples,
+                "missing".to_string(),
+                PathBuf::from("/nonexistent/ggml-missing.bin"),
+                options,
+                move |_| *counter.lock() += 1,
+                |_| {},
+                Arc::new(AtomicBool::new(true)),
+            );
+
+            assert_eq!(result.unwrap_err(), TRANSCRIPTION_CANCELLED);
+            assert_eq!(*progress_calls.lock(), 0);
+        }
+    }
+
+    /// Tests for progress event throttling
+    mod progress_throttle_tests {
+        use super::*;
+
+        #[test]
+        fn test_reports_each_whole_percentage_once() {
+            let mut throttle = ProgressThrottle::default();
+
+            assert_eq!(throttle.update(0), Some(0));
+            assert_eq!(throttle.update(0), None);
+            assert_eq!(throttle.update(5), Some(5));
+            assert_eq!(throttle.update(3), None);
+            assert_eq!(throttle.update(5), None);
+        }
+
+        #[test]
+        fn test_clamps_out_of_range_values() {
+            let mut throttle = ProgressThrottle::default();
+
+            assert_eq!(throttle.update(-10), Some(0));
+            assert_eq!(throttle.update(150), Some(100));
+            assert_eq!(throttle.update(100), None);
+        }
+
+        #[test]
+        fn test_chunk_progress_covers_whole_transcription() {
+            let percentage = |chunk, chunk_count, chunk_percentage| {
+                TranscriptionProgress::new(chunk, chunk_count, chunk_percentage).percentage
+            };
+
+            assert_eq!(percentage(0, 1, 42), 42);
+            assert_eq!(percentage(0, 4, 0), 0);
+            assert_eq!(percentage(0, 4, 100), 25);
+            assert_eq!(percentage(1, 4, 50), 37);
+            assert_eq!(percentage(3, 4, 100), 100);
+            assert_eq!(percentage(2, 4, 150), 75);
+            assert_eq!(percentage(0, 0, 60), 60);
+        }
+    }
+
+    /// Tests for routing files to the WAV reader or the compressed audio decoder
+    #[cfg(feature = "compressed-audio")]
+    mod audio_format_tests {
+        use super::*;
+
+        fn fixture() -> String {
+            concat!(
+                env!("CARGO_MANIFEST_DIR"),
+                "/tests/fixtures/tone-8khz-mono.flac"
+            )
+            .to_string()
+        }
+
+        #[test]
+        fn test_accepts_compressed_formats() {
+            assert!(check_audio_format(Path::new(&fixture())).is_ok());
+        }
+
+        #[test]
+        fn test_rejects_unknown_extension() {
+            let path =
+                std::env::temp_dir().join(format!("rustler_format_{}.txt", std::process::id()));
+            std::fs::write(&path, b"not audio").unwrap();
+
+            let err = check_audio_format(&path).unwrap_err();
+            assert!(err.contains("not a supported audio file"), "{}", err);
+            std::fs::remove_file(&path).ok();
+        }
+
+        #[test]
+        fn test_rejects_missing_paths_and_directories() {
+            let missing = std::env::temp_dir().join("rustler_format_missing.mp3");
+            let err = check_audio_format(&missing).unwrap_err();
+            assert!(err.contains("does not exist"), "{}", err);
+
+            let err = check_audio_format(&std::env::temp_dir()).unwrap_err();
+            assert!(err.contains("is not a file"), "{}", err);
+        }
+
+        #[test]
+        fn test_non_audio_file_with_audio_extension_fails_to_load() {
+            let path =
+                std::env::temp_dir().join(format!("rustler_format_{}.mp3", std::process::id()));
+            std::fs::write(&path, b"this is a text file, not an mp3").unwrap();
+
+            assert!(check_audio_format(&path).is_ok());
+            assert!(load_audio_samples(&path.to_string_lossy()).is_err());
+            std::fs::remove_file(&path).ok();
+        }
+
+        #[test]
+        fn test_loads_compressed_file_through_decoder() {
+            let samples = load_audio_samples(&fixture()).unwrap();
+            assert_eq!(samples.len(), 1600);
+        }
+    }
+
+    /// Tests for reading audio files before transcription
+    mod audio_loading_tests {
+        use super::*;
+
+        #[test]
+        fn test_truncated_wav_errors_instead_of_panicking() {
+            let path =
+                std::env::temp_dir().join(format!("rustler_truncated_{}.wav", std::process::id()));
+            let spec = hound::WavSpec {
+                channels: 1,
+                sample_rate: SAMPLE_RATE,
+                bits_per_sample: 16,
+                sample_format: hound::SampleFormat::Int,
+            };
+            let mut writer = hound::WavWriter::create(&path, spec).unwrap();
+            for i in 0..8000 {
+                writer.write_sample((i % 100) as i16).unwrap();
+            }
+            writer.finalize().unwrap();
+
+            // Cut the data chunk short of the length declared in the header,
+            // in the middle of a sample
+            let len = std::fs::metadata(&path).unwrap().len();
+            let file = std::fs::OpenOptions::new().write(true).open(&path).unwrap();
+            file.set_len(len / 2 + 1).unwrap();
+            drop(file);
+
+            let err = load_audio_samples(path.to_str().unwrap()).unwrap_err();
+            assert!(err.starts_with("Failed to read audio file"), "{}", err);
+            std::fs::remove_file(&path).ok();
+        }
+
+        #[test]
+        fn test_worker_crashed_error_names_the_task() {
+            let err = worker_crashed_error("transcription");
+            assert!(
+                err.starts_with("The transcription thread crashed"),
+                "{}",
+                err
+            );
+        }
+    }
+
+    /// Tests for sequential batch transcription
+    mod batch_tests {
+        use super::*;
+
+        fn paths(names: &[&str]) -> Vec<String> {
+            names.iter().map(|name| name.to_string()).collect()
+        }
+
+        fn transcribed(text: &str) -> TranscriptionResult {
+            TranscriptionResult::new(
+                vec![Segment::from_whisper(0, 100, text)],
+                "base".to_string(),
+                16000,
+                None,
+                false,
+            )
+        }
+
+        #[tokio::test]
+        async fn test_failure_does_not_abort_batch() {
+            let flag = AtomicBool::new(false);
+            let mut events = Vec::new();
+
+            let results = run_batch(
+                paths(&["a.wav", "corrupt.mp3", "c.m4a"]),
+                &flag,
+                |path| async move {
+                    if path == "corrupt.mp3" {
+                        Err("Corrupt audio file: bad frame".to_string())
+                    } else {
+                        Ok(transcribed(&path))
+                    }
+                },
+                |progress| events.push((progress.index, progress.status)),
+            )
+            .await;
+
+            let statuses: Vec<_> = results.iter().map(|result| result.status).collect();
+            assert_eq!(
+                statuses,
+                vec![
+                    BatchFileStatus::Done,
+                    BatchFileStatus::Failed,
+                    BatchFileStatus::Done
+                ]
+            );
+            assert_eq!(results[2].result.as_ref().unwrap().text, "c.m4a");
+            assert_eq!(
+                results[1].error.as_deref(),
+                Some("Corrupt audio file: bad frame")
+            );
+            assert_eq!(
+                events,
+                vec![
+                    (0, BatchFileStatus::Processing),
+                    (0, BatchFileStatus::Done),
+                    (1, BatchFileStatus::Processing),
+                    (1, BatchFileStatus::Failed),
+                    (2, BatchFileStatus::Processing),
+                    (2, BatchFileStatus::Done),
+                ]
+            );
+        }
+
+        #[tokio::test]
+        async fn test_cancel_stops_after_current_file() {
+            let flag = AtomicBool::new(false);
+            let mut transcribed_paths = Vec::new();
+
+            let results = run_batch(
+                paths(&["a.wav", "b.wav", "c.wav"]),
+                &flag,
+                |path| {
+                    transcribed_paths.push(path.clone());
+                    // Cancelled while the first file is being transcribed
+                    flag.store(true, Ordering::SeqCst);
+                    async move { Ok(transcribed(&path)) }
+                },
+                |_| {},
+            )
+            .await;
+
+            assert_eq!(transcribed_paths, vec!["a.wav"]);
+            assert_eq!(results.len(), 3);
+            assert_eq!(results[0].status, BatchFileStatus::Done);
+            assert_eq!(results[1].status, BatchFileStatus::Skipped);
+            assert_eq!(results[2].status, BatchFileStatus::Skipped);
+        }
+
+        #[tokio::test]
+        async fn test_cancelled_transcription_skips_rest() {
+            let flag = AtomicBool::new(false);
+
+            let results = run_batch(
+                paths(&["a.wav", "b.wav"]),
+                &flag,
+                |_| async { Err(TRANSCRIPTION_CANCELLED.to_string()) },
+                |_| {},
+            )
+            .await;
+
+            assert_eq!(results[0].status, BatchFileStatus::Failed);
+            assert_eq!(results[1].status, BatchFileStatus::Skipped);
+        }
+    }
+
+    /// Tests for loading models ahead of time
+    mod preload_tests {
+        use super::*;
+        use parking_lot::Mutex;
+
+        /// Runs a preload, returning its result and the emitted events
+        async fn preload(
+            model_path: &Path,
+            load: impl FnOnce() -> Result<(), String> + Send + 'static,
+        ) -> (Result<(), String>, Vec<(String, serde_json::Value)>) {
+            let events = Mutex::new(Vec::new());
+            let result = preload_with(
+                "base",
+                model_path,
+                |event, payload| events.lock().push((event.to_string(), payload)),
+                load,
+            )
+            .await;
+            (result, events.into_inner())
+        }
+
+        #[tokio::test]
+        async fn test_emits_start_then_complete_around_load() {
+            let model_path =
+                std::env::temp_dir().join(format!("rustler_preload_{}.bin", std::process::id()));
+            std::fs::write(&model_path, b"ggml").unwrap();
+
+            let (result, events) = preload(&model_path, || Ok(())).await;
+            assert!(result.is_ok());
+            assert_eq!(
+                events,
+                vec![
+                    (
+                        "model-preload-start".to_string(),
+                        serde_json::json!({ "modelId": "base" })
+                    ),
+                    (
+                        "model-preload-complete".to_string(),
+                        serde_json::json!({ "modelId": "base", "success": true, "error": null })
+                    ),
+                ]
+            );
+
+            let (result, events) = preload(&model_path, || {
+                Err("Failed to load model: bad magic".to_string())
+            })
+            .await;
+            assert_eq!(result, Err("Failed to load model: bad magic".to_string()));
+            assert_eq!(events.len(), 2);
+            assert_eq!(events[1].1["success"], false);
+            assert_eq!(events[1].1["error"], "Failed to load model: bad magic");
+
+            std::fs::remove_file(&model_path).unwrap();
+        }
+
+        #[tokio::test]
+        async fn test_missing_model_fails_before_loading() {
+            let model_path = std::env::temp_dir().join("rustler_preload_missing_model.bin");
+
+            let (result, events) =
+                preload(&model_path, || panic!("tried to load a missing model")).await;
+
+            assert_eq!(
+                result,
+                Err("Model 'base' not found. Please download it first.".to_string())
+            );
+            assert!(events.is_empty());
+        }
+    }
+
+    /// Tests for forwarding partial segments without blocking Whisper
+    mod partial_segment_tests {
+        use super::*;
+
+        #[test]
+        fn test_queues_segment_with_converted_timestamps() {
+            let (tx, mut rx) = tokio::sync::mpsc::channel(4);
+
+            assert!(send_partial_segment(&tx, 150, 420, " Hello there. "));
+            assert_eq!(
+                rx.try_recv().unwrap(),
+                Segment {
+                    start_ms: 1500,
+                    end_ms: 4200,
+                    text: "Hello there.".to_string(),
+                    confidence: None,
+                }
+            );
+        }
+
+        #[test]
+        fn test_drops_segments_when_buffer_is_full() {
+            let (tx, mut rx) = tokio::sync::mpsc::channel(1);
+
+            assert!(send_partial_segment(&tx, 0, 100, "first"));
+            assert!(!send_partial_segment(&tx, 100, 200, "second"));
+            assert_eq!(rx.try_recv().unwrap().text, "first");
+            assert!(rx.try_recv().is_err());
+        }
+
+        #[test]
+        fn test_skips_empty_segments_and_closed_channel() {
+            let (tx, rx) = tokio::sync::mpsc::channel(4);
+
+            assert!(!send_partial_segment(&tx, 0, 100, "   "));
+            drop(rx);
+            assert!(!send_partial_segment(&tx, 0, 100, "late"));
         }
     }
 }
@@ -0,0 +1,277 @@
+//! Detection of hotkeys that are already taken before they are registered
+
+use crate::hotkey::HotkeyAction;
+use serde::Serialize;
+use std::collections::HashMap;
+use tauri_plugin_global_shortcut::Shortcut;
+
+/// Name reported when a shortcut is already bound to one of our own actions
+const OWN_APP_NAME: &str = "Rustler";
+
+/// Well-known system shortcuts as (shortcut, owner, function)
+#[cfg(target_os = "macos")]
+pub const SYSTEM_SHORTCUTS: &[(&str, &str, &str)] = &[
+    ("Cmd+Space", "macOS", "Spotlight search"),
+    ("Cmd+Alt+Space", "macOS", "Finder search window"),
+    ("Ctrl+Space", "macOS", "Switching input sources"),
+    ("Cmd+Tab", "macOS", "Switching applications"),
+    ("Cmd+Shift+3", "macOS", "Screenshot"),
+    ("Cmd+Shift+4", "macOS", "Screenshot of a selection"),
+    ("Cmd+Shift+5", "macOS", "Screenshot and recording options"),
+    ("Cmd+Ctrl+Q", "macOS", "Locking the screen"),
+    ("Cmd+Ctrl+Space", "macOS", "Character viewer"),
+];
+
+/// Well-known system shortcuts as (shortcut, owner, function)
+#[cfg(target_os = "windows")]
+pub const SYSTEM_SHORTCUTS: &[(&str, &str, &str)] = &[
+    ("Alt+Tab", "Windows", "Switching applications"),
+    ("Alt+F4", "Windows", "Closing the active window"),
+    ("Super+L", "Windows", "Locking the screen"),
+    ("Super+D", "Windows", "Showing the desktop"),
+    ("Super+E", "Windows", "File Explorer"),
+    ("Super+Space", "Windows", "Switching input languages"),
+    ("Super+Shift+S", "Windows", "Snipping Tool"),
+    ("Ctrl+Shift+Escape", "Windows", "Task Manager"),
+];
+
+/// Well-known system shortcuts as (shortcut, owner, function)
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+pub const SYSTEM_SHORTCUTS: &[(&str, &str, &str)] = &[
+    (
+        "Alt+Tab",
+        "the desktop environment",
+        "Switching applications",
+    ),
+    (
+        "Alt+F4",
+        "the desktop environment",
+        "Closing the active window",
+    ),
+    ("Super+L", "the desktop environment", "Locking the screen"),
+    (
+        "Super+Space",
+        "the desktop environment",
+        "Switching input sources",
+    ),
+    (
+        "Ctrl+Alt+T",
+        "the desktop environment",
+        "Opening a terminal",
+    ),
+    ("Ctrl+Alt+Delete", "the desktop environment", "Logging out"),
+];
+
+/// Result of checking a shortcut for conflicts
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HotkeyConflictResult {
+    /// Whether the shortcut is already taken
+    pub conflict: bool,
+    /// Application or system component that owns the shortcut, if known
+    pub conflicting_app: Option<String>,
+    /// Human-readable description of the conflict
+    pub reason: Option<String>,
+}
+
+impl HotkeyConflictResult {
+    fn free() -> Self {
+        Self {
+            conflict: false,
+            conflicting_app: None,
+            reason: None,
+        }
+    }
+
+    fn taken(conflicting_app: Option<String>, reason: String) -> Self {
+        Self {
+            conflict: true,
+            conflicting_app,
+            reason: Some(reason),
+        }
+    }
+}
+
+/// Tries whether a shortcut can be grabbed right now
+pub trait ShortcutProbe {
+    /// Registers the shortcut and immediately unregisters it again
+    ///
+    /// # Returns
+    /// * `Ok(())` if the shortcut could be registered
+    /// * `Err(String)` if registering failed, usually because another
+    ///   application holds the shortcut
+    fn probe(&self, shortcut: Shortcut) -> Result<(), String>;
+}
+
+/// Checks a shortcut for conflicts with system shortcuts, our own hotkeys
+/// and other applications
+///
+/// # Arguments
+/// * `shortcut` - Shortcut to check (e.g., "CommandOrControl+Shift+Space")
+/// * `own_bindings` - Hotkeys currently registered by this app
+/// * `system_shortcuts` - Well-known system shortcuts (see `SYSTEM_SHORTCUTS`)
+/// * `probe` - Registers the shortcut in test mode to detect other applications
+///
+/// # Returns
+/// * `Ok(HotkeyConflictResult)` describing the first conflict found, if any
+/// * `Err(String)` if the shortcut cannot be parsed
+pub fn check_conflict(
+    shortcut: &str,
+    own_bindings: &HashMap<HotkeyAction, String>,
+    system_shortcuts: &[(&str, &str, &str)],
+    probe: &dyn ShortcutProbe,
+) -> Result<HotkeyConflictResult, String> {
+    let parsed: Shortcut = shortcut
+        .parse()
+        .map_err(|e| format!("Invalid shortcut format '{}': {}", shortcut, e))?;
+
+    let matches = |other: &str| other.parse::<Shortcut>().is_ok_and(|other| other == parsed);
+
+    if let Some((_, owner, function)) = system_shortcuts.iter().find(|(s, _, _)| matches(s)) {
+        return Ok(HotkeyConflictResult::taken(
+            Some(owner.to_string()),
+            format!("{} is reserved by {} for {}", shortcut, owner, function),
+        ));
+    }
+
+    // Registering a shortcut we already hold would fail too, so name the action instead
+    if let Some(action) = HotkeyAction::ALL
+        .into_iter()
+        .find(|action| own_bindings.get(action).is_some_and(|s| matches(s)))
+    {
+        return Ok(HotkeyConflictResult::taken(
+            Some(OWN_APP_NAME.to_string()),
+            format!("{} is already used for {}", shortcut, action.description()),
+        ));
+    }
+
+    match probe.probe(parsed) {
+        Ok(()) => Ok(HotkeyConflictResult::free()),
+        Err(e) => {
+            log::info!("Shortcut '{}' could not be registered: {}", shortcut, e);
+            Ok(HotkeyConflictResult::taken(
+                None,
+                format!("{} is already in use by another application", shortcut),
+            ))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+
+    /// Probe that records probed shortcuts and fails for the given ones
+    struct MockProbe {
+        taken: Vec<Shortcut>,
+        probed: RefCell<Vec<Shortcut>>,
+    }
+
+    impl MockProbe {
+        fn new(taken: &[&str]) -> Self {
+            Self {
+                taken: taken.iter().map(|s| s.parse().unwrap()).collect(),
+                probed: RefCell::new(Vec::new()),
+            }
+        }
+    }
+
+    impl ShortcutProbe for MockProbe {
+        fn probe(&self, shortcut: Shortcut) -> Result<(), String> {
+            self.probed.borrow_mut().push(shortcut);
+            if self.taken.contains(&shortcut) {
+                Err("HotKey already registered".to_string())
+            } else {
+                Ok(())
+            }
+        }
+    }
+
+    const TEST_SYSTEM_SHORTCUTS: &[(&str, &str, &str)] =
+        &[("Cmd+Space", "macOS", "Spotlight search")];
+
+    #[test]
+    fn test_free_shortcut() {
+        let probe = MockProbe::new(&[]);
+
+        let result = check_conflict(
+            "Ctrl+Shift+K",
+            &HashMap::new(),
+            TEST_SYSTEM_SHORTCUTS,
+            &probe,
+        )
+        .unwrap();
+
+        assert_eq!(result, HotkeyConflictResult::free());
+        assert_eq!(probe.probed.borrow().len(), 1);
+    }
+
+    #[test]
+    fn test_conflict_detected_by_probe() {
+        let probe = MockProbe::new(&["Ctrl+Shift+K"]);
+
+        let result = check_conflict(
+            "Ctrl+Shift+K",
+            &HashMap::new(),
+            TEST_SYSTEM_SHORTCUTS,
+            &probe,
+        )
+        .unwrap();
+
+        assert!(result.conflict);
+        assert_eq!(result.conflicting_app, None);
+        assert!(result.reason.unwrap().contains("another application"));
+    }
+
+    #[test]
+    fn test_known_system_shortcut() {
+        let probe = MockProbe::new(&[]);
+
+        // "Super" and "Cmd" are the same modifier
+        let result = check_conflict(
+            "Super+Space",
+            &HashMap::new(),
+            TEST_SYSTEM_SHORTCUTS,
+            &probe,
+        )
+        .unwrap();
+
+        assert!(result.conflict);
+        assert_eq!(result.conflicting_app.as_deref(), Some("macOS"));
+        assert!(result.reason.unwrap().contains("Spotlight"));
+        // Known shortcuts are never grabbed, not even briefly
+        assert!(probe.probed.borrow().is_empty());
+    }
+
+    #[test]
+    fn test_own_binding_conflict() {
+        let probe = MockProbe::new(&[]);
+        let own = HashMap::from([(HotkeyAction::PasteLast, "Ctrl+Shift+V".to_string())]);
+
+        let result = check_conflict("Shift+Ctrl+V", &own, TEST_SYSTEM_SHORTCUTS, &probe).unwrap();
+
+        assert!(result.conflict);
+        assert_eq!(result.conflicting_app.as_deref(), Some(OWN_APP_NAME));
+        assert!(result.reason.unwrap().contains("Paste Last Transcription"));
+    }
+
+    #[test]
+    fn test_invalid_shortcut_is_an_error() {
+        let probe = MockProbe::new(&[]);
+        assert!(check_conflict(
+            "Ctrl+NotAKey",
+            &HashMap::new(),
+            TEST_SYSTEM_SHORTCUTS,
+            &probe
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_system_shortcuts_parse() {
+        for (shortcut, _, _) in SYSTEM_SHORTCUTS {
+            assert!(shortcut.parse::<Shortcut>().is_ok(), "{}", shortcut);
+        }
+    }
+}
@@ -1,27 +1,177 @@
 #![allow(dead_code)]
 
-use anyhow::Result;
+use anyhow::{bail, Result};
 use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+pub mod conflict;
+pub mod native;
 
 #[cfg(target_os = "linux")]
 pub mod wayland;
 
+/// Default minimum time between two triggers of the same hotkey, in milliseconds
+pub const DEFAULT_HOTKEY_DEBOUNCE_MS: u64 = 500;
+
+/// Upper bound for the hotkey debounce window, in milliseconds
+pub const MAX_HOTKEY_DEBOUNCE_MS: u64 = 5000;
+
+/// Action triggered by a global hotkey
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum HotkeyAction {
+    /// Start recording, or stop it if already recording
+    ToggleRecording,
+    /// Stop the current recording
+    StopRecording,
+    /// Paste the most recent transcription again
+    PasteLast,
+    /// Cancel the transcription in progress
+    CancelTranscription,
+}
+
+impl HotkeyAction {
+    /// Every hotkey action
+    pub const ALL: [HotkeyAction; 4] = [
+        HotkeyAction::ToggleRecording,
+        HotkeyAction::StopRecording,
+        HotkeyAction::PasteLast,
+        HotkeyAction::CancelTranscription,
+    ];
+
+    /// Identifier used when binding the action through the Wayland portal.
+    /// The toggle keeps "record-toggle" so existing portal bindings stay valid.
+    pub fn shortcut_id(self) -> &'static str {
+        match self {
+            HotkeyAction::ToggleRecording => "record-toggle",
+            HotkeyAction::StopRecording => "record-stop",
+            HotkeyAction::PasteLast => "paste-last",
+            HotkeyAction::CancelTranscription => "cancel-transcription",
+        }
+    }
+
+    /// Looks up the action bound under a Wayland portal shortcut ID
+    pub fn from_shortcut_id(id: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|action| action.shortcut_id() == id)
+    }
+
+    /// Human-readable description shown in the system shortcut dialog
+    pub fn description(self) -> &'static str {
+        match self {
+            HotkeyAction::ToggleRecording => "Toggle Recording",
+            HotkeyAction::StopRecording => "Stop Recording",
+            HotkeyAction::PasteLast => "Paste Last Transcription",
+            HotkeyAction::CancelTranscription => "Cancel Transcription",
+        }
+    }
+
+    /// Event emitted to the main window when the hotkey is pressed
+    pub fn event(self) -> &'static str {
+        match self {
+            HotkeyAction::ToggleRecording => "hotkey-triggered",
+            HotkeyAction::StopRecording => "hotkey-stop-recording",
+            HotkeyAction::PasteLast => "hotkey-paste-last",
+            HotkeyAction::CancelTranscription => "hotkey-cancel-transcription",
+        }
+    }
+}
+
+/// Suppresses hotkey triggers that follow the previous one too quickly,
+/// e.g. when a key bounces or the OS delivers a press twice
+///
+/// Clones share the time of the last trigger.
+#[derive(Debug, Clone)]
+pub struct HotkeyDebouncer {
+    /// Time of the last trigger that was let through
+    last_trigger: Arc<Mutex<Option<Instant>>>,
+    /// Minimum time between two triggers
+    window: Duration,
+}
+
+impl HotkeyDebouncer {
+    /// Creates a debouncer; a zero window lets every trigger through
+    pub fn new(window: Duration) -> Self {
+        Self {
+            last_trigger: Arc::new(Mutex::new(None)),
+            window,
+        }
+    }
+
+    /// Records a trigger happening now
+    ///
+    /// # Returns
+    /// true if the action should run, false if the trigger is within the
+    /// debounce window of the previous one
+    pub fn try_trigger(&self) -> bool {
+        self.try_trigger_at(Instant::now())
+    }
+
+    /// Records a trigger at the given time, see `try_trigger`
+    fn try_trigger_at(&self, now: Instant) -> bool {
+        let mut last_trigger = self.last_trigger.lock();
+
+        if let Some(previous) = *last_trigger {
+            if now.saturating_duration_since(previous) < self.window {
+                return false;
+            }
+        }
+
+        *last_trigger = Some(now);
+        true
+    }
+}
+
+/// Callback run when a registered hotkey is pressed
+pub type HotkeyCallback = Box<dyn Fn() + Send + Sync + 'static>;
+
+/// Grabs and releases system-wide shortcuts on behalf of `HotkeyManager`
+pub trait HotkeyBackend: Send + Sync {
+    /// Registers a shortcut so `callback` runs each time it is pressed
+    fn register(&self, shortcut: &str, callback: HotkeyCallback) -> Result<()>;
+
+    /// Releases a shortcut registered with `register`
+    fn unregister(&self, shortcut: &str) -> Result<()>;
+}
+
 /// Manages global keyboard shortcuts for the application
+///
+/// Shortcuts are registered with the OS through the attached backend. Without
+/// a backend, as on Wayland where the portal binds the shortcuts, the manager
+/// only tracks which shortcut each action is bound to.
 pub struct HotkeyManager {
-    /// Currently registered hotkey
-    current_hotkey: Arc<Mutex<Option<String>>>,
+    /// Currently registered hotkeys by action
+    current_hotkeys: Arc<Mutex<HashMap<HotkeyAction, String>>>,
+    /// Backend that registers the shortcuts, if hotkeys are grabbed by this app
+    backend: Mutex<Option<Arc<dyn HotkeyBackend>>>,
 }
 
 impl HotkeyManager {
-    /// Creates a new hotkey manager
+    /// Creates a new hotkey manager without a backend
     pub fn new() -> Self {
         Self {
-            current_hotkey: Arc::new(Mutex::new(None)),
+            current_hotkeys: Arc::new(Mutex::new(HashMap::new())),
+            backend: Mutex::new(None),
         }
     }
 
-    /// Registers a global hotkey with a callback
+    /// Creates a new hotkey manager that registers shortcuts through `backend`
+    pub fn with_backend(backend: Arc<dyn HotkeyBackend>) -> Self {
+        let manager = Self::new();
+        manager.set_backend(backend);
+        manager
+    }
+
+    /// Attaches the backend used to register shortcuts from now on
+    pub fn set_backend(&self, backend: Arc<dyn HotkeyBackend>) {
+        *self.backend.lock() = Some(backend);
+    }
+
+    /// Registers the global hotkey for toggling recording with a callback
     ///
     /// # Arguments
     /// * `shortcut` - The keyboard shortcut string (e.g., "CommandOrControl+Shift+Space")
@@ -30,57 +180,139 @@ impl HotkeyManager {
     /// # Returns
     /// * `Ok(())` if the hotkey was registered successfully
     /// * `Err` if registration failed
-    pub fn register<F>(&self, shortcut: String, _callback: F) -> Result<()>
+    pub fn register<F>(&self, shortcut: String, callback: F) -> Result<()>
     where
-        F: Fn() + Send + 'static,
+        F: Fn() + Send + Sync + 'static,
     {
-        log::info!("Registering hotkey: {}", shortcut);
+        self.register_action(HotkeyAction::ToggleRecording, shortcut, callback)
+    }
+
+    /// Registers the global hotkey for an action, replacing its previous binding
+    ///
+    /// # Arguments
+    /// * `action` - Action triggered by the hotkey
+    /// * `shortcut` - The keyboard shortcut string (e.g., "CommandOrControl+Shift+Space")
+    /// * `callback` - Function to call when the hotkey is triggered
+    ///
+    /// # Returns
+    /// * `Ok(())` if the hotkey was registered successfully
+    /// * `Err` if no backend is attached or registration failed; the action is
+    ///   left without a hotkey
+    pub fn register_action<F>(
+        &self,
+        action: HotkeyAction,
+        shortcut: String,
+        callback: F,
+    ) -> Result<()>
+    where
+        F: Fn() + Send + Sync + 'static,
+    {
+        log::info!("Registering hotkey for {:?}: {}", action, shortcut);
 
-        // Unregister previous hotkey if any
-        self.unregister()?;
+        let Some(backend) = self.backend.lock().clone() else {
+            bail!("No hotkey backend is available to register '{}'", shortcut);
+        };
 
-        // Store the new hotkey
-        *self.current_hotkey.lock() = Some(shortcut.clone());
+        // Unregister previous hotkey of this action if any
+        self.unregister_action(action)?;
 
-        // Note: Actual registration would be done through tauri-plugin-global-shortcut
-        // This is a placeholder for the manager structure
+        backend.register(&shortcut, Box::new(callback))?;
+        self.current_hotkeys.lock().insert(action, shortcut);
 
         log::info!("Hotkey registered successfully");
         Ok(())
     }
 
-    /// Unregisters the current global hotkey
+    /// Records the hotkey of an action that was bound outside of this manager,
+    /// e.g. through the Wayland portal
+    pub fn track_action(&self, action: HotkeyAction, shortcut: String) {
+        self.current_hotkeys.lock().insert(action, shortcut);
+    }
+
+    /// Unregisters all global hotkeys
     ///
     /// # Returns
-    /// * `Ok(())` if the hotkey was unregistered successfully
-    /// * `Err` if unregistration failed
+    /// * `Ok(())` if the hotkeys were unregistered successfully
+    /// * `Err` if a hotkey could not be released; the others are still unregistered
     pub fn unregister(&self) -> Result<()> {
-        let mut hotkey = self.current_hotkey.lock();
+        let hotkeys: Vec<String> = self
+            .current_hotkeys
+            .lock()
+            .drain()
+            .map(|(_, shortcut)| shortcut)
+            .collect();
+        if hotkeys.is_empty() {
+            return Ok(());
+        }
 
-        if let Some(ref shortcut) = *hotkey {
-            log::info!("Unregistering hotkey: {}", shortcut);
+        log::info!("Unregistering {} hotkey(s)", hotkeys.len());
+        let mut first_error = None;
+        let backend = self.backend.lock().clone();
+        if let Some(backend) = backend {
+            for shortcut in &hotkeys {
+                if let Err(e) = backend.unregister(shortcut) {
+                    log::warn!("{:#}", e);
+                    first_error.get_or_insert(e);
+                }
+            }
+        }
 
-            // Note: Actual unregistration would be done through tauri-plugin-global-shortcut
-            // This is a placeholder for the manager structure
+        match first_error {
+            Some(e) => Err(e),
+            None => {
+                log::info!("Hotkeys unregistered successfully");
+                Ok(())
+            }
+        }
+    }
 
-            *hotkey = None;
-            log::info!("Hotkey unregistered successfully");
+    /// Unregisters the global hotkey of a single action
+    ///
+    /// # Returns
+    /// * `Ok(Some(shortcut))` with the shortcut that was bound to the action
+    /// * `Ok(None)` if the action had no hotkey
+    /// * `Err` if the hotkey could not be released; it is no longer tracked either way
+    pub fn unregister_action(&self, action: HotkeyAction) -> Result<Option<String>> {
+        let removed = self.current_hotkeys.lock().remove(&action);
+
+        if let Some(ref shortcut) = removed {
+            log::info!("Unregistering hotkey for {:?}: {}", action, shortcut);
+            let backend = self.backend.lock().clone();
+            if let Some(backend) = backend {
+                backend.unregister(shortcut)?;
+            }
         }
 
-        Ok(())
+        Ok(removed)
     }
 
-    /// Gets the currently registered hotkey
+    /// Gets the hotkey registered for toggling recording
     ///
     /// # Returns
     /// The keyboard shortcut string, or empty string if none registered
     pub fn get_current(&self) -> String {
-        self.current_hotkey
+        self.get_current_for(HotkeyAction::ToggleRecording)
+    }
+
+    /// Gets the hotkey registered for an action
+    ///
+    /// # Returns
+    /// The keyboard shortcut string, or empty string if none registered
+    pub fn get_current_for(&self, action: HotkeyAction) -> String {
+        self.current_hotkeys
             .lock()
-            .as_ref()
+            .get(&action)
             .cloned()
             .unwrap_or_default()
     }
+
+    /// Gets every registered hotkey
+    ///
+    /// # Returns
+    /// The keyboard shortcut strings by action
+    pub fn get_current_all(&self) -> HashMap<HotkeyAction, String> {
+        self.current_hotkeys.lock().clone()
+    }
 }
 
 impl Default for HotkeyManager {
@@ -92,7 +324,45 @@ impl Default for HotkeyManager {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+    /// Backend that keeps registered callbacks so tests can press the shortcuts
+    #[derive(Default)]
+    struct FakeBackend {
+        callbacks: Mutex<HashMap<String, HotkeyCallback>>,
+    }
+
+    impl FakeBackend {
+        /// Runs the callback of a shortcut, returning false if it isn't registered
+        fn press(&self, shortcut: &str) -> bool {
+            match self.callbacks.lock().get(shortcut) {
+                Some(callback) => {
+                    callback();
+                    true
+                }
+                None => false,
+            }
+        }
+    }
+
+    impl HotkeyBackend for FakeBackend {
+        fn register(&self, shortcut: &str, callback: HotkeyCallback) -> Result<()> {
+            if shortcut == "Taken" {
+                bail!("'{}' is registered by another application", shortcut);
+            }
+            self.callbacks.lock().insert(shortcut.to_string(), callback);
+            Ok(())
+        }
+
+        fn unregister(&self, shortcut: &str) -> Result<()> {
+            self.callbacks.lock().remove(shortcut);
+            Ok(())
+        }
+    }
+
+    fn manager() -> HotkeyManager {
+        HotkeyManager::with_backend(Arc::new(FakeBackend::default()))
+    }
 
     #[test]
     fn test_new_creates_manager_with_no_hotkey() {
@@ -108,7 +378,7 @@ mod tests {
 
     #[test]
     fn test_register_stores_hotkey() {
-        let manager = HotkeyManager::new();
+        let manager = manager();
 
         let result = manager.register("Ctrl+Shift+A".to_string(), || {});
 
@@ -118,7 +388,7 @@ mod tests {
 
     #[test]
     fn test_register_replaces_previous_hotkey() {
-        let manager = HotkeyManager::new();
+        let manager = manager();
 
         // Register first hotkey
         manager.register("Ctrl+A".to_string(), || {}).unwrap();
@@ -131,7 +401,7 @@ mod tests {
 
     #[test]
     fn test_unregister_clears_hotkey() {
-        let manager = HotkeyManager::new();
+        let manager = manager();
 
         // Register a hotkey
         manager.register("Ctrl+C".to_string(), || {}).unwrap();
@@ -145,7 +415,7 @@ mod tests {
 
     #[test]
     fn test_unregister_when_no_hotkey_is_ok() {
-        let manager = HotkeyManager::new();
+        let manager = manager();
 
         // Unregistering when nothing is registered should be fine
         let result = manager.unregister();
@@ -155,7 +425,7 @@ mod tests {
 
     #[test]
     fn test_unregister_multiple_times_is_ok() {
-        let manager = HotkeyManager::new();
+        let manager = manager();
 
         manager.register("Ctrl+D".to_string(), || {}).unwrap();
 
@@ -169,14 +439,14 @@ mod tests {
 
     #[test]
     fn test_get_current_returns_empty_for_new_manager() {
-        let manager = HotkeyManager::new();
+        let manager = manager();
         let current = manager.get_current();
         assert!(current.is_empty());
     }
 
     #[test]
     fn test_register_with_various_shortcut_formats() {
-        let manager = HotkeyManager::new();
+        let manager = manager();
 
         // Test various shortcut formats
         let shortcuts = vec![
@@ -198,7 +468,7 @@ mod tests {
 
     #[test]
     fn test_register_with_closure_capturing_state() {
-        let manager = HotkeyManager::new();
+        let manager = manager();
         let was_called = Arc::new(AtomicBool::new(false));
         let was_called_clone = Arc::clone(&was_called);
 
@@ -209,13 +479,79 @@ mod tests {
         let result = manager.register("Ctrl+X".to_string(), callback);
         assert!(result.is_ok());
 
-        // Note: The callback is stored but not invoked by register
-        // This tests that the closure is properly accepted
+        // Registering alone doesn't invoke the callback
+        assert!(!was_called.load(Ordering::SeqCst));
+    }
+
+    #[test]
+    fn test_pressing_registered_hotkey_fires_callback() {
+        let backend = Arc::new(FakeBackend::default());
+        let manager = HotkeyManager::with_backend(backend.clone());
+        let presses = Arc::new(AtomicUsize::new(0));
+        let presses_clone = Arc::clone(&presses);
+
+        manager
+            .register("Ctrl+X".to_string(), move || {
+                presses_clone.fetch_add(1, Ordering::SeqCst);
+            })
+            .unwrap();
+
+        assert!(backend.press("Ctrl+X"));
+        assert!(backend.press("Ctrl+X"));
+        assert_eq!(presses.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn test_replacing_hotkey_releases_previous_shortcut() {
+        let backend = Arc::new(FakeBackend::default());
+        let manager = HotkeyManager::with_backend(backend.clone());
+
+        manager.register("Ctrl+A".to_string(), || {}).unwrap();
+        manager.register("Ctrl+B".to_string(), || {}).unwrap();
+
+        assert!(!backend.press("Ctrl+A"));
+        assert!(backend.press("Ctrl+B"));
+
+        manager.unregister().unwrap();
+        assert!(!backend.press("Ctrl+B"));
+    }
+
+    #[test]
+    fn test_failed_registration_leaves_action_unbound() {
+        let manager = manager();
+        manager.register("Ctrl+A".to_string(), || {}).unwrap();
+
+        let result = manager.register("Taken".to_string(), || {});
+
+        assert!(result.is_err());
+        assert_eq!(manager.get_current(), "");
+    }
+
+    #[test]
+    fn test_register_without_backend_fails() {
+        let manager = HotkeyManager::new();
+
+        assert!(manager.register("Ctrl+A".to_string(), || {}).is_err());
+        assert_eq!(manager.get_current(), "");
+    }
+
+    #[test]
+    fn test_track_action_records_binding_without_backend() {
+        let manager = HotkeyManager::new();
+
+        manager.track_action(HotkeyAction::PasteLast, "Ctrl+Shift+V".to_string());
+
+        assert_eq!(
+            manager.get_current_for(HotkeyAction::PasteLast),
+            "Ctrl+Shift+V"
+        );
+        assert!(manager.unregister().is_ok());
+        assert!(manager.get_current_all().is_empty());
     }
 
     #[test]
     fn test_thread_safety_of_manager() {
-        let manager = Arc::new(HotkeyManager::new());
+        let manager = Arc::new(manager());
         let mut handles = vec![];
 
         // Spawn multiple threads accessing the manager
@@ -242,6 +578,118 @@ mod tests {
         assert!(current.is_empty() || !current.is_empty());
     }
 
+    #[test]
+    fn test_get_current_all_reports_every_action() {
+        let manager = manager();
+
+        manager
+            .register("Ctrl+Shift+Space".to_string(), || {})
+            .unwrap();
+        manager
+            .register_action(
+                HotkeyAction::StopRecording,
+                "Ctrl+Shift+S".to_string(),
+                || {},
+            )
+            .unwrap();
+        manager
+            .register_action(HotkeyAction::PasteLast, "Ctrl+Shift+V".to_string(), || {})
+            .unwrap();
+        manager
+            .register_action(
+                HotkeyAction::CancelTranscription,
+                "Escape".to_string(),
+                || {},
+            )
+            .unwrap();
+
+        let all = manager.get_current_all();
+        assert_eq!(all.len(), 4);
+        assert_eq!(all[&HotkeyAction::ToggleRecording], "Ctrl+Shift+Space");
+        assert_eq!(all[&HotkeyAction::StopRecording], "Ctrl+Shift+S");
+        assert_eq!(all[&HotkeyAction::PasteLast], "Ctrl+Shift+V");
+        assert_eq!(all[&HotkeyAction::CancelTranscription], "Escape");
+        assert_eq!(manager.get_current(), "Ctrl+Shift+Space");
+    }
+
+    #[test]
+    fn test_unregister_action_keeps_other_bindings() {
+        let manager = manager();
+        manager.register("Ctrl+A".to_string(), || {}).unwrap();
+        manager
+            .register_action(HotkeyAction::PasteLast, "Ctrl+B".to_string(), || {})
+            .unwrap();
+
+        let removed = manager.unregister_action(HotkeyAction::PasteLast).unwrap();
+
+        assert_eq!(removed.as_deref(), Some("Ctrl+B"));
+        assert_eq!(manager.get_current_for(HotkeyAction::PasteLast), "");
+        assert_eq!(manager.get_current(), "Ctrl+A");
+        assert_eq!(
+            manager.unregister_action(HotkeyAction::PasteLast).unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_shortcut_ids_round_trip() {
+        for action in HotkeyAction::ALL {
+            assert_eq!(
+                HotkeyAction::from_shortcut_id(action.shortcut_id()),
+                Some(action)
+            );
+        }
+        assert_eq!(HotkeyAction::from_shortcut_id("unknown"), None);
+        assert_eq!(HotkeyAction::ToggleRecording.event(), "hotkey-triggered");
+    }
+
+    #[test]
+    fn test_action_serializes_camel_case() {
+        let hotkeys: HashMap<HotkeyAction, String> =
+            serde_json::from_str(r#"{"pasteLast": "Ctrl+V"}"#).unwrap();
+        assert_eq!(hotkeys[&HotkeyAction::PasteLast], "Ctrl+V");
+    }
+
+    #[test]
+    fn test_debounce_suppresses_rapid_second_trigger() {
+        let debouncer = HotkeyDebouncer::new(Duration::from_millis(DEFAULT_HOTKEY_DEBOUNCE_MS));
+        let recording_starts = AtomicUsize::new(0);
+        let on_hotkey = |at: Instant| {
+            if debouncer.try_trigger_at(at) {
+                recording_starts.fetch_add(1, Ordering::SeqCst);
+            }
+        };
+
+        let start = Instant::now();
+        on_hotkey(start);
+        on_hotkey(start + Duration::from_millis(120));
+
+        assert_eq!(recording_starts.load(Ordering::SeqCst), 1);
+
+        // Measured from the last accepted trigger, not the suppressed one
+        on_hotkey(start + Duration::from_millis(500));
+        assert_eq!(recording_starts.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn test_zero_debounce_lets_every_trigger_through() {
+        let debouncer = HotkeyDebouncer::new(Duration::ZERO);
+        let now = Instant::now();
+
+        assert!(debouncer.try_trigger_at(now));
+        assert!(debouncer.try_trigger_at(now));
+    }
+
+    #[test]
+    fn test_debouncer_clones_share_state() {
+        let debouncer = HotkeyDebouncer::new(Duration::from_secs(1));
+        let clone = debouncer.clone();
+        let now = Instant::now();
+
+        assert!(debouncer.try_trigger_at(now));
+        assert!(!clone.try_trigger_at(now + Duration::from_millis(10)));
+    }
+
     /// Tests for parking_lot mutex behavior
     mod mutex_tests {
         use parking_lot::Mutex;
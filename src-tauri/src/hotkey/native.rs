@@ -0,0 +1,60 @@
+//! Global hotkeys registered with the OS through tauri-plugin-global-shortcut
+//!
+//! Used on X11, macOS and Windows. On Wayland, applications can't grab keys
+//! themselves and hotkeys go through the portal in `wayland` instead.
+
+use super::{HotkeyBackend, HotkeyCallback};
+use anyhow::{Context, Result};
+use tauri::AppHandle;
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutState};
+
+/// Hotkey backend grabbing shortcuts with the platform API
+/// (e.g., `XGrabKey` on X11, `RegisterHotKey` on Windows)
+pub struct GlobalShortcutBackend {
+    app: AppHandle,
+}
+
+impl GlobalShortcutBackend {
+    /// Creates a backend registering shortcuts for the given app;
+    /// the global shortcut plugin must already be initialized
+    pub fn new(app: AppHandle) -> Self {
+        Self { app }
+    }
+}
+
+/// Parses a shortcut string into the plugin's shortcut type
+fn parse_shortcut(shortcut: &str) -> Result<Shortcut> {
+    shortcut
+        .parse()
+        .map_err(|e| anyhow::anyhow!("Invalid shortcut format '{}': {}", shortcut, e))
+}
+
+impl HotkeyBackend for GlobalShortcutBackend {
+    fn register(&self, shortcut: &str, callback: HotkeyCallback) -> Result<()> {
+        let parsed = parse_shortcut(shortcut)?;
+
+        self.app
+            .global_shortcut()
+            .on_shortcut(parsed, move |_app, shortcut, event| {
+                log::info!(
+                    "Shortcut callback fired! shortcut={:?}, state={:?}",
+                    shortcut,
+                    event.state
+                );
+                // Only trigger on key press, not release
+                if event.state == ShortcutState::Pressed {
+                    callback();
+                }
+            })
+            .with_context(|| format!("Failed to register hotkey '{}'", shortcut))
+    }
+
+    fn unregister(&self, shortcut: &str) -> Result<()> {
+        let parsed = parse_shortcut(shortcut)?;
+
+        self.app
+            .global_shortcut()
+            .unregister(parsed)
+            .with_context(|| format!("Failed to unregister hotkey '{}'", shortcut))
+    }
+}
@@ -9,6 +9,7 @@
 
 use ashpd::desktop::global_shortcuts::{GlobalShortcuts, NewShortcut};
 use parking_lot::Mutex;
+use std::collections::{HashMap, HashSet};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use tokio::sync::mpsc;
@@ -55,12 +56,69 @@ pub fn clear_stored_shortcuts(app_id: &str) {
     }
 }
 
+/// Time the availability probe waits for each portal call; short, since it runs
+/// while the settings UI is open
+const PROBE_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(2);
+
+/// Checks whether the GlobalShortcuts portal responds, without binding any shortcut
+///
+/// Connects to the portal and opens (then closes) a session. The result replaces
+/// the unavailable flag, so a successful probe clears a stale negative from an
+/// earlier failed registration, and a failed one makes registration fail fast.
+///
+/// # Returns
+/// `true` if the portal answered both calls in time
+pub async fn probe_portal() -> bool {
+    let result = probe_session().await;
+    if let Err(e) = &result {
+        log::info!("Wayland: GlobalShortcuts portal probe failed: {}", e);
+    }
+    record_probe_result(result.is_ok())
+}
+
+/// Opens and closes a portal session, with a timeout on every call
+async fn probe_session() -> Result<(), String> {
+    let shortcuts = tokio::time::timeout(PROBE_TIMEOUT, GlobalShortcuts::new())
+        .await
+        .map_err(|_| "timed out connecting to the portal".to_string())?
+        .map_err(|e| e.to_string())?;
+
+    let session = tokio::time::timeout(PROBE_TIMEOUT, shortcuts.create_session())
+        .await
+        .map_err(|_| "timed out creating a session".to_string())?
+        .map_err(|e| e.to_string())?;
+
+    // The portal answered; failing to close the unused session doesn't change that
+    if let Err(e) = session.close().await {
+        log::debug!("Wayland: Failed to close probe session: {}", e);
+    }
+    Ok(())
+}
+
+/// Stores the outcome of a probe in the unavailable flag, returning whether the portal is available
+fn record_probe_result(available: bool) -> bool {
+    PORTAL_UNAVAILABLE.store(!available, Ordering::Relaxed);
+    available
+}
+
+/// A shortcut to bind through the GlobalShortcuts portal
+pub struct ShortcutBinding {
+    /// Unique identifier for the shortcut (e.g., "record-toggle")
+    pub id: String,
+    /// Human-readable description (e.g., "Toggle Recording")
+    pub description: String,
+    /// Preferred key combination (e.g., "Alt+E")
+    pub preferred_trigger: String,
+}
+
 /// Manages global shortcuts on Wayland via xdg-desktop-portal
 pub struct WaylandHotkeyManager {
     /// Channel to send shutdown signal to the listener task
     shutdown_tx: Arc<Mutex<Option<mpsc::Sender<()>>>>,
     /// Handle to the spawned listener task so we can await its termination
     listener_handle: Arc<Mutex<Option<tokio::task::JoinHandle<()>>>>,
+    /// IDs of the bound shortcuts whose activations are forwarded to the callback
+    enabled_ids: Arc<Mutex<HashSet<String>>>,
 }
 
 impl WaylandHotkeyManager {
@@ -69,33 +127,34 @@ impl WaylandHotkeyManager {
         Self {
             shutdown_tx: Arc::new(Mutex::new(None)),
             listener_handle: Arc::new(Mutex::new(None)),
+            enabled_ids: Arc::new(Mutex::new(HashSet::new())),
         }
     }
 
-    /// Registers a global shortcut and starts listening for activation events
+    /// Registers global shortcuts in a single portal request and starts
+    /// listening for activation events
     ///
     /// # Arguments
-    /// * `shortcut_id` - Unique identifier for the shortcut (e.g., "record-toggle")
-    /// * `description` - Human-readable description (e.g., "Toggle Recording")
-    /// * `preferred_trigger` - Preferred key combination (e.g., "Alt+E")
-    /// * `callback` - Function to call when the shortcut is activated
+    /// * `bindings` - Shortcuts to bind
+    /// * `callback` - Function called with the shortcut ID when a shortcut is activated
     ///
-    /// Returns the actual trigger description from the GNOME dialog if available.
+    /// Returns the actual trigger descriptions from the GNOME dialog by shortcut ID,
+    /// for the shortcuts where one is available.
     pub async fn register<F>(
         &self,
-        shortcut_id: &str,
-        description: &str,
-        preferred_trigger: &str,
+        bindings: &[ShortcutBinding],
         callback: F,
-    ) -> Result<Option<String>, String>
+    ) -> Result<HashMap<String, String>, String>
     where
-        F: Fn() + Send + Sync + 'static,
+        F: Fn(&str) + Send + Sync + 'static,
     {
-        log::info!(
-            "Wayland: Registering shortcut '{}' with trigger '{}'",
-            shortcut_id,
-            preferred_trigger
-        );
+        for binding in bindings {
+            log::info!(
+                "Wayland: Registering shortcut '{}' with trigger '{}'",
+                binding.id,
+                binding.preferred_trigger
+            );
+        }
 
         // Check if we've already determined the portal is unavailable
         if PORTAL_UNAVAILABLE.load(Ordering::Relaxed) {
@@ -159,9 +218,14 @@ impl WaylandHotkeyManager {
             format!("Failed to create shortcuts session: {}. Please use the in-app recording button instead.", e)
         })?;
 
-        // Define the shortcut
-        let new_shortcut =
-            NewShortcut::new(shortcut_id, description).preferred_trigger(preferred_trigger);
+        // Define the shortcuts
+        let new_shortcuts: Vec<NewShortcut> = bindings
+            .iter()
+            .map(|binding| {
+                NewShortcut::new(binding.id.as_str(), binding.description.as_str())
+                    .preferred_trigger(binding.preferred_trigger.as_str())
+            })
+            .collect();
 
         log::info!(
             "Wayland: A system dialog may appear - please configure the shortcut in the dialog"
@@ -171,7 +235,7 @@ impl WaylandHotkeyManager {
         // Timeout is 60 seconds because GNOME shows a dialog that requires user interaction
         let request = tokio::time::timeout(
             std::time::Duration::from_secs(60),
-            shortcuts.bind_shortcuts(&session, &[new_shortcut], None)
+            shortcuts.bind_shortcuts(&session, &new_shortcuts, None)
         )
         .await
         .map_err(|_| {
@@ -199,23 +263,31 @@ impl WaylandHotkeyManager {
                 }
             })?;
 
-        // Extract the actual trigger description from the response — this is what
+        // Extract the actual trigger descriptions from the response — this is what
         // the user chose in the GNOME dialog, which may differ from preferred_trigger
-        let actual_trigger = response
+        let actual_triggers: HashMap<String, String> = response
             .shortcuts()
             .iter()
-            .find(|s| s.id() == shortcut_id)
-            .map(|s| s.trigger_description().to_string());
-
-        if let Some(ref trigger) = actual_trigger {
-            log::info!(
-                "Wayland: Shortcut bound successfully with trigger: {}",
-                trigger
-            );
-        } else {
-            log::info!("Wayland: Shortcut bound successfully (no trigger description in response)");
+            .filter(|s| bindings.iter().any(|binding| binding.id == s.id()))
+            .map(|s| (s.id().to_string(), s.trigger_description().to_string()))
+            .collect();
+
+        for binding in bindings {
+            match actual_triggers.get(&binding.id) {
+                Some(trigger) => log::info!(
+                    "Wayland: Shortcut '{}' bound successfully with trigger: {}",
+                    binding.id,
+                    trigger
+                ),
+                None => log::info!(
+                    "Wayland: Shortcut '{}' bound successfully (no trigger description in response)",
+                    binding.id
+                ),
+            }
         }
 
+        *self.enabled_ids.lock() = bindings.iter().map(|b| b.id.clone()).collect();
+
         // Create shutdown channel
         let (shutdown_tx, mut shutdown_rx) = mpsc::channel::<()>(1);
         *self.shutdown_tx.lock() = Some(shutdown_tx);
@@ -223,8 +295,7 @@ impl WaylandHotkeyManager {
         // Create oneshot channel so the listener task can confirm it's ready
         let (ready_tx, ready_rx) = tokio::sync::oneshot::channel::<Result<(), String>>();
 
-        // Clone shortcut_id for the async task
-        let shortcut_id_owned = shortcut_id.to_string();
+        let enabled_ids = Arc::clone(&self.enabled_ids);
         let callback = Arc::new(callback);
 
         // Spawn task to listen for activation events.
@@ -257,9 +328,10 @@ impl WaylandHotkeyManager {
                 tokio::select! {
                     Some(activated) = activated_stream.next() => {
                         log::info!("Wayland: Shortcut activated: {}", activated.shortcut_id());
-                        if activated.shortcut_id() == shortcut_id_owned {
-                            log::info!("Wayland: Shortcut '{}' triggered!", shortcut_id_owned);
-                            callback();
+                        let shortcut_id = activated.shortcut_id();
+                        if enabled_ids.lock().contains(shortcut_id) {
+                            log::info!("Wayland: Shortcut '{}' triggered!", shortcut_id);
+                            callback(shortcut_id);
                         }
                     }
                     _ = shutdown_rx.recv() => {
@@ -303,7 +375,7 @@ impl WaylandHotkeyManager {
             }
         }
 
-        Ok(actual_trigger)
+        Ok(actual_triggers)
     }
 
     /// Sends shutdown signal and awaits the listener task to fully terminate.
@@ -347,11 +419,26 @@ impl WaylandHotkeyManager {
         let _ = self.listener_handle.lock().take();
     }
 
-    /// Unregisters the current shortcut and stops the listener
+    /// Unregisters all shortcuts and stops the listener
     pub fn unregister(&self) {
+        self.enabled_ids.lock().clear();
         self.stop_listener();
     }
 
+    /// Stops forwarding activations of a single shortcut.
+    /// The portal has no way to unbind one shortcut of a session, so the binding
+    /// stays with the compositor until the next registration replaces the session.
+    ///
+    /// # Returns
+    /// true if the shortcut was enabled
+    pub fn disable(&self, shortcut_id: &str) -> bool {
+        let removed = self.enabled_ids.lock().remove(shortcut_id);
+        if self.enabled_ids.lock().is_empty() {
+            self.stop_listener();
+        }
+        removed
+    }
+
     /// Check if we're running on Wayland or XWayland
     /// On Wayland sessions (including XWayland), we need to use the portal for global shortcuts
     pub fn is_wayland() -> bool {
@@ -415,5 +502,30 @@ mod tests {
         let manager = WaylandHotkeyManager::new();
         assert!(manager.shutdown_tx.lock().is_none());
         assert!(manager.listener_handle.lock().is_none());
+        assert!(manager.enabled_ids.lock().is_empty());
+    }
+
+    #[test]
+    fn test_probe_result_replaces_unavailable_flag() {
+        PORTAL_UNAVAILABLE.store(true, Ordering::Relaxed);
+        assert!(record_probe_result(true));
+        assert!(!PORTAL_UNAVAILABLE.load(Ordering::Relaxed));
+
+        assert!(!record_probe_result(false));
+        assert!(PORTAL_UNAVAILABLE.load(Ordering::Relaxed));
+        reset_portal_state();
+    }
+
+    #[test]
+    fn test_disable_removes_enabled_id() {
+        let manager = WaylandHotkeyManager::new();
+        *manager.enabled_ids.lock() = ["record-toggle", "paste-last"]
+            .into_iter()
+            .map(str::to_string)
+            .collect();
+
+        assert!(manager.disable("paste-last"));
+        assert!(!manager.disable("paste-last"));
+        assert!(manager.enabled_ids.lock().contains("record-toggle"));
     }
 }
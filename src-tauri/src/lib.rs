@@ -4,8 +4,10 @@
 #![deny(warnings)]
 
 use parking_lot::Mutex;
+use std::collections::HashMap;
+use std::sync::atomic::AtomicBool;
 use std::sync::Arc;
-use tauri::{Manager, WindowEvent};
+use tauri::{Emitter, Manager, WindowEvent};
 
 // Module declarations
 mod audio;
@@ -13,6 +15,9 @@ mod clipboard;
 mod commands;
 mod hotkey;
 mod models;
+mod postprocess;
+mod storage;
+mod tray;
 mod whisper;
 
 /// Application state shared across all Tauri commands
@@ -23,9 +28,18 @@ pub struct AppState {
     /// Whisper context for transcription (reserved for future use)
     #[allow(dead_code)]
     whisper_context: Mutex<Option<whisper::context::WhisperContext>>,
-    /// Hotkey manager (reserved for future use)
-    #[allow(dead_code)]
-    hotkey_manager: Mutex<Option<hotkey::HotkeyManager>>,
+    /// Registers the global hotkeys and tracks which one is bound to each action
+    hotkey_manager: hotkey::HotkeyManager,
+    /// Control handles for in-flight model downloads, keyed by model ID
+    downloads: Mutex<HashMap<String, models::downloader::DownloadHandle>>,
+    /// Set to abort the transcription currently in progress
+    transcription_cancelled: Arc<AtomicBool>,
+    /// Lets one transcription run at a time, queueing the others
+    transcription_queue: whisper::queue::TranscriptionQueue,
+    /// Set to stop a batch transcription after the current file
+    batch_cancelled: Arc<AtomicBool>,
+    /// Idle stream keeping pre-roll audio, running only when enabled in settings
+    pre_roll: Mutex<Option<audio::ringbuffer::PreRollListener>>,
 }
 
 /// Main entry point for the Tauri application
@@ -56,52 +70,63 @@ pub fn run() {
             app.handle().plugin(tauri_plugin_notification::init())?;
 
             // Initialize app state
-            app.manage(Arc::new(AppState::default()));
+            let state = Arc::new(AppState::default());
+            app.manage(Arc::clone(&state));
+
+            // Grab hotkeys through the global shortcut plugin; on Wayland the portal binds them
+            if !commands::hotkey::is_wayland_session() {
+                state.hotkey_manager.set_backend(Arc::new(
+                    hotkey::native::GlobalShortcutBackend::new(app.handle().clone()),
+                ));
+            }
 
-            // Start the model cache cleanup task (unloads models after 5 min of inactivity)
+            // Start the pre-roll idle stream if the user enabled it
+            commands::recording::sync_pre_roll(&state);
+
+            // Start the model cache cleanup task (unloads models after the configured idle timeout)
             whisper::cache::start_cleanup_task();
 
-            // Setup system tray icon with menu
+            // Let the UI show a notice when models are loaded on the CPU because the GPU failed
+            let fallback_app = app.handle().clone();
+            whisper::cache::get_model_cache().on_gpu_fallback(move |error| {
+                let _ = fallback_app.emit("gpu-fallback", serde_json::json!({ "message": error }));
+            });
+
+            // Remove recordings left behind in the recordings directory by earlier sessions
+            std::thread::spawn(|| {
+                if let Err(e) = commands::recording::cleanup_temp_files(None) {
+                    log::warn!("Failed to clean up old recordings: {}", e);
+                }
+            });
+
+            // Setup system tray icon; left click opens the window, right click shows the menu
             #[cfg(desktop)]
             {
                 use tauri::image::Image;
-                use tauri::menu::{MenuBuilder, MenuItemBuilder};
-                use tauri::tray::{TrayIconBuilder, TrayIconEvent};
+                use tauri::tray::{MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent};
 
                 let tray_icon = Image::from_bytes(include_bytes!("../icons/32x32.png"))
                     .expect("Failed to load tray icon");
 
-                let show_item = MenuItemBuilder::new("Show Window").id("show").build(app)?;
-                let quit_item = MenuItemBuilder::new("Quit").id("quit").build(app)?;
-                let menu = MenuBuilder::new(app)
-                    .item(&show_item)
-                    .item(&quit_item)
-                    .build()?;
+                let menu = tray::build_tray_menu(app.handle())?;
 
-                let _tray = TrayIconBuilder::with_id("main-tray")
+                let _tray = TrayIconBuilder::with_id(tray::TRAY_ID)
                     .icon(tray_icon)
                     .menu(&menu)
+                    .show_menu_on_left_click(false)
                     .tooltip("Rustler")
                     .on_tray_icon_event(|tray, event| {
-                        if let TrayIconEvent::Click { .. } = event {
-                            if let Some(window) = tray.app_handle().get_webview_window("main") {
-                                let _ = window.show();
-                                let _ = window.set_focus();
-                            }
-                        }
-                    })
-                    .on_menu_event(|app, event| match event.id().as_ref() {
-                        "show" => {
-                            if let Some(window) = app.get_webview_window("main") {
-                                let _ = window.show();
-                                let _ = window.set_focus();
-                            }
-                        }
-                        "quit" => {
-                            app.exit(0);
+                        // Other buttons open the menu
+                        if let TrayIconEvent::Click {
+                            button: MouseButton::Left,
+                            button_state: MouseButtonState::Up,
+                            ..
+                        } = event
+                        {
+                            tray::show_main_window(tray.app_handle());
                         }
-                        _ => {}
                     })
+                    .on_menu_event(tray::handle_menu_event)
                     .build(app)?;
             }
 
@@ -120,28 +145,79 @@ pub fn run() {
             // Recording commands
             commands::recording::start_recording,
             commands::recording::stop_recording,
+            commands::recording::cleanup_temp_files,
+            commands::dictation::finish_dictation,
             // Transcription commands
             commands::transcription::transcribe_audio,
+            commands::transcription::transcribe_audio_detailed,
+            commands::transcription::transcribe_file,
+            commands::transcription::transcribe_batch,
+            commands::subtitles::export_subtitles,
+            commands::transcription::cancel_transcription,
+            commands::transcription::get_transcription_queue_length,
+            commands::transcription::cancel_batch,
+            commands::transcription::detect_language,
+            commands::transcription::is_gpu_unavailable,
+            commands::transcription::get_cache_timeout,
+            commands::transcription::get_loaded_models,
+            commands::transcription::get_cache_info,
+            commands::transcription::unload_model,
+            commands::transcription::preload_model,
+            commands::transcription::is_model_loaded,
             // Settings commands
             commands::settings::get_settings,
             commands::settings::save_settings,
+            commands::settings::reset_settings,
+            commands::settings::validate_settings,
+            commands::settings::get_settings_schema,
+            commands::settings::export_settings,
+            commands::settings::import_settings,
+            commands::settings::list_profiles,
+            commands::settings::create_profile,
+            commands::settings::switch_profile,
+            commands::settings::delete_profile,
             // Model management commands
             commands::models::get_available_models,
             commands::models::download_model,
+            commands::models::pause_model_download,
+            commands::models::resume_model_download,
+            commands::models::cancel_model_download,
             commands::models::delete_model,
+            commands::models::import_model,
+            commands::models::add_custom_model,
+            commands::models::verify_model,
             commands::models::get_models_dir,
             // Hotkey commands
             commands::hotkey::register_hotkey,
             commands::hotkey::unregister_hotkeys,
+            commands::hotkey::unregister_hotkey,
+            commands::hotkey::check_hotkey_conflict,
             commands::hotkey::is_wayland_session,
+            commands::hotkey::probe_global_shortcuts_available,
             commands::hotkey::reset_wayland_hotkey,
             // Clipboard commands
             commands::clipboard::paste_text,
+            commands::clipboard::copy_to_clipboard,
+            commands::clipboard::read_clipboard,
             // History commands
             commands::history::get_history,
+            commands::history::get_history_count,
+            commands::history::get_history_stats,
+            commands::history::search_history,
+            commands::history::export_history,
+            commands::history::copy_history_entry,
+            commands::history::paste_history_entry,
+            commands::history::update_history_entry,
+            commands::history::tag_history_entry,
+            commands::history::untag_history_entry,
+            commands::history::get_history_by_tag,
+            commands::history::list_all_tags,
             commands::history::add_history,
             commands::history::delete_history_entry,
             commands::history::clear_history,
+            commands::history::trim_history_to_limit,
+            commands::history::backup_history,
+            commands::history::restore_history,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
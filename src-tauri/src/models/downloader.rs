@@ -1,36 +1,247 @@
+use crate::commands::settings::{current_settings, CustomModelEntry};
+use crate::models::progress::{DownloadProgress, SpeedTracker};
 use anyhow::{Context, Result};
 use futures_util::StreamExt;
+use sha2::{Digest, Sha256};
 use std::collections::HashMap;
-use std::path::PathBuf;
-
-/// HuggingFace URLs for Whisper models
-const WHISPER_MODELS: &[(&str, &str)] = &[
-    (
-        "tiny",
-        "https://huggingface.co/ggerganov/whisper.cpp/resolve/main/ggml-tiny.bin",
-    ),
-    (
-        "base",
-        "https://huggingface.co/ggerganov/whisper.cpp/resolve/main/ggml-base.bin",
-    ),
-    (
-        "small",
-        "https://huggingface.co/ggerganov/whisper.cpp/resolve/main/ggml-small.bin",
-    ),
-    (
-        "medium",
-        "https://huggingface.co/ggerganov/whisper.cpp/resolve/main/ggml-medium.bin",
-    ),
-    (
-        "large",
-        "https://huggingface.co/ggerganov/whisper.cpp/resolve/main/ggml-large-v3.bin",
-    ),
-    (
-        "turbo",
-        "https://huggingface.co/ggerganov/whisper.cpp/resolve/main/ggml-large-v3-turbo.bin",
-    ),
+use std::io::{BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// How often a paused download re-checks its pause/cancel flags
+const PAUSE_POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+/// Size of the chunks local model files are copied in
+const COPY_CHUNK_SIZE: usize = 1024 * 1024;
+
+/// Repository the built-in models are downloaded from
+const MODEL_REPO_URL: &str = "https://huggingface.co/ggerganov/whisper.cpp/resolve/main";
+
+/// A Whisper model from the whisper.cpp model repository
+#[derive(Debug, Clone, Copy)]
+pub struct BuiltinModel {
+    /// Model ID, also used for the local file name (`ggml-{id}.bin`)
+    pub id: &'static str,
+    /// Name shown in the model list
+    pub name: &'static str,
+    /// Approximate size of the model file in MB
+    pub size_mb: u64,
+    /// File name in the model repository
+    file: &'static str,
+    /// SHA-256 checksum, pinned only once confirmed against the upstream
+    /// whisper.cpp model hashes; models without one are checked against the
+    /// checksum the model repository reports
+    sha256: Option<&'static str>,
+}
+
+impl BuiltinModel {
+    /// Download URL of the model
+    fn url(&self) -> String {
+        format!("{}/{}", MODEL_REPO_URL, self.file)
+    }
+}
+
+/// Built-in models, in the order they are listed in the catalog
+///
+/// Besides the multilingual models this covers the English-only (`.en`)
+/// models, which are more accurate for English speech, and the quantized
+/// models, which are a fraction of the size at a small accuracy cost.
+pub const BUILTIN_MODELS: &[BuiltinModel] = &[
+    BuiltinModel {
+        id: "tiny",
+        name: "Tiny (75 MB)",
+        size_mb: 75,
+        file: "ggml-tiny.bin",
+        sha256: None,
+    },
+    BuiltinModel {
+        id: "tiny.en",
+        name: "Tiny, English-only (75 MB)",
+        size_mb: 75,
+        file: "ggml-tiny.en.bin",
+        sha256: None,
+    },
+    BuiltinModel {
+        id: "tiny-q5_1",
+        name: "Tiny, quantized q5_1 (32 MB)",
+        size_mb: 32,
+        file: "ggml-tiny-q5_1.bin",
+        sha256: None,
+    },
+    BuiltinModel {
+        id: "tiny-q8_0",
+        name: "Tiny, quantized q8_0 (44 MB)",
+        size_mb: 44,
+        file: "ggml-tiny-q8_0.bin",
+        sha256: None,
+    },
+    BuiltinModel {
+        id: "base",
+        name: "Base (142 MB)",
+        size_mb: 142,
+        file: "ggml-base.bin",
+        sha256: None,
+    },
+    BuiltinModel {
+        id: "base.en",
+        name: "Base, English-only (142 MB)",
+        size_mb: 142,
+        file: "ggml-base.en.bin",
+        sha256: None,
+    },
+    BuiltinModel {
+        id: "base-q5_1",
+        name: "Base, quantized q5_1 (60 MB)",
+        size_mb: 60,
+        file: "ggml-base-q5_1.bin",
+        sha256: None,
+    },
+    BuiltinModel {
+        id: "base-q8_0",
+        name: "Base, quantized q8_0 (82 MB)",
+        size_mb: 82,
+        file: "ggml-base-q8_0.bin",
+        sha256: None,
+    },
+    BuiltinModel {
+        id: "small",
+        name: "Small (466 MB)",
+        size_mb: 466,
+        file: "ggml-small.bin",
+        sha256: None,
+    },
+    BuiltinModel {
+        id: "small.en",
+        name: "Small, English-only (466 MB)",
+        size_mb: 466,
+        file: "ggml-small.en.bin",
+        sha256: None,
+    },
+    BuiltinModel {
+        id: "small-q5_1",
+        name: "Small, quantized q5_1 (190 MB)",
+        size_mb: 190,
+        file: "ggml-small-q5_1.bin",
+        sha256: None,
+    },
+    BuiltinModel {
+        id: "small-q8_0",
+        name: "Small, quantized q8_0 (264 MB)",
+        size_mb: 264,
+        file: "ggml-small-q8_0.bin",
+        sha256: None,
+    },
+    BuiltinModel {
+        id: "medium",
+        name: "Medium (1.5 GB)",
+        size_mb: 1500,
+        file: "ggml-medium.bin",
+        sha256: None,
+    },
+    BuiltinModel {
+        id: "medium.en",
+        name: "Medium, English-only (1.5 GB)",
+        size_mb: 1500,
+        file: "ggml-medium.en.bin",
+        sha256: None,
+    },
+    BuiltinModel {
+        id: "medium-q5_0",
+        name: "Medium, quantized q5_0 (539 MB)",
+        size_mb: 539,
+        file: "ggml-medium-q5_0.bin",
+        sha256: None,
+    },
+    BuiltinModel {
+        id: "medium-q8_0",
+        name: "Medium, quantized q8_0 (823 MB)",
+        size_mb: 823,
+        file: "ggml-medium-q8_0.bin",
+        sha256: None,
+    },
+    BuiltinModel {
+        id: "large",
+        name: "Large (2.9 GB)",
+        size_mb: 2900,
+        file: "ggml-large-v3.bin",
+        sha256: None,
+    },
+    BuiltinModel {
+        id: "large-v3-q5_0",
+        name: "Large v3, quantized q5_0 (1.1 GB)",
+        size_mb: 1080,
+        file: "ggml-large-v3-q5_0.bin",
+        sha256: None,
+    },
+    BuiltinModel {
+        id: "turbo",
+        name: "Turbo (1.5 GB)",
+        size_mb: 1500,
+        file: "ggml-large-v3-turbo.bin",
+        sha256: None,
+    },
+    BuiltinModel {
+        id: "turbo-q5_0",
+        name: "Turbo, quantized q5_0 (574 MB)",
+        size_mb: 574,
+        file: "ggml-large-v3-turbo-q5_0.bin",
+        sha256: None,
+    },
+    BuiltinModel {
+        id: "turbo-q8_0",
+        name: "Turbo, quantized q8_0 (874 MB)",
+        size_mb: 874,
+        file: "ggml-large-v3-turbo-q8_0.bin",
+        sha256: None,
+    },
 ];
 
+/// Control handle for an in-flight model download
+///
+/// Cloning the handle shares the underlying flags, so one clone can be kept
+/// in application state while another is passed to `ModelDownloader::download`.
+#[derive(Debug, Clone, Default)]
+pub struct DownloadHandle {
+    /// Set when the download should be aborted
+    cancelled: Arc<AtomicBool>,
+    /// Set while the download should stop reading from the network
+    paused: Arc<AtomicBool>,
+}
+
+impl DownloadHandle {
+    /// Creates a new handle in the running (not paused, not cancelled) state
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Requests cancellation of the download
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::SeqCst);
+    }
+
+    /// Pauses the download until `resume` or `cancel` is called
+    pub fn pause(&self) {
+        self.paused.store(true, Ordering::SeqCst);
+    }
+
+    /// Resumes a paused download
+    pub fn resume(&self) {
+        self.paused.store(false, Ordering::SeqCst);
+    }
+
+    /// Returns true if cancellation has been requested
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::SeqCst)
+    }
+
+    /// Returns true if the download is currently paused
+    pub fn is_paused(&self) -> bool {
+        self.paused.load(Ordering::SeqCst)
+    }
+}
+
 /// Manages downloading and storing Whisper models
 pub struct ModelDownloader {
     /// Model URLs by ID
@@ -39,9 +250,226 @@ pub struct ModelDownloader {
     models_dir: PathBuf,
 }
 
+/// Looks up a built-in model by ID
+fn builtin_model(model_id: &str) -> Option<&'static BuiltinModel> {
+    BUILTIN_MODELS.iter().find(|model| model.id == model_id)
+}
+
+/// Returns true if the model ID is one of the built-in models
+pub fn is_builtin_model(model_id: &str) -> bool {
+    builtin_model(model_id).is_some()
+}
+
+/// Returns the download URLs of all built-in models by ID
+fn builtin_model_urls() -> HashMap<String, String> {
+    BUILTIN_MODELS
+        .iter()
+        .map(|model| (model.id.to_string(), model.url()))
+        .collect()
+}
+
+/// Returns true if the model ID is safe to use in a file name
+pub fn is_valid_model_id(model_id: &str) -> bool {
+    !model_id.is_empty()
+        && model_id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+        && !model_id.starts_with('.')
+}
+
+/// Returns the pinned SHA-256 checksum of a built-in model, if it has one
+fn expected_sha256(model_id: &str) -> Option<&'static str> {
+    builtin_model(model_id).and_then(|model| model.sha256)
+}
+
+/// Response header in which the model repository reports the SHA-256 of a file
+/// stored with Git LFS
+const LINKED_ETAG_HEADER: &str = "x-linked-etag";
+
+/// Extracts the SHA-256 checksum from an `X-Linked-Etag` header value
+fn parse_linked_etag(value: &str) -> Option<String> {
+    let value = value.trim().trim_start_matches("W/").trim_matches('"');
+    (value.len() == 64 && value.chars().all(|c| c.is_ascii_hexdigit()))
+        .then(|| value.to_ascii_lowercase())
+}
+
+/// Asks the model repository for the SHA-256 checksum of a model file
+///
+/// HuggingFace answers a `resolve` URL with a redirect to its CDN and reports
+/// the checksum of the LFS file in the `X-Linked-Etag` header of that
+/// redirect, so the redirect is not followed.
+async fn fetch_upstream_sha256(url: &str) -> Result<String> {
+    let client = reqwest::Client::builder()
+        .redirect(reqwest::redirect::Policy::none())
+        .build()?;
+    let response = client.head(url).send().await?.error_for_status()?;
+
+    let etag = response
+        .headers()
+        .get(LINKED_ETAG_HEADER)
+        .context("No X-Linked-Etag header in the response")?
+        .to_str()?;
+    parse_linked_etag(etag).with_context(|| format!("Unexpected X-Linked-Etag value {:?}", etag))
+}
+
+/// Resolves the SHA-256 checksum a model file should have
+///
+/// Pinned checksums come first; other built-in models are checked against the
+/// checksum the model repository reports. Custom models have none.
+async fn expected_checksum(model_id: &str) -> Option<String> {
+    if let Some(pinned) = expected_sha256(model_id) {
+        return Some(pinned.to_string());
+    }
+    let model = builtin_model(model_id)?;
+    match fetch_upstream_sha256(&model.url()).await {
+        Ok(sha256) => Some(sha256),
+        Err(e) => {
+            log::warn!("No upstream checksum for model '{}': {}", model_id, e);
+            None
+        }
+    }
+}
+
+/// Returns the lowercase hex digest of everything fed into the hasher
+fn finalize_hex(hasher: Sha256) -> String {
+    format!("{:x}", hasher.finalize())
+}
+
+/// Checks a computed digest against the expected checksum
+fn verify_checksum(model_id: &str, actual: &str, expected: &str) -> Result<()> {
+    if !actual.eq_ignore_ascii_case(expected) {
+        anyhow::bail!(
+            "Checksum mismatch for model '{}': expected {}, got {}. The download may be truncated or corrupted",
+            model_id,
+            expected,
+            actual
+        );
+    }
+    Ok(())
+}
+
+/// Checks the number of bytes received against the size announced by the server
+fn check_downloaded_size(model_id: &str, downloaded: u64, expected: Option<u64>) -> Result<()> {
+    match expected {
+        Some(expected) if downloaded != expected => anyhow::bail!(
+            "Incomplete download of model '{}': received {} of {} bytes",
+            model_id,
+            downloaded,
+            expected
+        ),
+        _ => Ok(()),
+    }
+}
+
+/// Path of the temp file a model is downloaded to before being moved into place
+fn part_path(model_path: &Path) -> PathBuf {
+    let mut name = model_path.file_name().unwrap_or_default().to_os_string();
+    name.push(".part");
+    model_path.with_file_name(name)
+}
+
+/// Copies a file chunk by chunk, reporting progress after every chunk
+///
+/// Blocks the calling thread; waits while the handle is paused and stops once
+/// it is cancelled.
+fn copy_in_chunks(
+    source: &Path,
+    dest: &Path,
+    handle: Option<&DownloadHandle>,
+    mut report: impl FnMut(DownloadProgress),
+) -> Result<()> {
+    let mut reader =
+        std::fs::File::open(source).with_context(|| format!("Failed to open {:?}", source))?;
+    let total_size = reader
+        .metadata()
+        .map(|m| m.len())
+        .ok()
+        .filter(|&size| size > 0);
+
+    let file =
+        std::fs::File::create(dest).with_context(|| format!("Failed to create {:?}", dest))?;
+    let mut writer = BufWriter::new(file);
+    let mut buffer = vec![0; COPY_CHUNK_SIZE];
+
+    let mut copied: u64 = 0;
+    let mut speed = SpeedTracker::new();
+    report(speed.record(Instant::now(), copied, total_size));
+
+    loop {
+        if let Some(handle) = handle {
+            if handle.is_paused() {
+                while handle.is_paused() && !handle.is_cancelled() {
+                    std::thread::sleep(PAUSE_POLL_INTERVAL);
+                }
+                // Time spent paused must not drag down the measured speed
+                speed.reset();
+                speed.record(Instant::now(), copied, total_size);
+            }
+
+            if handle.is_cancelled() {
+                return Err(anyhow::anyhow!("cancelled"));
+            }
+        }
+
+        let read = match reader.read(&mut buffer) {
+            Ok(0) => break,
+            Ok(read) => read,
+            Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e).context("Failed to read model file"),
+        };
+        writer
+            .write_all(&buffer[..read])
+            .context("Failed to write model file")?;
+        copied += read as u64;
+
+        report(speed.record(Instant::now(), copied, total_size));
+    }
+
+    writer.flush().context("Failed to write model file")?;
+    Ok(())
+}
+
+/// Returns true if a model source is an HTTP(S) URL rather than a local path
+fn is_remote_source(source: &str) -> bool {
+    source.starts_with("https://") || source.starts_with("http://")
+}
+
+/// Adds custom models to the URL map.
+/// Entries with an invalid ID or clashing with a built-in model are skipped.
+fn merge_custom_models(
+    model_urls: &mut HashMap<String, String>,
+    custom_models: &[CustomModelEntry],
+) {
+    for entry in custom_models {
+        if !is_valid_model_id(&entry.id) {
+            log::warn!("Skipping custom model with invalid ID: {:?}", entry.id);
+            continue;
+        }
+        if is_builtin_model(&entry.id) {
+            log::warn!(
+                "Skipping custom model '{}': ID clashes with a built-in model",
+                entry.id
+            );
+            continue;
+        }
+        model_urls.insert(entry.id.clone(), entry.url_or_path.clone());
+    }
+}
+
 impl ModelDownloader {
-    /// Creates a new model downloader
+    /// Creates a new model downloader, including custom models from settings
     pub fn new() -> Self {
+        let custom_models = current_settings()
+            .map(|s| s.custom_models)
+            .unwrap_or_default();
+        Self::with_custom_models(&custom_models)
+    }
+
+    /// Creates a new model downloader with the given custom models
+    ///
+    /// # Arguments
+    /// * `custom_models` - User-provided models merged into the built-in list
+    pub fn with_custom_models(custom_models: &[CustomModelEntry]) -> Self {
         let models_dir = Self::get_default_models_dir();
 
         // Create models directory if it doesn't exist
@@ -49,10 +477,8 @@ impl ModelDownloader {
             std::fs::create_dir_all(&models_dir).ok();
         }
 
-        let model_urls: HashMap<String, String> = WHISPER_MODELS
-            .iter()
-            .map(|(id, url)| (id.to_string(), url.to_string()))
-            .collect();
+        let mut model_urls = builtin_model_urls();
+        merge_custom_models(&mut model_urls, custom_models);
 
         Self {
             model_urls,
@@ -72,14 +498,20 @@ impl ModelDownloader {
     ///
     /// # Arguments
     /// * `model_id` - ID of the model to download
-    /// * `progress_callback` - Function called with download progress (0.0 to 1.0)
+    /// * `handle` - Optional handle used to pause, resume or cancel the download
+    /// * `progress_callback` - Function called with the downloaded bytes, speed and ETA
     ///
     /// # Returns
     /// * `Ok(PathBuf)` with the path to the downloaded model
-    /// * `Err` if download failed
-    pub async fn download<F>(&self, model_id: &str, mut progress_callback: F) -> Result<PathBuf>
+    /// * `Err` if download failed or was cancelled
+    pub async fn download<F>(
+        &self,
+        model_id: &str,
+        handle: Option<DownloadHandle>,
+        mut progress_callback: F,
+    ) -> Result<PathBuf>
     where
-        F: FnMut(f64),
+        F: FnMut(DownloadProgress),
     {
         log::info!("Starting download for model: {}", model_id);
 
@@ -93,35 +525,158 @@ impl ModelDownloader {
             return Ok(model_path);
         }
 
+        // Custom models may point at a local file instead of a URL
+        if !is_remote_source(url) {
+            log::info!("Copying model from local path: {}", url);
+            Self::copy_local_model(Path::new(url), &model_path, handle, &mut progress_callback)
+                .await
+                .with_context(|| format!("Failed to copy model from {}", url))?;
+            return Ok(model_path);
+        }
+
+        let expected = expected_checksum(model_id).await;
+
+        // Stream into a temp file so a failed download never leaves a broken model behind
+        let part_path = part_path(&model_path);
+        let digest = match Self::download_to_file(
+            url,
+            &part_path,
+            model_id,
+            handle.as_ref(),
+            &mut progress_callback,
+        )
+        .await
+        {
+            Ok(digest) => digest,
+            Err(e) => {
+                std::fs::remove_file(&part_path).ok();
+                return Err(e);
+            }
+        };
+
+        // Refuse to store a truncated or corrupted model; models without a
+        // known checksum are stored as downloaded
+        if let Some(expected) = expected {
+            if let Err(e) = verify_checksum(model_id, &digest, &expected) {
+                std::fs::remove_file(&part_path).ok();
+                return Err(e);
+            }
+        }
+
+        std::fs::rename(&part_path, &model_path).context("Failed to move model into place")?;
+
+        log::info!("Model downloaded successfully: {:?}", model_path);
+
+        Ok(model_path)
+    }
+
+    /// Copies a local model file into place without blocking the async runtime
+    ///
+    /// The file is copied to a temp file on a blocking thread and moved into
+    /// place once complete, so a failed or cancelled copy never leaves a broken
+    /// model behind. Progress is passed to the callback as the copy goes.
+    async fn copy_local_model<F>(
+        source: &Path,
+        model_path: &Path,
+        handle: Option<DownloadHandle>,
+        progress_callback: &mut F,
+    ) -> Result<()>
+    where
+        F: FnMut(DownloadProgress),
+    {
+        let part_path = part_path(model_path);
+
+        let (progress_tx, mut progress_rx) = tokio::sync::mpsc::unbounded_channel();
+        let copy = {
+            let source = source.to_path_buf();
+            let part_path = part_path.clone();
+            tokio::task::spawn_blocking(move || {
+                copy_in_chunks(&source, &part_path, handle.as_ref(), |progress| {
+                    let _ = progress_tx.send(progress);
+                })
+            })
+        };
+
+        // The channel closes once the copy is done and drops its sender
+        while let Some(progress) = progress_rx.recv().await {
+            progress_callback(progress);
+        }
+
+        if let Err(e) = copy.await.context("Model copy task failed")? {
+            std::fs::remove_file(&part_path).ok();
+            return Err(e);
+        }
+
+        std::fs::rename(&part_path, model_path).context("Failed to move model into place")
+    }
+
+    /// Streams a download into a file, chunk by chunk
+    ///
+    /// # Returns
+    /// * `Ok(String)` with the hex SHA-256 digest of the downloaded data
+    /// * `Err` if the request or a write failed, or the download was cancelled
+    async fn download_to_file<F>(
+        url: &str,
+        file_path: &Path,
+        model_id: &str,
+        handle: Option<&DownloadHandle>,
+        progress_callback: &mut F,
+    ) -> Result<String>
+    where
+        F: FnMut(DownloadProgress),
+    {
         // Create HTTP client
         let client = reqwest::Client::new();
-        let response = client.get(url).send().await?;
+        let response = client.get(url).send().await?.error_for_status()?;
 
         // Get total size
-        let total_size = response.content_length().unwrap_or(0);
+        let total_size = response.content_length().filter(|&size| size > 0);
+
+        let file = std::fs::File::create(file_path)
+            .with_context(|| format!("Failed to create {:?}", file_path))?;
+        let mut writer = BufWriter::new(file);
+        let mut hasher = Sha256::new();
 
         // Download with progress tracking
         let mut downloaded: u64 = 0;
         let mut stream = response.bytes_stream();
-        let mut file_bytes = Vec::new();
+        let mut speed = SpeedTracker::new();
+        progress_callback(speed.record(Instant::now(), downloaded, total_size));
 
         while let Some(chunk) = stream.next().await {
+            if let Some(handle) = handle {
+                // Hold off reading further chunks while paused
+                if handle.is_paused() {
+                    while handle.is_paused() && !handle.is_cancelled() {
+                        tokio::time::sleep(PAUSE_POLL_INTERVAL).await;
+                    }
+                    // Time spent paused must not drag down the measured speed
+                    speed.reset();
+                    speed.record(Instant::now(), downloaded, total_size);
+                }
+
+                if handle.is_cancelled() {
+                    log::info!("Download cancelled for model: {}", model_id);
+                    return Err(anyhow::anyhow!("cancelled"));
+                }
+            }
+
             let chunk = chunk?;
-            file_bytes.extend_from_slice(&chunk);
+            writer
+                .write_all(&chunk)
+                .context("Failed to write model file")?;
+            hasher.update(&chunk);
             downloaded += chunk.len() as u64;
 
-            if total_size > 0 {
-                let progress = downloaded as f64 / total_size as f64;
-                progress_callback(progress);
-            }
+            progress_callback(speed.record(Instant::now(), downloaded, total_size));
         }
 
-        // Write to file
-        std::fs::write(&model_path, file_bytes).context("Failed to write model file")?;
+        writer.flush().context("Failed to write model file")?;
 
-        log::info!("Model downloaded successfully: {:?}", model_path);
+        // A connection dropped mid-transfer can end the stream without an error
+        check_downloaded_size(model_id, downloaded, total_size)?;
 
-        Ok(model_path)
+        Ok(finalize_hex(hasher))
     }
 
     /// Gets the path where a model would be stored
@@ -146,6 +701,112 @@ impl ModelDownloader {
         self.get_model_path(model_id).exists()
     }
 
+    /// Re-hashes a downloaded model and compares it with its known checksum
+    ///
+    /// # Arguments
+    /// * `model_id` - ID of the model to verify
+    ///
+    /// # Returns
+    /// * `Ok(true)` if the file matches its checksum, `Ok(false)` if it doesn't
+    /// * `Err` if the model is not downloaded, has no known checksum or could not be read
+    pub async fn verify_model(&self, model_id: &str) -> Result<bool> {
+        let model_path = self.get_model_path(model_id);
+        if !model_path.is_file() {
+            anyhow::bail!("Model '{}' is not downloaded", model_id);
+        }
+
+        let expected = expected_checksum(model_id)
+            .await
+            .with_context(|| format!("No checksum known for model '{}'", model_id))?;
+
+        // Stream the file through the hasher; large models don't fit comfortably
+        // in memory and take a while to hash, so keep it off the async runtime
+        let actual = tokio::task::spawn_blocking(move || -> Result<String> {
+            let mut file = std::fs::File::open(&model_path).context("Failed to open model file")?;
+            let mut hasher = Sha256::new();
+            std::io::copy(&mut file, &mut hasher).context("Failed to read model file")?;
+            Ok(finalize_hex(hasher))
+        })
+        .await??;
+
+        let valid = actual.eq_ignore_ascii_case(&expected);
+        if !valid {
+            log::warn!(
+                "Model '{}' failed verification: expected {}, got {}",
+                model_id,
+                expected,
+                actual
+            );
+        }
+        Ok(valid)
+    }
+
+    /// Copies a local model file into the models directory
+    ///
+    /// # Arguments
+    /// * `source_path` - Path of the `.bin` file to import
+    /// * `model_id` - ID to store the model under (`ggml-{model_id}.bin`)
+    /// * `handle` - Optional handle used to pause, resume or cancel the copy
+    /// * `progress_callback` - Function called with the copied bytes, speed and ETA
+    ///
+    /// # Returns
+    /// * `Ok(PathBuf)` with the path of the imported model
+    /// * `Err` if the ID is invalid, the source is missing, the destination
+    ///   already exists or the copy failed or was cancelled
+    pub async fn import<F>(
+        &self,
+        source_path: &Path,
+        model_id: &str,
+        handle: Option<DownloadHandle>,
+        mut progress_callback: F,
+    ) -> Result<PathBuf>
+    where
+        F: FnMut(DownloadProgress),
+    {
+        if !is_valid_model_id(model_id) {
+            anyhow::bail!("Invalid model ID '{}'", model_id);
+        }
+
+        if !source_path.is_file() {
+            anyhow::bail!("Model file not found: {:?}", source_path);
+        }
+
+        let model_path = self.get_model_path(model_id);
+        if model_path.exists() {
+            anyhow::bail!("Model '{}' already exists", model_id);
+        }
+
+        std::fs::create_dir_all(&self.models_dir).context("Failed to create models directory")?;
+        Self::copy_local_model(source_path, &model_path, handle, &mut progress_callback)
+            .await
+            .context("Failed to copy model file")?;
+
+        log::info!("Imported model '{}' from {:?}", model_id, source_path);
+        Ok(model_path)
+    }
+
+    /// Lists the IDs of all model files present in the models directory
+    ///
+    /// # Returns
+    /// IDs derived from `ggml-{id}.bin` file names, sorted alphabetically
+    pub fn list_local_model_ids(&self) -> Vec<String> {
+        let mut ids: Vec<String> = std::fs::read_dir(&self.models_dir)
+            .map(|entries| {
+                entries
+                    .flatten()
+                    .filter_map(|entry| {
+                        let name = entry.file_name().to_string_lossy().to_string();
+                        name.strip_prefix("ggml-")
+                            .and_then(|rest| rest.strip_suffix(".bin"))
+                            .map(str::to_string)
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        ids.sort();
+        ids
+    }
+
     /// Gets the models directory path
     ///
     /// # Returns
@@ -164,6 +825,7 @@ impl Default for ModelDownloader {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::commands::settings::CACHE_TEST_LOCK;
     use std::fs;
     use std::path::Path;
 
@@ -193,10 +855,7 @@ mod tests {
 
     /// Test helper to create a downloader with a custom models directory
     fn create_test_downloader(models_dir: PathBuf) -> ModelDownloader {
-        let model_urls: HashMap<String, String> = WHISPER_MODELS
-            .iter()
-            .map(|(id, url)| (id.to_string(), url.to_string()))
-            .collect();
+        let model_urls = builtin_model_urls();
 
         ModelDownloader {
             model_urls,
@@ -206,6 +865,7 @@ mod tests {
 
     #[test]
     fn test_new_creates_downloader_with_all_models() {
+        let _cache_guard = CACHE_TEST_LOCK.lock();
         let downloader = ModelDownloader::new();
 
         // Check all expected models are available
@@ -221,6 +881,7 @@ mod tests {
 
     #[test]
     fn test_default_impl() {
+        let _cache_guard = CACHE_TEST_LOCK.lock();
         let downloader = ModelDownloader::default();
         assert!(!downloader.model_urls.is_empty());
     }
@@ -300,7 +961,7 @@ mod tests {
         // Download should return the existing path without downloading
         let mut progress_called = false;
         let result = downloader
-            .download("tiny", |_| {
+            .download("tiny", None, |_| {
                 progress_called = true;
             })
             .await;
@@ -322,7 +983,7 @@ mod tests {
         let test_dir = create_test_dir();
         let downloader = create_test_downloader(test_dir.clone());
 
-        let result = downloader.download("nonexistent_model", |_| {}).await;
+        let result = downloader.download("nonexistent_model", None, |_| {}).await;
 
         assert!(result.is_err());
         let err = result.unwrap_err();
@@ -330,4 +991,307 @@ mod tests {
 
         cleanup_test_dir(&test_dir);
     }
+
+    fn custom_entry(id: &str, url_or_path: &str) -> CustomModelEntry {
+        CustomModelEntry {
+            id: id.to_string(),
+            name: id.to_string(),
+            url_or_path: url_or_path.to_string(),
+            size_mb: 100,
+        }
+    }
+
+    #[test]
+    fn test_merge_custom_models_adds_entries() {
+        let mut model_urls = HashMap::new();
+        merge_custom_models(
+            &mut model_urls,
+            &[custom_entry("my-finetune", "https://example.com/model.bin")],
+        );
+
+        assert_eq!(
+            model_urls.get("my-finetune").map(String::as_str),
+            Some("https://example.com/model.bin")
+        );
+    }
+
+    #[test]
+    fn test_merge_custom_models_skips_builtin_and_invalid_ids() {
+        let mut model_urls = builtin_model_urls();
+        merge_custom_models(
+            &mut model_urls,
+            &[
+                custom_entry("base", "https://example.com/evil.bin"),
+                custom_entry("../escape", "https://example.com/evil.bin"),
+            ],
+        );
+
+        assert_eq!(
+            model_urls.get("base").map(String::as_str),
+            Some("https://huggingface.co/ggerganov/whisper.cpp/resolve/main/ggml-base.bin")
+        );
+        assert!(!model_urls.contains_key("../escape"));
+    }
+
+    #[test]
+    fn test_is_valid_model_id() {
+        assert!(is_valid_model_id("large-v3-q5_0"));
+        assert!(!is_valid_model_id(""));
+        assert!(!is_valid_model_id("a/b"));
+        assert!(!is_valid_model_id(".."));
+    }
+
+    #[tokio::test]
+    async fn test_import_copies_model_file() {
+        let test_dir = create_test_dir();
+        let downloader = create_test_downloader(test_dir.join("models"));
+        let source = test_dir.join("custom.bin");
+        fs::write(&source, b"custom model").unwrap();
+
+        let mut last_progress = None;
+        let path = downloader
+            .import(&source, "custom", None, |progress| {
+                last_progress = Some(progress)
+            })
+            .await
+            .unwrap();
+        let last_progress = last_progress.unwrap();
+        assert_eq!(last_progress.downloaded_bytes, 12);
+        assert_eq!(last_progress.fraction(), Some(1.0));
+
+        assert_eq!(path, downloader.get_model_path("custom"));
+        assert_eq!(fs::read(&path).unwrap(), b"custom model");
+        assert_eq!(
+            downloader.list_local_model_ids(),
+            vec!["custom".to_string()]
+        );
+
+        cleanup_test_dir(&test_dir);
+    }
+
+    #[tokio::test]
+    async fn test_import_fails_if_destination_exists() {
+        let test_dir = create_test_dir();
+        let downloader = create_test_downloader(test_dir.join("models"));
+        let source = test_dir.join("custom.bin");
+        fs::write(&source, b"new model").unwrap();
+        fs::create_dir_all(&downloader.models_dir).unwrap();
+        fs::write(downloader.get_model_path("custom"), b"existing model").unwrap();
+
+        let result = downloader.import(&source, "custom", None, |_| {}).await;
+
+        assert!(result.is_err());
+        assert!(result.unwrap_err().to_string().contains("already exists"));
+        // The existing file must be left untouched
+        assert_eq!(
+            fs::read(downloader.get_model_path("custom")).unwrap(),
+            b"existing model"
+        );
+
+        cleanup_test_dir(&test_dir);
+    }
+
+    /// SHA-256 of the ASCII bytes "abc" (FIPS 180-2 test vector)
+    const ABC_SHA256: &str = "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad";
+
+    /// Hashes a payload the same way downloads are hashed, one chunk at a time
+    fn sha256_of_chunks(chunks: &[&[u8]]) -> String {
+        let mut hasher = Sha256::new();
+        for chunk in chunks {
+            hasher.update(chunk);
+        }
+        finalize_hex(hasher)
+    }
+
+    #[test]
+    fn test_sha256_known_payload() {
+        assert_eq!(sha256_of_chunks(&[b"abc"]), ABC_SHA256);
+        assert_eq!(sha256_of_chunks(&[b"a", b"bc"]), ABC_SHA256);
+    }
+
+    #[test]
+    fn test_verify_checksum() {
+        let digest = sha256_of_chunks(&[b"abc"]);
+        assert!(verify_checksum("tiny", &digest, ABC_SHA256).is_ok());
+        assert!(verify_checksum("tiny", &digest, &ABC_SHA256.to_uppercase()).is_ok());
+
+        let truncated = sha256_of_chunks(&[b"ab"]);
+        let err = verify_checksum("tiny", &truncated, ABC_SHA256).unwrap_err();
+        assert!(err.to_string().contains("Checksum mismatch"));
+    }
+
+    #[tokio::test]
+    async fn test_cancelled_import_leaves_no_file_behind() {
+        let test_dir = create_test_dir();
+        let downloader = create_test_downloader(test_dir.join("models"));
+        let source = test_dir.join("custom.bin");
+        fs::write(&source, vec![0; COPY_CHUNK_SIZE * 3]).unwrap();
+
+        // The copy waits while paused, so cancelling on the first report
+        // stops it before any data was copied
+        let handle = DownloadHandle::new();
+        handle.pause();
+        let cancel = handle.clone();
+        let mut copied = 0;
+        let result = downloader
+            .import(&source, "custom", Some(handle), |progress| {
+                copied = progress.downloaded_bytes;
+                cancel.cancel();
+            })
+            .await;
+
+        assert_eq!(copied, 0);
+        assert!(result.is_err());
+        assert!(!downloader.get_model_path("custom").exists());
+        assert!(!part_path(&downloader.get_model_path("custom")).exists());
+
+        cleanup_test_dir(&test_dir);
+    }
+
+    #[test]
+    fn test_part_path() {
+        let model_path = PathBuf::from("/models/ggml-base.bin");
+        assert_eq!(
+            part_path(&model_path),
+            PathBuf::from("/models/ggml-base.bin.part")
+        );
+    }
+
+    #[test]
+    fn test_partial_download_is_not_listed_as_model() {
+        let test_dir = create_test_dir();
+        let downloader = create_test_downloader(test_dir.clone());
+        fs::write(part_path(&downloader.get_model_path("base")), b"partial").unwrap();
+
+        assert!(downloader.list_local_model_ids().is_empty());
+        assert!(!downloader.is_downloaded("base"));
+
+        cleanup_test_dir(&test_dir);
+    }
+
+    #[test]
+    fn test_pinned_checksums_are_sha256() {
+        for model in BUILTIN_MODELS {
+            let Some(sha256) = model.sha256 else { continue };
+            assert_eq!(
+                sha256.len(),
+                64,
+                "checksum of '{}' is not SHA-256",
+                model.id
+            );
+            assert!(sha256.chars().all(|c| c.is_ascii_hexdigit()));
+        }
+    }
+
+    #[test]
+    fn test_builtin_models_are_listed_once() {
+        for (i, model) in BUILTIN_MODELS.iter().enumerate() {
+            assert!(is_valid_model_id(model.id));
+            assert!(model.size_mb > 0);
+            assert!(
+                BUILTIN_MODELS[i + 1..].iter().all(|m| m.id != model.id),
+                "model '{}' is listed twice",
+                model.id
+            );
+        }
+    }
+
+    #[test]
+    fn test_check_downloaded_size() {
+        assert!(check_downloaded_size("tiny", 100, Some(100)).is_ok());
+        assert!(check_downloaded_size("tiny", 100, None).is_ok());
+
+        let err = check_downloaded_size("tiny", 60, Some(100)).unwrap_err();
+        assert!(err.to_string().contains("Incomplete download"));
+    }
+
+    #[test]
+    fn test_english_model_path_does_not_collide() {
+        let _cache_guard = CACHE_TEST_LOCK.lock();
+        let downloader = ModelDownloader::new();
+
+        let english = downloader.get_model_path("base.en");
+        assert!(english.ends_with("ggml-base.en.bin"));
+        assert_ne!(english, downloader.get_model_path("base"));
+        assert!(is_valid_model_id("base.en"));
+        assert!(is_builtin_model("base.en"));
+    }
+
+    #[test]
+    fn test_builtin_model_urls() {
+        let urls = builtin_model_urls();
+
+        assert_eq!(urls.len(), BUILTIN_MODELS.len());
+        assert_eq!(
+            urls.get("turbo-q5_0").map(String::as_str),
+            Some("https://huggingface.co/ggerganov/whisper.cpp/resolve/main/ggml-large-v3-turbo-q5_0.bin")
+        );
+        assert_eq!(
+            urls.get("small.en").map(String::as_str),
+            Some("https://huggingface.co/ggerganov/whisper.cpp/resolve/main/ggml-small.en.bin")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_verify_model_errors() {
+        let test_dir = create_test_dir();
+        let downloader = create_test_downloader(test_dir.clone());
+
+        // Not downloaded yet
+        assert!(downloader.verify_model("tiny").await.is_err());
+
+        // Custom models have no known checksum
+        fs::write(downloader.get_model_path("my-model"), b"model data").unwrap();
+        let err = downloader.verify_model("my-model").await.unwrap_err();
+        assert!(err.to_string().contains("No checksum known"));
+
+        cleanup_test_dir(&test_dir);
+    }
+
+    #[test]
+    fn test_parse_linked_etag() {
+        assert_eq!(
+            parse_linked_etag(&format!("\"{}\"", ABC_SHA256.to_uppercase())),
+            Some(ABC_SHA256.to_string())
+        );
+        assert_eq!(
+            parse_linked_etag(&format!("W/\"{}\"", ABC_SHA256)),
+            Some(ABC_SHA256.to_string())
+        );
+        // Git object IDs of small files are SHA-1, not a file checksum
+        assert_eq!(
+            parse_linked_etag("\"a9993e364706816aba3e25717850c26c9cd0d89d\""),
+            None
+        );
+    }
+
+    #[test]
+    fn test_download_handle_starts_running() {
+        let handle = DownloadHandle::new();
+        assert!(!handle.is_cancelled());
+        assert!(!handle.is_paused());
+    }
+
+    #[test]
+    fn test_download_handle_pause_and_resume() {
+        let handle = DownloadHandle::new();
+
+        handle.pause();
+        assert!(handle.is_paused());
+
+        handle.resume();
+        assert!(!handle.is_paused());
+    }
+
+    #[test]
+    fn test_download_handle_clones_share_flags() {
+        let handle = DownloadHandle::new();
+        let clone = handle.clone();
+
+        clone.cancel();
+        assert!(handle.is_cancelled());
+
+        clone.pause();
+        assert!(handle.is_paused());
+    }
 }
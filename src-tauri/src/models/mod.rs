@@ -1,2 +1,5 @@
 /// Model downloading and management
 pub mod downloader;
+
+/// Download speed and ETA tracking
+pub mod progress;
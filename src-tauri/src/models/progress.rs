@@ -0,0 +1,196 @@
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+/// Length of the rolling window used to measure download speed
+const SPEED_WINDOW: Duration = Duration::from_secs(3);
+
+/// Progress of a model download
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DownloadProgress {
+    /// Bytes downloaded so far
+    pub downloaded_bytes: u64,
+    /// Total size of the download, if the server reported it
+    pub total_bytes: Option<u64>,
+    /// Current download speed, measured over the last few seconds
+    pub bytes_per_sec: f64,
+    /// Estimated seconds until the download completes, if it can be estimated
+    pub eta_secs: Option<f64>,
+}
+
+impl DownloadProgress {
+    /// Progress of a download that finished in one step (e.g., a local copy)
+    pub fn complete(total_bytes: u64) -> Self {
+        Self {
+            downloaded_bytes: total_bytes,
+            total_bytes: Some(total_bytes),
+            bytes_per_sec: 0.0,
+            eta_secs: Some(0.0),
+        }
+    }
+
+    /// Returns the completed fraction (0.0 to 1.0), if the total size is known
+    pub fn fraction(&self) -> Option<f64> {
+        match self.total_bytes {
+            Some(total) if total > 0 => {
+                Some((self.downloaded_bytes as f64 / total as f64).min(1.0))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Tracks download speed over a rolling window so the ETA reacts quickly to
+/// changes in throughput instead of averaging over the whole download
+pub struct SpeedTracker {
+    /// Byte counts at recent points in time, oldest first
+    samples: VecDeque<(Instant, u64)>,
+    /// Length of the window
+    window: Duration,
+}
+
+impl SpeedTracker {
+    /// Creates a tracker measuring speed over `SPEED_WINDOW`
+    pub fn new() -> Self {
+        Self::with_window(SPEED_WINDOW)
+    }
+
+    /// Creates a tracker measuring speed over the given window
+    pub fn with_window(window: Duration) -> Self {
+        Self {
+            samples: VecDeque::new(),
+            window,
+        }
+    }
+
+    /// Forgets all measurements, e.g. after a download was paused
+    pub fn reset(&mut self) {
+        self.samples.clear();
+    }
+
+    /// Records the downloaded byte count and computes the current progress
+    ///
+    /// # Arguments
+    /// * `now` - Time of the measurement
+    /// * `downloaded_bytes` - Bytes downloaded so far
+    /// * `total_bytes` - Total size of the download, if known
+    pub fn record(
+        &mut self,
+        now: Instant,
+        downloaded_bytes: u64,
+        total_bytes: Option<u64>,
+    ) -> DownloadProgress {
+        self.samples.push_back((now, downloaded_bytes));
+
+        // Keep one sample at or beyond the window edge as the baseline
+        while self.samples.len() > 2 && now.duration_since(self.samples[1].0) >= self.window {
+            self.samples.pop_front();
+        }
+
+        let bytes_per_sec = match (self.samples.front(), self.samples.back()) {
+            (Some(&(start, start_bytes)), Some(&(end, end_bytes))) => {
+                let elapsed = end.duration_since(start).as_secs_f64();
+                if elapsed > 0.0 {
+                    end_bytes.saturating_sub(start_bytes) as f64 / elapsed
+                } else {
+                    0.0
+                }
+            }
+            _ => 0.0,
+        };
+
+        let eta_secs = match total_bytes {
+            Some(total) if bytes_per_sec > 0.0 => {
+                Some(total.saturating_sub(downloaded_bytes) as f64 / bytes_per_sec)
+            }
+            _ => None,
+        };
+
+        DownloadProgress {
+            downloaded_bytes,
+            total_bytes,
+            bytes_per_sec,
+            eta_secs,
+        }
+    }
+}
+
+impl Default for SpeedTracker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MB: u64 = 1024 * 1024;
+
+    #[test]
+    fn test_first_sample_has_no_speed() {
+        let mut tracker = SpeedTracker::new();
+        let progress = tracker.record(Instant::now(), 0, Some(10 * MB));
+
+        assert_eq!(progress.bytes_per_sec, 0.0);
+        assert_eq!(progress.eta_secs, None);
+        assert_eq!(progress.fraction(), Some(0.0));
+    }
+
+    #[test]
+    fn test_speed_and_eta() {
+        let mut tracker = SpeedTracker::new();
+        let start = Instant::now();
+
+        tracker.record(start, 0, Some(10 * MB));
+        let progress = tracker.record(start + Duration::from_secs(2), 2 * MB, Some(10 * MB));
+
+        assert_eq!(progress.bytes_per_sec, MB as f64);
+        assert_eq!(progress.eta_secs, Some(8.0));
+        assert_eq!(progress.fraction(), Some(0.2));
+    }
+
+    #[test]
+    fn test_speed_uses_rolling_window() {
+        let mut tracker = SpeedTracker::with_window(Duration::from_secs(2));
+        let start = Instant::now();
+
+        // 1 MB/s for 10 seconds, then 4 MB/s
+        for second in 0..=10 {
+            tracker.record(start + Duration::from_secs(second), second * MB, None);
+        }
+        for second in 11..=14 {
+            tracker.record(
+                start + Duration::from_secs(second),
+                10 * MB + (second - 10) * 4 * MB,
+                None,
+            );
+        }
+        let progress = tracker.record(start + Duration::from_secs(15), 30 * MB, None);
+
+        // A cumulative average would report 2 MB/s
+        assert_eq!(progress.bytes_per_sec, 4.0 * MB as f64);
+        assert_eq!(progress.eta_secs, None);
+        assert_eq!(progress.fraction(), None);
+    }
+
+    #[test]
+    fn test_reset_forgets_paused_time() {
+        let mut tracker = SpeedTracker::new();
+        let start = Instant::now();
+
+        tracker.record(start, 0, Some(4 * MB));
+        tracker.reset();
+        tracker.record(start + Duration::from_secs(60), MB, Some(4 * MB));
+        let progress = tracker.record(start + Duration::from_secs(61), 2 * MB, Some(4 * MB));
+
+        assert_eq!(progress.bytes_per_sec, MB as f64);
+        assert_eq!(progress.eta_secs, Some(2.0));
+    }
+
+    #[test]
+    fn test_complete_progress() {
+        let progress = DownloadProgress::complete(MB);
+        assert_eq!(progress.fraction(), Some(1.0));
+        assert_eq!(progress.eta_secs, Some(0.0));
+    }
+}
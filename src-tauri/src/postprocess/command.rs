@@ -0,0 +1,231 @@
+use anyhow::{anyhow, bail, Context, Result};
+use std::io::{Read, Write};
+use std::process::{Command, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Default time a post-processing command may run, in milliseconds
+pub const DEFAULT_POSTPROCESS_TIMEOUT_MS: u64 = 5000;
+
+/// Upper bound for the configurable timeout, so pasting can't hang for long
+pub const MAX_POSTPROCESS_TIMEOUT_MS: u64 = 60_000;
+
+/// Maximum output accepted from a post-processing command (1 MiB)
+pub const MAX_POSTPROCESS_OUTPUT_BYTES: usize = 1024 * 1024;
+
+/// Amount of stderr kept for the failure message
+const MAX_STDERR_BYTES: usize = 4096;
+
+/// How often the command is checked for having exited
+const POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// Substitutes the `{language}` and `{model}` placeholders in a command
+///
+/// # Arguments
+/// * `command` - Command string from settings
+/// * `language` - Language code of the transcription (e.g., "en")
+/// * `model` - ID of the model used for the transcription
+pub fn expand_placeholders(command: &str, language: &str, model: &str) -> String {
+    command
+        .replace("{language}", language)
+        .replace("{model}", model)
+}
+
+/// Builds a command running `command` through the platform shell
+fn shell_command(command: &str) -> Command {
+    #[cfg(windows)]
+    {
+        let mut shell = Command::new("cmd");
+        shell.args(["/C", command]);
+        shell
+    }
+    #[cfg(not(windows))]
+    {
+        let mut shell = Command::new("sh");
+        shell.args(["-c", command]);
+        shell
+    }
+}
+
+/// Reads a stream to the end, keeping at most `limit + 1` bytes so an
+/// oversized output can be detected without the writer blocking on a full pipe
+fn read_capped(mut reader: impl Read, limit: usize) -> std::io::Result<Vec<u8>> {
+    let mut kept = Vec::new();
+    let mut chunk = [0u8; 8192];
+    loop {
+        let read = reader.read(&mut chunk)?;
+        if read == 0 {
+            return Ok(kept);
+        }
+        let room = (limit + 1).saturating_sub(kept.len());
+        kept.extend_from_slice(&chunk[..read.min(room)]);
+    }
+}
+
+/// Pipes text through an external command
+///
+/// The command runs through the platform shell with the text on stdin.
+/// Its stdout, without trailing line breaks, becomes the new text.
+///
+/// # Arguments
+/// * `command` - Shell command, with placeholders already expanded
+/// * `text` - Text written to the command's stdin
+/// * `timeout` - Time after which the command is killed
+/// * `max_output_bytes` - Maximum accepted size of the command's output
+///
+/// # Returns
+/// * `Ok(String)` with the command's output
+/// * `Err` if the command could not be started, timed out, exited with a
+///   non-zero code, or produced empty, oversized or non-UTF-8 output
+pub fn run_postprocess_command(
+    command: &str,
+    text: &str,
+    timeout: Duration,
+    max_output_bytes: usize,
+) -> Result<String> {
+    let mut child = shell_command(command)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .with_context(|| format!("Failed to start post-processing command '{}'", command))?;
+
+    // Feed stdin and drain stdout/stderr on their own threads so neither side
+    // can block the other on a full pipe
+    let mut stdin = child.stdin.take().context("Failed to open stdin")?;
+    let input = text.to_string();
+    thread::spawn(move || {
+        // A command that doesn't read its input closes the pipe early; that's fine
+        let _ = stdin.write_all(input.as_bytes());
+    });
+
+    let stdout = child.stdout.take().context("Failed to open stdout")?;
+    let stdout_reader = thread::spawn(move || read_capped(stdout, max_output_bytes));
+    let stderr = child.stderr.take().context("Failed to open stderr")?;
+    let stderr_reader = thread::spawn(move || read_capped(stderr, MAX_STDERR_BYTES));
+
+    let deadline = Instant::now() + timeout;
+    let status = loop {
+        if let Some(status) = child.try_wait()? {
+            break status;
+        }
+        if Instant::now() >= deadline {
+            let _ = child.kill();
+            let _ = child.wait();
+            // The reader threads finish once every holder of the pipes is gone
+            bail!(
+                "Post-processing command timed out after {} ms",
+                timeout.as_millis()
+            );
+        }
+        thread::sleep(POLL_INTERVAL);
+    };
+
+    let output = stdout_reader
+        .join()
+        .map_err(|_| anyhow!("Failed to read command output"))??;
+
+    if !status.success() {
+        let stderr = stderr_reader
+            .join()
+            .ok()
+            .and_then(Result::ok)
+            .unwrap_or_default();
+        let stderr = String::from_utf8_lossy(&stderr);
+        bail!(
+            "Post-processing command exited with {}: {}",
+            status,
+            stderr.trim()
+        );
+    }
+
+    if output.len() > max_output_bytes {
+        bail!(
+            "Post-processing command output exceeds {} bytes",
+            max_output_bytes
+        );
+    }
+
+    let output =
+        String::from_utf8(output).context("Post-processing command output is not valid UTF-8")?;
+    let output = output.trim_end_matches(['\r', '\n']);
+    if output.trim().is_empty() {
+        bail!("Post-processing command produced no output");
+    }
+
+    Ok(output.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TIMEOUT: Duration = Duration::from_secs(5);
+
+    #[test]
+    fn test_expand_placeholders() {
+        assert_eq!(
+            expand_placeholders("fix --lang {language} --model {model}", "de", "small"),
+            "fix --lang de --model small"
+        );
+        assert_eq!(expand_placeholders("cat", "de", "small"), "cat");
+    }
+
+    #[test]
+    fn test_read_capped_keeps_one_extra_byte() {
+        let data = vec![b'a'; 100];
+        assert_eq!(read_capped(&data[..], 10).unwrap().len(), 11);
+        assert_eq!(read_capped(&data[..], 200).unwrap().len(), 100);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_output_replaces_text() {
+        let output = run_postprocess_command("tr a-z A-Z", "hello world", TIMEOUT, 1024).unwrap();
+        assert_eq!(output, "HELLO WORLD");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_trailing_newline_is_trimmed() {
+        let output = run_postprocess_command("cat; echo", "hello", TIMEOUT, 1024).unwrap();
+        assert_eq!(output, "hello");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_non_zero_exit_fails() {
+        let err = run_postprocess_command("echo broken >&2; exit 3", "hello", TIMEOUT, 1024)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("exited"), "{}", err);
+        assert!(err.contains("broken"), "{}", err);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_timeout_kills_command() {
+        let started = Instant::now();
+        let err =
+            run_postprocess_command("exec sleep 10", "hello", Duration::from_millis(200), 1024)
+                .unwrap_err()
+                .to_string();
+        assert!(err.contains("timed out"), "{}", err);
+        assert!(started.elapsed() < Duration::from_secs(5));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_oversized_output_fails() {
+        let err = run_postprocess_command("yes a | head -c 5000", "", TIMEOUT, 1000)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("exceeds"), "{}", err);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_empty_output_fails() {
+        assert!(run_postprocess_command("true", "hello", TIMEOUT, 1024).is_err());
+    }
+}
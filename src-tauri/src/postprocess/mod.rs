@@ -0,0 +1,183 @@
+use crate::commands::settings::{Settings, SplitDelivery};
+
+/// Piping transcripts through a user-configured external command
+pub mod command;
+
+/// Normalizes a word for phrase matching: lowercase, without surrounding punctuation
+fn normalize_word(word: &str) -> String {
+    word.trim_matches(|c: char| !c.is_alphanumeric())
+        .to_lowercase()
+}
+
+/// Cleans up a part produced by splitting, returning `None` if nothing meaningful is left
+fn clean_part(part: &str) -> Option<String> {
+    let part = part
+        .trim()
+        .trim_start_matches(|c: char| c.is_whitespace() || matches!(c, ',' | ';' | ':'));
+
+    part.chars()
+        .any(char::is_alphanumeric)
+        .then(|| part.to_string())
+}
+
+/// Splits a transcription on every occurrence of a spoken phrase
+///
+/// Matching is case-insensitive and on whole words, ignoring punctuation that
+/// Whisper attaches to words (so "Next note," matches "next note").
+///
+/// # Arguments
+/// * `text` - Transcribed text
+/// * `phrase` - Phrase separating the parts (e.g., "next note")
+///
+/// # Returns
+/// The trimmed parts in spoken order, with empty parts dropped
+pub fn split_on_phrase(text: &str, phrase: &str) -> Vec<String> {
+    let phrase_words: Vec<String> = phrase
+        .split_whitespace()
+        .map(normalize_word)
+        .filter(|word| !word.is_empty())
+        .collect();
+
+    if phrase_words.is_empty() {
+        return clean_part(text).into_iter().collect();
+    }
+
+    // Byte ranges and normalized form of every word in the text
+    let words: Vec<(usize, usize, String)> = text
+        .split_whitespace()
+        .map(|word| {
+            let start = word.as_ptr() as usize - text.as_ptr() as usize;
+            (start, start + word.len(), normalize_word(word))
+        })
+        .collect();
+
+    let mut parts = Vec::new();
+    let mut part_start = 0;
+    let mut i = 0;
+
+    while i + phrase_words.len() <= words.len() {
+        let candidate = &words[i..i + phrase_words.len()];
+        if candidate
+            .iter()
+            .zip(&phrase_words)
+            .all(|((_, _, word), phrase_word)| word == phrase_word)
+        {
+            parts.extend(clean_part(&text[part_start..candidate[0].0]));
+            part_start = candidate[candidate.len() - 1].1;
+            i += phrase_words.len();
+        } else {
+            i += 1;
+        }
+    }
+    parts.extend(clean_part(&text[part_start..]));
+
+    parts
+}
+
+/// Splits a transcription according to the configured split phrase
+///
+/// # Returns
+/// The parts to store, or the whole text as a single part if no phrase is configured
+pub fn split_transcription(text: &str, settings: &Settings) -> Vec<String> {
+    match settings.split_phrase.as_deref() {
+        Some(phrase) if !phrase.trim().is_empty() => split_on_phrase(text, phrase),
+        _ => vec![text.to_string()],
+    }
+}
+
+/// Builds the text to paste for a transcription
+///
+/// With a split phrase configured, the parts are joined with the configured
+/// separator, or only the first part is pasted, depending on `split_delivery`.
+pub fn delivery_text(text: &str, settings: &Settings) -> String {
+    let parts = split_transcription(text, settings);
+    match settings.split_delivery {
+        SplitDelivery::Joined => parts.join(&settings.split_separator),
+        SplitDelivery::FirstOnly => parts.into_iter().next().unwrap_or_default(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn split_settings(delivery: SplitDelivery) -> Settings {
+        Settings {
+            split_phrase: Some("next note".to_string()),
+            split_delivery: delivery,
+            split_separator: "\n- ".to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_phrase_absent_keeps_text() {
+        assert_eq!(
+            split_on_phrase("Buy milk and eggs.", "next note"),
+            vec!["Buy milk and eggs.".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_splits_on_phrase_case_insensitive() {
+        assert_eq!(
+            split_on_phrase("Buy milk. Next note, call mom.", "next note"),
+            vec!["Buy milk.".to_string(), "call mom.".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_phrase_at_start_and_end() {
+        assert_eq!(
+            split_on_phrase("Next note buy milk next note.", "next note"),
+            vec!["buy milk".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_repeated_phrases_drop_empty_parts() {
+        assert_eq!(
+            split_on_phrase("One. Next note. Next note two next note three", "next note"),
+            vec!["One.".to_string(), "two".to_string(), "three".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_matches_whole_words_only() {
+        assert_eq!(
+            split_on_phrase("The nextnote app is great", "next note"),
+            vec!["The nextnote app is great".to_string()]
+        );
+        assert_eq!(
+            split_on_phrase("Take notes next notebook", "next note"),
+            vec!["Take notes next notebook".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_split_transcription_without_phrase() {
+        let settings = Settings::default();
+        assert_eq!(
+            split_transcription("Buy milk next note call mom", &settings),
+            vec!["Buy milk next note call mom".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_delivery_joined() {
+        let settings = split_settings(SplitDelivery::Joined);
+        assert_eq!(
+            delivery_text("Buy milk next note call mom", &settings),
+            "Buy milk\n- call mom"
+        );
+    }
+
+    #[test]
+    fn test_delivery_first_only() {
+        let settings = split_settings(SplitDelivery::FirstOnly);
+        assert_eq!(
+            delivery_text("Buy milk next note call mom", &settings),
+            "Buy milk"
+        );
+    }
+}
@@ -0,0 +1,351 @@
+use anyhow::{anyhow, Context, Result};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::Value;
+use std::marker::PhantomData;
+use std::path::PathBuf;
+
+/// Migration function: receives the version found on disk and the stored data,
+/// and returns the data upgraded to the store's current version
+pub type MigrateFn = fn(u32, Value) -> Result<Value>;
+
+/// Small JSON persistence helper shared by the sidecar files in the config directory
+///
+/// Data is written as `{ "version": N, "data": ... }` using a temp file followed by
+/// a rename, so a crash mid-write never leaves a truncated file behind. Files that
+/// predate the envelope are treated as version 0. Files that fail to parse are moved
+/// aside to `<file>.corrupt-<timestamp>` instead of being silently overwritten.
+pub struct JsonStore<T> {
+    /// Path of the JSON file
+    path: PathBuf,
+    /// Current schema version
+    version: u32,
+    /// Upgrades data written by older versions
+    migrate: MigrateFn,
+    _marker: PhantomData<T>,
+}
+
+/// Migration function for stores whose layout has not changed between versions
+pub fn no_migration(_from_version: u32, data: Value) -> Result<Value> {
+    Ok(data)
+}
+
+impl<T> JsonStore<T>
+where
+    T: Serialize + DeserializeOwned + Default,
+{
+    /// Creates a store for the given file
+    ///
+    /// # Arguments
+    /// * `path` - Path of the JSON file
+    /// * `version` - Current schema version of `T`
+    /// * `migrate` - Function upgrading data from older versions
+    pub fn new(path: PathBuf, version: u32, migrate: MigrateFn) -> Self {
+        Self {
+            path,
+            version,
+            migrate,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Loads the stored data
+    ///
+    /// # Returns
+    /// * `Ok(T)` with the stored data, or `T::default()` if the file does not exist
+    ///   or was corrupt (in which case it is quarantined)
+    /// * `Err` if the file could not be read or was written by a newer version
+    pub fn load(&self) -> Result<T> {
+        if !self.path.exists() {
+            return Ok(T::default());
+        }
+
+        let contents = std::fs::read_to_string(&self.path)
+            .with_context(|| format!("Failed to read {:?}", self.path))?;
+
+        match self.decode(&contents) {
+            Ok(Some(data)) => Ok(data),
+            Ok(None) => Err(anyhow!(
+                "{:?} was written by a newer version of the application",
+                self.path
+            )),
+            Err(e) => {
+                log::error!("Failed to parse {:?}: {}", self.path, e);
+                self.quarantine();
+                Ok(T::default())
+            }
+        }
+    }
+
+    /// Loads a file that must exist and be valid, such as a user-chosen backup
+    ///
+    /// Unlike `load`, a missing or corrupt file is an error and is left untouched.
+    ///
+    /// # Returns
+    /// * `Ok(T)` with the stored data
+    /// * `Err` if the file is missing, unreadable, invalid or written by a newer version
+    pub fn load_strict(&self) -> Result<T> {
+        let contents = std::fs::read_to_string(&self.path)
+            .with_context(|| format!("Failed to read {:?}", self.path))?;
+
+        self.decode(&contents)
+            .with_context(|| format!("{:?} is not a valid file", self.path))?
+            .ok_or_else(|| {
+                anyhow!(
+                    "{:?} was written by a newer version of the application",
+                    self.path
+                )
+            })
+    }
+
+    /// Atomically writes the data to disk
+    ///
+    /// # Arguments
+    /// * `data` - Data to store
+    ///
+    /// # Returns
+    /// * `Ok(())` if the data was written
+    /// * `Err` if serialization or any file operation failed
+    pub fn save(&self, data: &T) -> Result<()> {
+        let envelope = serde_json::json!({
+            "version": self.version,
+            "data": data,
+        });
+        let contents =
+            serde_json::to_string_pretty(&envelope).context("Failed to serialize data")?;
+
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create directory {:?}", parent))?;
+        }
+
+        let temp_path = self.temp_path();
+        std::fs::write(&temp_path, contents)
+            .with_context(|| format!("Failed to write {:?}", temp_path))?;
+        std::fs::rename(&temp_path, &self.path)
+            .with_context(|| format!("Failed to replace {:?}", self.path))?;
+
+        Ok(())
+    }
+
+    /// Parses file contents, migrating older versions.
+    /// Returns `Ok(None)` if the file was written by a newer version.
+    fn decode(&self, contents: &str) -> Result<Option<T>> {
+        let value: Value = serde_json::from_str(contents)?;
+
+        let (version, data) = match value {
+            Value::Object(mut map) if map.contains_key("version") && map.contains_key("data") => {
+                let version = map
+                    .get("version")
+                    .and_then(Value::as_u64)
+                    .ok_or_else(|| anyhow!("Invalid version field"))?;
+                let version = u32::try_from(version).context("Invalid version field")?;
+                (version, map.remove("data").unwrap_or(Value::Null))
+            }
+            // Files written before the versioned envelope existed
+            other => (0, other),
+        };
+
+        if version > self.version {
+            return Ok(None);
+        }
+
+        let data = if version < self.version {
+            log::info!(
+                "Migrating {:?} from version {} to {}",
+                self.path,
+                version,
+                self.version
+            );
+            (self.migrate)(version, data)?
+        } else {
+            data
+        };
+
+        Ok(Some(serde_json::from_value(data)?))
+    }
+
+    /// Moves a corrupt file aside so it can be inspected later
+    fn quarantine(&self) {
+        let quarantine_path = self.corrupt_path(chrono::Utc::now().timestamp());
+        match std::fs::rename(&self.path, &quarantine_path) {
+            Ok(()) => log::warn!("Moved corrupt file to {:?}", quarantine_path),
+            Err(e) => log::error!("Failed to quarantine {:?}: {}", self.path, e),
+        }
+    }
+
+    /// Path of the temp file used for atomic writes
+    fn temp_path(&self) -> PathBuf {
+        let mut name = self.path.file_name().unwrap_or_default().to_os_string();
+        name.push(".tmp");
+        self.path.with_file_name(name)
+    }
+
+    /// Path a corrupt file is moved to
+    fn corrupt_path(&self, timestamp: i64) -> PathBuf {
+        let mut name = self.path.file_name().unwrap_or_default().to_os_string();
+        name.push(format!(".corrupt-{}", timestamp));
+        self.path.with_file_name(name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+    use std::fs;
+    use std::path::Path;
+
+    #[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+    struct TestData {
+        items: Vec<String>,
+    }
+
+    /// Helper to create a temporary test directory
+    fn create_test_dir() -> PathBuf {
+        use std::time::{SystemTime, UNIX_EPOCH};
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos())
+            .unwrap_or(0);
+        let test_dir = std::env::temp_dir().join(format!(
+            "rustler_storage_test_{}_{}",
+            nanos,
+            std::process::id()
+        ));
+        fs::create_dir_all(&test_dir).unwrap();
+        test_dir
+    }
+
+    /// Helper to clean up test directory
+    fn cleanup_test_dir(path: &Path) {
+        if path.exists() {
+            fs::remove_dir_all(path).ok();
+        }
+    }
+
+    /// Migration used by the tests: version 0 stored a bare list of items
+    fn migrate_bare_list(from_version: u32, data: Value) -> Result<Value> {
+        match from_version {
+            0 => Ok(serde_json::json!({ "items": data })),
+            _ => Ok(data),
+        }
+    }
+
+    #[test]
+    fn test_load_missing_file_returns_default() {
+        let test_dir = create_test_dir();
+        let store: JsonStore<TestData> =
+            JsonStore::new(test_dir.join("data.json"), 1, no_migration);
+
+        assert_eq!(store.load().unwrap(), TestData::default());
+
+        cleanup_test_dir(&test_dir);
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let test_dir = create_test_dir();
+        let store: JsonStore<TestData> =
+            JsonStore::new(test_dir.join("data.json"), 1, no_migration);
+        let data = TestData {
+            items: vec!["a".to_string(), "b".to_string()],
+        };
+
+        store.save(&data).unwrap();
+
+        assert_eq!(store.load().unwrap(), data);
+        assert!(!store.temp_path().exists());
+
+        cleanup_test_dir(&test_dir);
+    }
+
+    #[test]
+    fn test_save_writes_version_envelope() {
+        let test_dir = create_test_dir();
+        let store: JsonStore<TestData> =
+            JsonStore::new(test_dir.join("data.json"), 3, no_migration);
+
+        store.save(&TestData::default()).unwrap();
+
+        let value: Value = serde_json::from_str(&fs::read_to_string(&store.path).unwrap()).unwrap();
+        assert_eq!(value["version"], 3);
+        assert!(value["data"].is_object());
+
+        cleanup_test_dir(&test_dir);
+    }
+
+    #[test]
+    fn test_migrates_unversioned_file() {
+        let test_dir = create_test_dir();
+        let path = test_dir.join("data.json");
+        fs::write(&path, r#"["legacy"]"#).unwrap();
+
+        let store: JsonStore<TestData> = JsonStore::new(path, 1, migrate_bare_list);
+
+        assert_eq!(store.load().unwrap().items, vec!["legacy".to_string()]);
+
+        cleanup_test_dir(&test_dir);
+    }
+
+    #[test]
+    fn test_newer_version_is_rejected_and_kept() {
+        let test_dir = create_test_dir();
+        let path = test_dir.join("data.json");
+        fs::write(&path, r#"{ "version": 9, "data": { "items": [] } }"#).unwrap();
+
+        let store: JsonStore<TestData> = JsonStore::new(path.clone(), 1, no_migration);
+
+        assert!(store.load().is_err());
+        assert!(path.exists());
+
+        cleanup_test_dir(&test_dir);
+    }
+
+    #[test]
+    fn test_corrupt_file_is_quarantined() {
+        let test_dir = create_test_dir();
+        let path = test_dir.join("data.json");
+        fs::write(&path, "{ not valid json").unwrap();
+
+        let store: JsonStore<TestData> = JsonStore::new(path.clone(), 1, no_migration);
+
+        assert_eq!(store.load().unwrap(), TestData::default());
+        assert!(!path.exists());
+
+        let quarantined: Vec<_> = fs::read_dir(&test_dir)
+            .unwrap()
+            .flatten()
+            .filter(|e| {
+                e.file_name()
+                    .to_string_lossy()
+                    .starts_with("data.json.corrupt-")
+            })
+            .collect();
+        assert_eq!(quarantined.len(), 1);
+
+        cleanup_test_dir(&test_dir);
+    }
+
+    #[test]
+    fn test_load_strict_rejects_missing_and_corrupt_files() {
+        let test_dir = create_test_dir();
+        let path = test_dir.join("data.json");
+        let store: JsonStore<TestData> = JsonStore::new(path.clone(), 1, no_migration);
+
+        assert!(store.load_strict().is_err());
+
+        fs::write(&path, "{ not valid json").unwrap();
+        assert!(store.load_strict().is_err());
+        // Not quarantined: the file is the user's, not ours
+        assert!(path.exists());
+
+        let data = TestData {
+            items: vec!["a".to_string()],
+        };
+        store.save(&data).unwrap();
+        assert_eq!(store.load_strict().unwrap(), data);
+
+        cleanup_test_dir(&test_dir);
+    }
+}
@@ -0,0 +1,263 @@
+use crate::commands::hotkey::emit_hotkey_event;
+use crate::hotkey::HotkeyAction;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use tauri::menu::{Menu, MenuBuilder, MenuEvent, MenuItemBuilder};
+use tauri::{AppHandle, Emitter, Manager};
+
+/// Tray icon ID used to look up the tray for icon and menu updates
+pub const TRAY_ID: &str = "main-tray";
+
+/// Event asking the main window to switch to the settings tab
+const OPEN_SETTINGS_EVENT: &str = "tray-open-settings";
+
+/// Whether a recording is in progress, as last passed to `update_tray_menu`
+static RECORDING: AtomicBool = AtomicBool::new(false);
+
+/// Number of transcriptions running, counted by `TranscribingTray`
+static TRANSCRIPTIONS: AtomicUsize = AtomicUsize::new(0);
+
+/// What the app is doing, as reflected by the tray menu
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TrayStatus {
+    Idle,
+    Recording,
+    Transcribing,
+}
+
+impl TrayStatus {
+    /// Status for the given state; a recording started while an earlier one
+    /// is still transcribing shows as recording
+    fn new(is_recording: bool, is_transcribing: bool) -> Self {
+        if is_recording {
+            TrayStatus::Recording
+        } else if is_transcribing {
+            TrayStatus::Transcribing
+        } else {
+            TrayStatus::Idle
+        }
+    }
+
+    /// Current status of the app
+    fn current() -> Self {
+        Self::new(
+            RECORDING.load(Ordering::SeqCst),
+            TRANSCRIPTIONS.load(Ordering::SeqCst) > 0,
+        )
+    }
+}
+
+/// An entry of the tray context menu
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TrayAction {
+    StartRecording,
+    StopRecording,
+    Open,
+    Settings,
+    Quit,
+}
+
+impl TrayAction {
+    /// Every entry, in menu order
+    const ALL: [TrayAction; 5] = [
+        TrayAction::StartRecording,
+        TrayAction::StopRecording,
+        TrayAction::Open,
+        TrayAction::Settings,
+        TrayAction::Quit,
+    ];
+
+    /// Menu item ID the entry is reported under in menu events
+    fn id(self) -> &'static str {
+        match self {
+            TrayAction::StartRecording => "start-recording",
+            TrayAction::StopRecording => "stop-recording",
+            TrayAction::Open => "show",
+            TrayAction::Settings => "settings",
+            TrayAction::Quit => "quit",
+        }
+    }
+
+    /// Text of the entry; the stop entry reports a running transcription
+    fn label(self, status: TrayStatus) -> &'static str {
+        match self {
+            TrayAction::StartRecording => "Start Recording",
+            TrayAction::StopRecording if status == TrayStatus::Transcribing => "Transcribing...",
+            TrayAction::StopRecording => "Stop Recording",
+            TrayAction::Open => "Open Rustler",
+            TrayAction::Settings => "Settings",
+            TrayAction::Quit => "Quit",
+        }
+    }
+
+    fn from_id(id: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|action| action.id() == id)
+    }
+
+    /// Whether the entry can be chosen; only the recording control matching
+    /// the current state is enabled. A new recording can be started while the
+    /// previous one is transcribing, since transcriptions are queued.
+    fn is_enabled(self, status: TrayStatus) -> bool {
+        match self {
+            TrayAction::StartRecording => status != TrayStatus::Recording,
+            TrayAction::StopRecording => status == TrayStatus::Recording,
+            _ => true,
+        }
+    }
+}
+
+/// Builds the tray context menu for the current recording and transcription state
+///
+/// # Arguments
+/// * `app` - Application handle
+pub fn build_tray_menu(app: &AppHandle) -> tauri::Result<Menu<tauri::Wry>> {
+    let status = TrayStatus::current();
+    let mut builder = MenuBuilder::new(app);
+    for action in TrayAction::ALL {
+        if matches!(action, TrayAction::Open | TrayAction::Quit) {
+            builder = builder.separator();
+        }
+        let item = MenuItemBuilder::new(action.label(status))
+            .id(action.id())
+            .enabled(action.is_enabled(status))
+            .build(app)?;
+        builder = builder.item(&item);
+    }
+    builder.build()
+}
+
+/// Rebuilds the tray menu so the recording controls match the recording state
+///
+/// # Arguments
+/// * `app` - Application handle
+/// * `is_recording` - Whether a recording is in progress
+pub fn update_tray_menu(app: &AppHandle, is_recording: bool) {
+    RECORDING.store(is_recording, Ordering::SeqCst);
+    refresh_tray_menu(app);
+}
+
+/// Rebuilds the tray menu from the current state
+fn refresh_tray_menu(app: &AppHandle) {
+    let Some(tray) = app.tray_by_id(TRAY_ID) else {
+        return;
+    };
+    match build_tray_menu(app) {
+        Ok(menu) => {
+            if let Err(e) = tray.set_menu(Some(menu)) {
+                log::warn!("Failed to update tray menu: {}", e);
+            }
+        }
+        Err(e) => log::warn!("Failed to build tray menu: {}", e),
+    }
+}
+
+/// Shows a transcription in the tray menu while alive
+///
+/// Created when a transcription starts; dropping it on any exit path, errors
+/// and cancellation included, restores the menu.
+pub struct TranscribingTray {
+    app: AppHandle,
+}
+
+impl TranscribingTray {
+    /// Marks a transcription as running in the tray menu
+    pub fn start(app: &AppHandle) -> Self {
+        TRANSCRIPTIONS.fetch_add(1, Ordering::SeqCst);
+        refresh_tray_menu(app);
+        Self { app: app.clone() }
+    }
+}
+
+impl Drop for TranscribingTray {
+    fn drop(&mut self) {
+        TRANSCRIPTIONS.fetch_sub(1, Ordering::SeqCst);
+        refresh_tray_menu(&self.app);
+    }
+}
+
+/// Handles a click on a tray menu entry
+///
+/// Start and Stop emit the recording hotkey's event rather than calling the
+/// recording commands, so the frontend runs the same pipeline as for the
+/// hotkey: stopping transcribes, saves to history and pastes the text.
+pub fn handle_menu_event(app: &AppHandle, event: MenuEvent) {
+    match TrayAction::from_id(event.id().as_ref()) {
+        Some(TrayAction::StartRecording | TrayAction::StopRecording) => {
+            emit_hotkey_event(app, HotkeyAction::ToggleRecording);
+        }
+        Some(TrayAction::Open) => show_main_window(app),
+        Some(TrayAction::Settings) => {
+            show_main_window(app);
+            let _ = app.emit_to("main", OPEN_SETTINGS_EVENT, ());
+        }
+        Some(TrayAction::Quit) => app.exit(0),
+        None => {}
+    }
+}
+
+/// Shows and focuses the main window
+pub fn show_main_window(app: &AppHandle) {
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.show();
+        let _ = window.set_focus();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALL_STATUSES: [TrayStatus; 3] = [
+        TrayStatus::Idle,
+        TrayStatus::Recording,
+        TrayStatus::Transcribing,
+    ];
+
+    #[test]
+    fn test_recording_controls_follow_recording_state() {
+        assert!(TrayAction::StartRecording.is_enabled(TrayStatus::Idle));
+        assert!(!TrayAction::StopRecording.is_enabled(TrayStatus::Idle));
+
+        assert!(!TrayAction::StartRecording.is_enabled(TrayStatus::Recording));
+        assert!(TrayAction::StopRecording.is_enabled(TrayStatus::Recording));
+    }
+
+    #[test]
+    fn test_stop_entry_is_disabled_and_relabelled_while_transcribing() {
+        let status = TrayStatus::new(false, true);
+        assert_eq!(status, TrayStatus::Transcribing);
+
+        assert!(!TrayAction::StopRecording.is_enabled(status));
+        assert_eq!(TrayAction::StopRecording.label(status), "Transcribing...");
+        // The next recording can start while the last one is transcribed
+        assert!(TrayAction::StartRecording.is_enabled(status));
+
+        assert_eq!(
+            TrayAction::StopRecording.label(TrayStatus::Recording),
+            "Stop Recording"
+        );
+    }
+
+    #[test]
+    fn test_recording_takes_precedence_over_transcribing() {
+        assert_eq!(TrayStatus::new(true, true), TrayStatus::Recording);
+        assert_eq!(TrayStatus::new(true, false), TrayStatus::Recording);
+        assert_eq!(TrayStatus::new(false, false), TrayStatus::Idle);
+    }
+
+    #[test]
+    fn test_other_entries_are_always_enabled() {
+        for action in [TrayAction::Open, TrayAction::Settings, TrayAction::Quit] {
+            for status in ALL_STATUSES {
+                assert!(action.is_enabled(status));
+            }
+        }
+    }
+
+    #[test]
+    fn test_ids_round_trip() {
+        for action in TrayAction::ALL {
+            assert_eq!(TrayAction::from_id(action.id()), Some(action));
+        }
+        assert_eq!(TrayAction::from_id("unknown"), None);
+    }
+}
@@ -2,18 +2,23 @@
 
 use anyhow::Result;
 use parking_lot::Mutex;
+use serde::Serialize;
 use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 use whisper_rs::{WhisperContext, WhisperContextParameters};
 
-/// Default timeout for unloading unused models (5 minutes)
-const DEFAULT_UNLOAD_TIMEOUT: Duration = Duration::from_secs(5 * 60);
+/// Default timeout for unloading unused models, in seconds (5 minutes)
+pub const DEFAULT_UNLOAD_TIMEOUT_SECS: u64 = 5 * 60;
+
+/// Default number of models kept loaded at the same time
+pub const DEFAULT_MAX_CACHED_MODELS: usize = 2;
 
 /// Cached Whisper model with usage tracking
-struct CachedModel {
-    /// The loaded Whisper context
-    context: WhisperContext,
+struct CachedModel<C> {
+    /// The loaded Whisper context, shared with the guards of running transcriptions
+    context: Arc<C>,
     /// Model identifier (name)
     model_id: String,
     /// Whether this model was loaded with GPU
@@ -22,12 +27,87 @@ struct CachedModel {
     last_used: Instant,
 }
 
-/// Model cache that keeps models loaded and unloads them after inactivity
-pub struct ModelCache {
-    /// Currently cached model (only one at a time to save memory)
-    cached: Mutex<Option<CachedModel>>,
-    /// Timeout after which unused models are unloaded
-    unload_timeout: Duration,
+impl<C> CachedModel<C> {
+    /// Returns true if this entry was loaded for the given model and GPU setting
+    fn matches(&self, model_id: &str, use_gpu: bool) -> bool {
+        self.model_id == model_id && self.use_gpu == use_gpu
+    }
+
+    /// Returns true if a `ModelGuard` for this model is alive
+    fn in_use(&self) -> bool {
+        Arc::strong_count(&self.context) > 1
+    }
+}
+
+/// Returns true if a model unused for `idle_for` should be unloaded.
+/// A timeout of 0 means models are never unloaded automatically.
+fn is_expired(idle_for: Duration, timeout_secs: u64) -> bool {
+    timeout_secs > 0 && idle_for > Duration::from_secs(timeout_secs)
+}
+
+/// A model loaded in the cache
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SlotInfo {
+    /// Model identifier (name)
+    pub model_id: String,
+    /// Whether the model was loaded with GPU
+    pub use_gpu: bool,
+    /// Whole seconds since the model was last used
+    pub idle_secs: u64,
+    /// Whether a transcription is using the model right now
+    pub in_use: bool,
+}
+
+/// State of the model cache, for display in the frontend
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CacheInfo {
+    /// Most recently used model, `None` if no model is loaded
+    pub model_id: Option<String>,
+    /// Whole seconds since the most recently used model was last used
+    pub idle_secs: Option<u64>,
+    /// Whether the most recently used model was loaded with GPU
+    pub use_gpu: Option<bool>,
+    /// Every loaded model, most recently used first
+    pub loaded_slots: Vec<SlotInfo>,
+}
+
+/// Called with the GPU error when a model had to be loaded on the CPU instead
+type GpuFallbackListener = Box<dyn Fn(&str) + Send + Sync>;
+
+/// Returns the index of the least recently used entry, if any
+fn least_recently_used(last_used: impl Iterator<Item = Instant>) -> Option<usize> {
+    last_used
+        .enumerate()
+        .min_by_key(|(_, used)| *used)
+        .map(|(index, _)| index)
+}
+
+/// Model cache that keeps up to N models loaded, keyed by model and GPU setting,
+/// evicting the least recently used one when full and unloading idle ones
+///
+/// Models in use by a `ModelGuard` are never unloaded for being idle or
+/// evicted, so at most N models are in memory: if every cached model is in
+/// use, a new one is loaded past the limit and the cache shrinks back as the
+/// guards are dropped. A model force unloaded while in use stays alive until
+/// its guards are dropped. Generic over the context type so tests can use a
+/// fake model.
+pub struct ModelCache<C = WhisperContext> {
+    /// Currently cached models
+    cached: Mutex<Vec<CachedModel<C>>>,
+    /// Held while a model is loading, so loads run one at a time without
+    /// keeping `cached` locked
+    loading: Mutex<()>,
+    /// Seconds after which unused models are unloaded (0 = never)
+    unload_timeout_secs: AtomicU64,
+    /// Maximum number of models kept loaded at once
+    max_models: AtomicUsize,
+    /// Set once loading a model on the GPU failed and the CPU worked instead,
+    /// so the GPU isn't retried on every load
+    gpu_unavailable: AtomicBool,
+    /// Notified when a model falls back to the CPU
+    gpu_fallback_listener: Mutex<Option<GpuFallbackListener>>,
 }
 
 impl Default for ModelCache {
@@ -37,139 +117,383 @@ impl Default for ModelCache {
 }
 
 impl ModelCache {
-    /// Creates a new model cache with default timeout (5 minutes)
+    /// Creates a new model cache with default timeout (5 minutes) and capacity (2 models).
+    /// Both can be changed later with `set_timeout` and `set_max_models`.
     pub fn new() -> Self {
-        Self {
-            cached: Mutex::new(None),
-            unload_timeout: DEFAULT_UNLOAD_TIMEOUT,
-        }
+        Self::with_capacity(DEFAULT_MAX_CACHED_MODELS)
+    }
+
+    /// Creates a new model cache holding at most `max_models` models
+    pub fn with_capacity(max_models: usize) -> Self {
+        Self::empty(max_models)
     }
 
-    /// Gets or loads a model, returning a reference to use for transcription
+    /// Creates a new model cache unloading models unused for `timeout`
+    /// (whole seconds, zero to never unload)
+    pub fn with_timeout(timeout: Duration) -> Self {
+        let cache = Self::new();
+        cache.set_timeout(timeout.as_secs());
+        cache
+    }
+
+    /// Gets or loads a model, returning a guard to use for transcription
     ///
     /// If the requested model is already cached with the same GPU setting, returns it immediately.
-    /// If a different model or GPU setting is requested, unloads the current one first.
-    /// Updates the last_used timestamp on access.
+    /// Otherwise the model is loaded, evicting the least recently used model if the cache is full.
+    /// If loading on the GPU fails, the model is loaded on the CPU instead (see
+    /// `get_or_insert_with_fallback`). Updates the last_used timestamp on access.
     pub fn get_or_load(
         &self,
         model_id: &str,
         model_path: PathBuf,
         use_gpu: bool,
     ) -> Result<ModelGuard<'_>> {
-        let mut cached = self.cached.lock();
+        self.get_or_insert_with_fallback(model_id, use_gpu, |use_gpu| {
+            log::info!(
+                "Loading model '{}' from {:?} (GPU: {})",
+                model_id,
+                model_path,
+                use_gpu
+            );
+            let mut params = WhisperContextParameters::default();
+            params.use_gpu(use_gpu);
 
-        // Check if we have the right model cached with the same GPU setting
-        if let Some(ref mut model) = *cached {
-            if model.model_id == model_id && model.use_gpu == use_gpu {
-                // Update last used time
-                model.last_used = Instant::now();
-                log::info!("Using cached model: {} (GPU: {})", model_id, use_gpu);
-                return Ok(ModelGuard {
-                    cache: self,
-                    _marker: std::marker::PhantomData,
-                });
-            } else {
-                // Different model or GPU setting requested, unload current one
-                log::info!(
-                    "Unloading cached model '{}' (GPU: {}) to load '{}' (GPU: {})",
-                    model.model_id,
-                    model.use_gpu,
-                    model_id,
-                    use_gpu
-                );
-            }
+            Ok(WhisperContext::new_with_params(
+                model_path
+                    .to_str()
+                    .ok_or_else(|| anyhow::anyhow!("Invalid model path"))?,
+                params,
+            )?)
+        })
+    }
+}
+
+impl<C> ModelCache<C> {
+    /// Creates an empty cache holding at most `max_models` models
+    fn empty(max_models: usize) -> Self {
+        Self {
+            cached: Mutex::new(Vec::new()),
+            loading: Mutex::new(()),
+            unload_timeout_secs: AtomicU64::new(DEFAULT_UNLOAD_TIMEOUT_SECS),
+            max_models: AtomicUsize::new(max_models.max(1)),
+            gpu_unavailable: AtomicBool::new(false),
+            gpu_fallback_listener: Mutex::new(None),
         }
+    }
+
+    /// Changes the maximum number of cached models, evicting the least
+    /// recently used ones if more are currently loaded
+    pub fn set_max_models(&self, max_models: usize) {
+        let max_models = max_models.max(1);
+        self.max_models.store(max_models, Ordering::SeqCst);
+
+        Self::evict_to(&mut self.cached.lock(), max_models);
+    }
+
+    /// Returns the maximum number of cached models
+    pub fn max_models(&self) -> usize {
+        self.max_models.load(Ordering::SeqCst)
+    }
+
+    /// Changes how long unused models stay loaded
+    ///
+    /// # Arguments
+    /// * `timeout_secs` - Seconds of inactivity before a model is unloaded, 0 to never unload
+    pub fn set_timeout(&self, timeout_secs: u64) {
+        self.unload_timeout_secs
+            .store(timeout_secs, Ordering::SeqCst);
+    }
+
+    /// Returns the unload timeout in seconds (0 = never)
+    pub fn timeout_secs(&self) -> u64 {
+        self.unload_timeout_secs.load(Ordering::SeqCst)
+    }
+
+    /// Removes the least recently used model that isn't in use from the cache
+    ///
+    /// Models in use are skipped: evicting one wouldn't free its memory
+    /// until its guards are dropped.
+    ///
+    /// # Returns
+    /// True if a model was evicted, false if every cached model is in use
+    fn evict_lru(cached: &mut Vec<CachedModel<C>>) -> bool {
+        let idle: Vec<usize> = (0..cached.len()).filter(|&i| !cached[i].in_use()).collect();
+        let Some(index) = least_recently_used(idle.iter().map(|&i| cached[i].last_used))
+            .map(|position| idle[position])
+        else {
+            return false;
+        };
 
-        // Load the new model with specified GPU setting
+        let model = cached.remove(index);
         log::info!(
-            "Loading model '{}' from {:?} (GPU: {})",
-            model_id,
-            model_path,
-            use_gpu
+            "Evicting least recently used model '{}' (GPU: {})",
+            model.model_id,
+            model.use_gpu
         );
-        let mut params = WhisperContextParameters::default();
-        params.use_gpu(use_gpu);
+        true
+    }
 
-        let context = WhisperContext::new_with_params(
-            model_path
-                .to_str()
-                .ok_or_else(|| anyhow::anyhow!("Invalid model path"))?,
-            params,
-        )?;
+    /// Evicts least recently used models until at most `limit` are cached,
+    /// or only models in use are left
+    fn evict_to(cached: &mut Vec<CachedModel<C>>, limit: usize) {
+        while cached.len() > limit && Self::evict_lru(cached) {}
+    }
 
-        *cached = Some(CachedModel {
-            context,
+    /// Returns a guard for the model if it is cached, marking it as used
+    fn cached_guard(&self, model_id: &str, use_gpu: bool) -> Option<ModelGuard<'_, C>> {
+        let mut cached = self.cached.lock();
+        let model = cached.iter_mut().find(|m| m.matches(model_id, use_gpu))?;
+        model.last_used = Instant::now();
+        log::info!("Using cached model: {} (GPU: {})", model_id, use_gpu);
+        Some(ModelGuard {
+            cache: self,
+            context: Arc::clone(&model.context),
+            model_id: model_id.to_string(),
+            use_gpu,
+        })
+    }
+
+    /// Gets a cached model or loads it with `load`, returning a guard that keeps it loaded
+    ///
+    /// The least recently used model is evicted first if the cache is full.
+    /// Loads run one at a time, so a model is never loaded twice, but the
+    /// cache isn't locked while loading: cached models and the cache info
+    /// stay available during the seconds a load takes.
+    ///
+    /// # Arguments
+    /// * `model_id` - Model identifier
+    /// * `use_gpu` - GPU setting the model is loaded with
+    /// * `load` - Loads the model if it isn't cached
+    pub fn get_or_insert_with(
+        &self,
+        model_id: &str,
+        use_gpu: bool,
+        load: impl FnOnce() -> Result<C>,
+    ) -> Result<ModelGuard<'_, C>> {
+        // Check if we have the right model cached with the same GPU setting
+        if let Some(guard) = self.cached_guard(model_id, use_gpu) {
+            return Ok(guard);
+        }
+
+        // Another thread may have loaded the model while this one waited
+        let _loading = self.loading.lock();
+        if let Some(guard) = self.cached_guard(model_id, use_gpu) {
+            return Ok(guard);
+        }
+
+        // Make room before loading, so the evicted model's memory is freed first
+        Self::evict_to(&mut self.cached.lock(), self.max_models() - 1);
+
+        let context = Arc::new(load()?);
+
+        let mut cached = self.cached.lock();
+        Self::evict_to(&mut cached, self.max_models() - 1);
+        cached.push(CachedModel {
+            context: Arc::clone(&context),
             model_id: model_id.to_string(),
             use_gpu,
             last_used: Instant::now(),
         });
 
-        log::info!("Model '{}' loaded and cached (GPU: {})", model_id, use_gpu);
+        log::info!(
+            "Model '{}' loaded and cached (GPU: {}, {} model(s) loaded)",
+            model_id,
+            use_gpu,
+            cached.len()
+        );
 
         Ok(ModelGuard {
             cache: self,
-            _marker: std::marker::PhantomData,
+            context,
+            model_id: model_id.to_string(),
+            use_gpu,
         })
     }
 
-    /// Access the cached context for transcription
+    /// Gets a cached model or loads it with `load`, falling back to the CPU if
+    /// the GPU load fails
     ///
-    /// # Safety
-    /// Only call this while holding a ModelGuard
-    pub fn with_context<F, R>(&self, f: F) -> Result<R>
-    where
-        F: FnOnce(&WhisperContext) -> Result<R>,
-    {
-        let cached = self.cached.lock();
-        match &*cached {
-            Some(model) => f(&model.context),
-            None => Err(anyhow::anyhow!("No model loaded")),
+    /// After a successful fallback the GPU is considered unavailable and later
+    /// loads go straight to the CPU, until `reset_gpu_fallback` is called. If
+    /// the CPU load fails too, the model itself is broken and the GPU isn't
+    /// blamed.
+    ///
+    /// # Arguments
+    /// * `model_id` - Model identifier
+    /// * `use_gpu` - GPU setting from settings
+    /// * `load` - Loads the model, with the GPU if its argument is true
+    pub fn get_or_insert_with_fallback(
+        &self,
+        model_id: &str,
+        use_gpu: bool,
+        load: impl Fn(bool) -> Result<C>,
+    ) -> Result<ModelGuard<'_, C>> {
+        if !use_gpu || self.gpu_unavailable() {
+            return self.get_or_insert_with(model_id, false, || load(false));
+        }
+
+        let gpu_error = match self.get_or_insert_with(model_id, true, || load(true)) {
+            Ok(guard) => return Ok(guard),
+            Err(e) => e,
+        };
+        log::warn!(
+            "Failed to load model '{}' on the GPU, retrying on the CPU: {:#}",
+            model_id,
+            gpu_error
+        );
+
+        let guard = self
+            .get_or_insert_with(model_id, false, || load(false))
+            .map_err(|e| anyhow::anyhow!("{:#} (GPU load failed too: {:#})", e, gpu_error))?;
+
+        self.gpu_unavailable.store(true, Ordering::SeqCst);
+        if let Some(listener) = self.gpu_fallback_listener.lock().as_ref() {
+            listener(&format!("{:#}", gpu_error));
+        }
+        Ok(guard)
+    }
+
+    /// Returns true if models are loaded on the CPU because the GPU failed
+    pub fn gpu_unavailable(&self) -> bool {
+        self.gpu_unavailable.load(Ordering::SeqCst)
+    }
+
+    /// Forgets an earlier GPU failure, so the next load tries the GPU again
+    pub fn reset_gpu_fallback(&self) {
+        self.gpu_unavailable.store(false, Ordering::SeqCst);
+    }
+
+    /// Sets the function called with the GPU error whenever a model falls back to the CPU
+    pub fn on_gpu_fallback(&self, listener: impl Fn(&str) + Send + Sync + 'static) {
+        *self.gpu_fallback_listener.lock() = Some(Box::new(listener));
+    }
+
+    /// Marks a cached model as used just now, and evicts models loaded past
+    /// the limit while every cached model was in use
+    fn touch(&self, model_id: &str, use_gpu: bool) {
+        let mut cached = self.cached.lock();
+        if let Some(model) = cached.iter_mut().find(|m| m.matches(model_id, use_gpu)) {
+            model.last_used = Instant::now();
         }
+        Self::evict_to(&mut cached, self.max_models());
     }
 
-    /// Checks if the cached model has been idle for longer than the timeout
-    /// and unloads it if so. Returns true if a model was unloaded.
+    /// Unloads every cached model that has been idle for longer than the timeout.
+    /// Models in use are kept regardless of when they were last used.
+    /// Returns true if at least one model was unloaded.
     pub fn cleanup_if_idle(&self) -> bool {
+        let timeout_secs = self.timeout_secs();
         let mut cached = self.cached.lock();
+        let before = cached.len();
 
-        if let Some(ref model) = *cached {
-            if model.last_used.elapsed() > self.unload_timeout {
+        cached.retain(|model| {
+            let idle = !model.in_use() && is_expired(model.last_used.elapsed(), timeout_secs);
+            if idle {
                 log::info!(
                     "Unloading model '{}' after {} seconds of inactivity",
                     model.model_id,
                     model.last_used.elapsed().as_secs()
                 );
-                *cached = None;
-                return true;
             }
-        }
+            !idle
+        });
 
-        false
+        cached.len() < before
     }
 
-    /// Forces unloading of any cached model
+    /// Forces unloading of all cached models
+    ///
+    /// Models in use are removed from the cache but stay alive until the
+    /// transcriptions using them finish.
     pub fn unload(&self) {
         let mut cached = self.cached.lock();
-        if let Some(ref model) = *cached {
+        for model in cached.iter() {
             log::info!("Force unloading model: {}", model.model_id);
         }
-        *cached = None;
+        cached.clear();
+    }
+
+    /// Returns info about the currently cached models, most recently used first
+    pub fn get_cached_info(&self) -> Vec<SlotInfo> {
+        let mut cached: Vec<(Instant, SlotInfo)> = self
+            .cached
+            .lock()
+            .iter()
+            .map(|m| {
+                let slot = SlotInfo {
+                    model_id: m.model_id.clone(),
+                    use_gpu: m.use_gpu,
+                    idle_secs: m.last_used.elapsed().as_secs(),
+                    in_use: m.in_use(),
+                };
+                (m.last_used, slot)
+            })
+            .collect();
+        cached.sort_by_key(|(last_used, _)| std::cmp::Reverse(*last_used));
+        cached.into_iter().map(|(_, slot)| slot).collect()
     }
 
-    /// Returns info about the currently cached model, if any
-    pub fn get_cached_info(&self) -> Option<(String, Duration)> {
-        let cached = self.cached.lock();
-        cached
-            .as_ref()
-            .map(|m| (m.model_id.clone(), m.last_used.elapsed()))
+    /// Returns the state of the cache, describing the most recently used model
+    /// and listing every loaded one
+    pub fn cache_info(&self) -> CacheInfo {
+        let loaded_slots = self.get_cached_info();
+        match loaded_slots.first() {
+            Some(latest) => CacheInfo {
+                model_id: Some(latest.model_id.clone()),
+                idle_secs: Some(latest.idle_secs),
+                use_gpu: Some(latest.use_gpu),
+                loaded_slots,
+            },
+            None => CacheInfo::default(),
+        }
+    }
+
+    /// Returns the IDs of the loaded models, most recently used first.
+    /// A model loaded both with and without GPU is listed once.
+    pub fn loaded_models(&self) -> Vec<String> {
+        let mut models: Vec<String> = Vec::new();
+        for slot in self.get_cached_info() {
+            if !models.contains(&slot.model_id) {
+                models.push(slot.model_id);
+            }
+        }
+        models
     }
 }
 
-/// Guard that ensures the model stays loaded while in use
-pub struct ModelGuard<'a> {
-    #[allow(dead_code)]
-    cache: &'a ModelCache,
-    _marker: std::marker::PhantomData<&'a ()>,
+/// Guard that keeps a model loaded while in use
+///
+/// Holds a reference to the context, so the model can't be freed by idle
+/// cleanup, eviction or `unload` before the guard is dropped.
+pub struct ModelGuard<'a, C = WhisperContext> {
+    cache: &'a ModelCache<C>,
+    /// Context of the guarded model
+    context: Arc<C>,
+    /// Model this guard was handed out for
+    model_id: String,
+    /// GPU setting the model was loaded with
+    use_gpu: bool,
+}
+
+impl<C> ModelGuard<'_, C> {
+    /// Access the guarded model's context for transcription
+    ///
+    /// The cache isn't locked while `f` runs, so other models can be loaded
+    /// and cleanup can run in the meantime.
+    pub fn with_context<F, R>(&self, f: F) -> Result<R>
+    where
+        F: FnOnce(&C) -> Result<R>,
+    {
+        f(&self.context)
+    }
+}
+
+impl<C> Drop for ModelGuard<'_, C> {
+    fn drop(&mut self) {
+        // Start the idle timeout when the model stops being used, not when
+        // the (possibly long) transcription started
+        self.cache.touch(&self.model_id, self.use_gpu);
+    }
 }
 
 /// Global model cache instance
@@ -186,6 +510,16 @@ pub fn get_model_cache() -> Arc<ModelCache> {
 pub fn start_cleanup_task() {
     std::thread::spawn(|| {
         let cache = get_model_cache();
+
+        // Apply the configured limits before the first model gets loaded
+        match crate::commands::settings::current_settings() {
+            Ok(settings) => {
+                cache.set_timeout(settings.model_unload_timeout_secs);
+                cache.set_max_models(settings.max_cached_models);
+            }
+            Err(e) => log::warn!("Using default model cache settings: {}", e),
+        }
+
         loop {
             // Check every 30 seconds
             std::thread::sleep(Duration::from_secs(30));
@@ -193,3 +527,525 @@ pub fn start_cleanup_task() {
         }
     });
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::AtomicBool;
+    use std::sync::Barrier;
+
+    /// Stand-in for a Whisper context that records when it is freed
+    struct FakeContext {
+        dropped: Arc<AtomicBool>,
+    }
+
+    impl FakeContext {
+        fn is_alive(&self) -> bool {
+            !self.dropped.load(Ordering::SeqCst)
+        }
+    }
+
+    impl Drop for FakeContext {
+        fn drop(&mut self) {
+            self.dropped.store(true, Ordering::SeqCst);
+        }
+    }
+
+    /// Cache with a 1 second timeout and a loader for fake models
+    fn fake_cache() -> ModelCache<FakeContext> {
+        let cache = ModelCache::empty(2);
+        cache.set_timeout(1);
+        cache
+    }
+
+    /// Loads a fake model, returning the flag set when its context is freed
+    fn load_fake<'a>(
+        cache: &'a ModelCache<FakeContext>,
+        model_id: &str,
+    ) -> (ModelGuard<'a, FakeContext>, Arc<AtomicBool>) {
+        let dropped = Arc::new(AtomicBool::new(false));
+        let flag = Arc::clone(&dropped);
+        let guard = cache
+            .get_or_insert_with(model_id, false, || Ok(FakeContext { dropped: flag }))
+            .unwrap();
+        (guard, dropped)
+    }
+
+    /// Pretends every cached model was last used long past the timeout
+    fn make_idle(cache: &ModelCache<FakeContext>) {
+        for model in cache.cached.lock().iter_mut() {
+            model.last_used = model
+                .last_used
+                .checked_sub(Duration::from_secs(10))
+                .unwrap();
+        }
+    }
+
+    #[test]
+    fn test_cleanup_keeps_model_in_use_by_long_transcription() {
+        let cache = fake_cache();
+        let (guard, dropped) = load_fake(&cache, "base");
+        let started = Barrier::new(2);
+        let cleaned_up = Barrier::new(2);
+
+        std::thread::scope(|scope| {
+            let transcription = scope.spawn(|| {
+                guard.with_context(|context| {
+                    started.wait();
+                    // Cleanup fires while the transcription is running
+                    cleaned_up.wait();
+                    Ok(context.is_alive())
+                })
+            });
+
+            started.wait();
+            make_idle(&cache);
+            assert!(!cache.cleanup_if_idle());
+            cleaned_up.wait();
+
+            assert!(transcription.join().unwrap().unwrap());
+        });
+
+        // Between transcriptions the guard still pins the model
+        make_idle(&cache);
+        assert!(!cache.cleanup_if_idle());
+        assert!(!dropped.load(Ordering::SeqCst));
+
+        drop(guard);
+        make_idle(&cache);
+        assert!(cache.cleanup_if_idle());
+        assert!(dropped.load(Ordering::SeqCst));
+    }
+
+    #[test]
+    fn test_concurrent_transcriptions_do_not_serialize_on_cache_lock() {
+        let cache = fake_cache();
+        let (first, _) = load_fake(&cache, "base");
+        let second = cache
+            .get_or_insert_with("base", false, || panic!("model loaded twice"))
+            .unwrap();
+        let inside = AtomicUsize::new(0);
+
+        // Each transcription waits inside `with_context` until the other one is
+        // inside too, which times out if the cache lock were held while decoding
+        let transcribe = |guard: &ModelGuard<'_, FakeContext>| {
+            guard.with_context(|_| {
+                inside.fetch_add(1, Ordering::SeqCst);
+                let deadline = Instant::now() + Duration::from_secs(5);
+                while inside.load(Ordering::SeqCst) < 2 {
+                    if Instant::now() > deadline {
+                        return Ok(false);
+                    }
+                    std::thread::yield_now();
+                }
+                Ok(true)
+            })
+        };
+
+        std::thread::scope(|scope| {
+            let a = scope.spawn(|| transcribe(&first));
+            let b = scope.spawn(|| transcribe(&second));
+
+            // The cache stays usable while both are running
+            assert_eq!(cache.get_cached_info().len(), 1);
+
+            assert!(a.join().unwrap().unwrap());
+            assert!(b.join().unwrap().unwrap());
+        });
+    }
+
+    #[test]
+    fn test_cache_is_not_locked_while_loading() {
+        let cache = fake_cache();
+        let (base, _) = load_fake(&cache, "base");
+        drop(base);
+        let loading = Barrier::new(2);
+        let queried = Barrier::new(2);
+
+        std::thread::scope(|scope| {
+            let load = scope.spawn(|| {
+                cache
+                    .get_or_insert_with("large", false, || {
+                        loading.wait();
+                        // The cache is used while the model is loading
+                        queried.wait();
+                        Ok(FakeContext {
+                            dropped: Arc::new(AtomicBool::new(false)),
+                        })
+                    })
+                    .map(|_| ())
+            });
+
+            loading.wait();
+            assert_eq!(cache.loaded_models(), vec!["base"]);
+            cache
+                .get_or_insert_with("base", false, || panic!("model loaded twice"))
+                .unwrap();
+            queried.wait();
+
+            load.join().unwrap().unwrap();
+        });
+
+        assert_eq!(cache.loaded_models(), vec!["large", "base"]);
+    }
+
+    #[test]
+    fn test_concurrent_loads_of_a_model_load_it_once() {
+        let cache = fake_cache();
+        let loads = AtomicUsize::new(0);
+        let load = || {
+            loads.fetch_add(1, Ordering::SeqCst);
+            std::thread::sleep(Duration::from_millis(50));
+            Ok(FakeContext {
+                dropped: Arc::new(AtomicBool::new(false)),
+            })
+        };
+
+        std::thread::scope(|scope| {
+            let first = scope.spawn(|| cache.get_or_insert_with("base", false, load).map(|_| ()));
+            let second = scope.spawn(|| cache.get_or_insert_with("base", false, load).map(|_| ()));
+            first.join().unwrap().unwrap();
+            second.join().unwrap().unwrap();
+        });
+
+        assert_eq!(loads.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn test_unload_during_transcription_frees_model_afterwards() {
+        let cache = fake_cache();
+        let (guard, dropped) = load_fake(&cache, "base");
+
+        std::thread::scope(|scope| {
+            let transcription = scope.spawn(|| {
+                guard.with_context(|context| {
+                    std::thread::sleep(Duration::from_millis(50));
+                    Ok(context.is_alive())
+                })
+            });
+
+            cache.unload();
+            assert!(cache.get_cached_info().is_empty());
+            assert!(transcription.join().unwrap().unwrap());
+        });
+
+        assert!(!dropped.load(Ordering::SeqCst));
+        drop(guard);
+        assert!(dropped.load(Ordering::SeqCst));
+    }
+
+    #[test]
+    fn test_eviction_prefers_models_not_in_use() {
+        let cache = fake_cache();
+        let (in_use, in_use_dropped) = load_fake(&cache, "base");
+        let (idle, idle_dropped) = load_fake(&cache, "small");
+        drop(idle);
+
+        // "base" is the least recently used, but a transcription holds it
+        make_idle(&cache);
+        let (_medium, _) = load_fake(&cache, "medium");
+
+        let cached: Vec<String> = cache
+            .get_cached_info()
+            .into_iter()
+            .map(|slot| slot.model_id)
+            .collect();
+        assert_eq!(cached, vec!["medium", "base"]);
+        assert!(idle_dropped.load(Ordering::SeqCst));
+        assert!(!in_use_dropped.load(Ordering::SeqCst));
+        drop(in_use);
+    }
+
+    #[test]
+    fn test_models_in_use_are_never_evicted() {
+        let cache = fake_cache();
+        let (base, base_dropped) = load_fake(&cache, "base");
+        let (small, _) = load_fake(&cache, "small");
+
+        // Both cached models are in use, so the new one goes past the limit
+        let (medium, _) = load_fake(&cache, "medium");
+        assert_eq!(cache.get_cached_info().len(), 3);
+        assert!(!base_dropped.load(Ordering::SeqCst));
+
+        // Once a model is no longer in use, the cache shrinks back to the limit
+        drop(medium);
+        assert_eq!(cache.get_cached_info().len(), 3);
+        drop(base);
+        assert_eq!(cache.loaded_models(), vec!["base", "small"]);
+        assert!(!base_dropped.load(Ordering::SeqCst));
+        drop(small);
+        assert_eq!(cache.loaded_models(), vec!["small", "base"]);
+    }
+
+    #[test]
+    fn test_full_cache_evicts_least_recently_used_model() {
+        let cache = fake_cache();
+        let set_idle_for = |model_id: &str, secs: u64| {
+            let mut cached = cache.cached.lock();
+            let model = cached.iter_mut().find(|m| m.model_id == model_id).unwrap();
+            model.last_used = Instant::now()
+                .checked_sub(Duration::from_secs(secs))
+                .unwrap();
+        };
+
+        let (base, _) = load_fake(&cache, "base");
+        let (large, large_dropped) = load_fake(&cache, "large");
+        drop((base, large));
+        // "base" was used again after "large"
+        set_idle_for("base", 10);
+        set_idle_for("large", 20);
+        assert_eq!(cache.loaded_models(), vec!["base", "large"]);
+
+        let (_small, _) = load_fake(&cache, "small");
+
+        assert_eq!(cache.loaded_models(), vec!["small", "base"]);
+        assert!(large_dropped.load(Ordering::SeqCst));
+    }
+
+    #[test]
+    fn test_cache_info_without_models_is_empty() {
+        let cache = fake_cache();
+        assert_eq!(cache.cache_info(), CacheInfo::default());
+
+        let (guard, _) = load_fake(&cache, "base");
+        drop(guard);
+        cache.unload();
+
+        let info = cache.cache_info();
+        assert_eq!(info.model_id, None);
+        assert_eq!(info.idle_secs, None);
+        assert_eq!(info.use_gpu, None);
+        assert!(info.loaded_slots.is_empty());
+    }
+
+    #[test]
+    fn test_cache_info_describes_most_recently_used_model() {
+        let cache = fake_cache();
+        let (base, _) = load_fake(&cache, "base");
+        drop(base);
+        make_idle(&cache);
+        let (_small, _) = load_fake(&cache, "small");
+
+        let info = cache.cache_info();
+        assert_eq!(info.model_id.as_deref(), Some("small"));
+        assert_eq!(info.idle_secs, Some(0));
+        assert_eq!(info.use_gpu, Some(false));
+        assert_eq!(
+            info.loaded_slots,
+            vec![
+                SlotInfo {
+                    model_id: "small".to_string(),
+                    use_gpu: false,
+                    idle_secs: 0,
+                    in_use: true,
+                },
+                SlotInfo {
+                    model_id: "base".to_string(),
+                    use_gpu: false,
+                    idle_secs: 10,
+                    in_use: false,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_loaded_models_lists_gpu_and_cpu_copies_once() {
+        let cache = fake_cache();
+        let load = || {
+            Ok(FakeContext {
+                dropped: Arc::new(AtomicBool::new(false)),
+            })
+        };
+
+        let _cpu = cache.get_or_insert_with("base", false, load).unwrap();
+        let _gpu = cache.get_or_insert_with("base", true, load).unwrap();
+
+        assert_eq!(cache.get_cached_info().len(), 2);
+        assert_eq!(cache.loaded_models(), vec!["base"]);
+    }
+
+    #[test]
+    fn test_cached_model_is_shared_and_loaded_once() {
+        let cache = fake_cache();
+        let loads = AtomicUsize::new(0);
+        let load = || {
+            loads.fetch_add(1, Ordering::SeqCst);
+            Ok(FakeContext {
+                dropped: Arc::new(AtomicBool::new(false)),
+            })
+        };
+
+        let first = cache.get_or_insert_with("base", false, load).unwrap();
+        let second = cache.get_or_insert_with("base", false, load).unwrap();
+        // A different GPU setting is a different model
+        let gpu = cache.get_or_insert_with("base", true, load).unwrap();
+
+        assert_eq!(loads.load(Ordering::SeqCst), 2);
+        assert!(Arc::ptr_eq(&first.context, &second.context));
+        assert!(!Arc::ptr_eq(&first.context, &gpu.context));
+    }
+
+    /// Loader recording every attempt, failing on the GPU and optionally on the CPU
+    fn broken_gpu_loader(
+        attempts: &Mutex<Vec<bool>>,
+        cpu_works: bool,
+    ) -> impl Fn(bool) -> Result<FakeContext> + '_ {
+        move |use_gpu| {
+            attempts.lock().push(use_gpu);
+            if use_gpu {
+                anyhow::bail!("no CUDA device found");
+            }
+            if !cpu_works {
+                anyhow::bail!("invalid model file");
+            }
+            Ok(FakeContext {
+                dropped: Arc::new(AtomicBool::new(false)),
+            })
+        }
+    }
+
+    #[test]
+    fn test_gpu_load_failure_falls_back_to_cpu_once() {
+        let cache = fake_cache();
+        let attempts = Mutex::new(Vec::new());
+        let notified = Arc::new(Mutex::new(Vec::new()));
+        let listener_notified = Arc::clone(&notified);
+        cache.on_gpu_fallback(move |error| listener_notified.lock().push(error.to_string()));
+        let load = broken_gpu_loader(&attempts, true);
+
+        let guard = cache
+            .get_or_insert_with_fallback("base", true, &load)
+            .unwrap();
+        assert!(!guard.use_gpu);
+        assert!(cache.gpu_unavailable());
+        assert_eq!(*notified.lock(), vec!["no CUDA device found"]);
+        assert_eq!(*attempts.lock(), vec![true, false]);
+
+        // The GPU isn't retried, and the CPU model is reused
+        cache
+            .get_or_insert_with_fallback("base", true, &load)
+            .unwrap();
+        cache
+            .get_or_insert_with_fallback("small", true, &load)
+            .unwrap();
+        assert_eq!(*attempts.lock(), vec![true, false, false]);
+        assert_eq!(notified.lock().len(), 1);
+
+        // Until settings change
+        cache.reset_gpu_fallback();
+        cache
+            .get_or_insert_with_fallback("base", true, &load)
+            .unwrap();
+        assert_eq!(*attempts.lock(), vec![true, false, false, true]);
+    }
+
+    #[test]
+    fn test_model_failing_on_cpu_too_does_not_disable_gpu() {
+        let cache = fake_cache();
+        let attempts = Mutex::new(Vec::new());
+        let load = broken_gpu_loader(&attempts, false);
+
+        let error = cache
+            .get_or_insert_with_fallback("base", true, &load)
+            .err()
+            .unwrap();
+
+        assert_eq!(
+            error.to_string(),
+            "invalid model file (GPU load failed too: no CUDA device found)"
+        );
+        assert!(!cache.gpu_unavailable());
+        assert!(cache.get_cached_info().is_empty());
+    }
+
+    #[test]
+    fn test_cpu_setting_never_tries_gpu() {
+        let cache = fake_cache();
+        let attempts = Mutex::new(Vec::new());
+
+        cache
+            .get_or_insert_with_fallback("base", false, broken_gpu_loader(&attempts, true))
+            .unwrap();
+
+        assert_eq!(*attempts.lock(), vec![false]);
+        assert!(!cache.gpu_unavailable());
+    }
+
+    #[test]
+    fn test_least_recently_used_picks_oldest() {
+        let now = Instant::now();
+        let entries = [
+            now + Duration::from_secs(30),
+            now,
+            now + Duration::from_secs(10),
+        ];
+        assert_eq!(least_recently_used(entries.into_iter()), Some(1));
+    }
+
+    #[test]
+    fn test_least_recently_used_empty() {
+        assert_eq!(least_recently_used(std::iter::empty()), None);
+    }
+
+    #[test]
+    fn test_is_expired() {
+        assert!(is_expired(Duration::from_secs(301), 300));
+        assert!(!is_expired(Duration::from_secs(299), 300));
+    }
+
+    #[test]
+    fn test_zero_timeout_never_expires() {
+        assert!(!is_expired(Duration::from_secs(24 * 60 * 60), 0));
+    }
+
+    #[test]
+    fn test_set_timeout() {
+        let cache = ModelCache::new();
+        assert_eq!(cache.timeout_secs(), DEFAULT_UNLOAD_TIMEOUT_SECS);
+
+        cache.set_timeout(0);
+        assert_eq!(cache.timeout_secs(), 0);
+        assert!(!cache.cleanup_if_idle());
+    }
+
+    #[test]
+    fn test_timeout_change_applies_to_next_cleanup() {
+        let cache = fake_cache();
+        cache.set_timeout(60);
+        let (guard, dropped) = load_fake(&cache, "base");
+        drop(guard);
+
+        // Idle for 10 seconds: kept with the 60 second timeout
+        make_idle(&cache);
+        assert!(!cache.cleanup_if_idle());
+
+        cache.set_timeout(5);
+        assert!(cache.cleanup_if_idle());
+        assert!(dropped.load(Ordering::SeqCst));
+    }
+
+    #[test]
+    fn test_with_timeout() {
+        let cache = ModelCache::with_timeout(Duration::from_secs(90));
+        assert_eq!(cache.timeout_secs(), 90);
+        assert_eq!(cache.max_models(), DEFAULT_MAX_CACHED_MODELS);
+    }
+
+    #[test]
+    fn test_capacity_is_at_least_one() {
+        let cache = ModelCache::with_capacity(0);
+        assert_eq!(cache.max_models(), 1);
+
+        cache.set_max_models(3);
+        assert_eq!(cache.max_models(), 3);
+    }
+
+    #[test]
+    fn test_new_cache_is_empty() {
+        let cache = ModelCache::new();
+        assert_eq!(cache.max_models(), DEFAULT_MAX_CACHED_MODELS);
+        assert!(cache.get_cached_info().is_empty());
+        assert!(!cache.cleanup_if_idle());
+    }
+}
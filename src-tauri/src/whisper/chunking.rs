@@ -0,0 +1,403 @@
+use crate::audio::wav::SAMPLE_RATE;
+use crate::commands::transcription::calculate_rms;
+use crate::whisper::result::samples_to_ms;
+use std::ops::Range;
+
+/// Longest chunk length accepted in settings, in seconds
+pub const MAX_CHUNK_LENGTH_SECS: u32 = 600;
+
+/// Default overlap between consecutive chunks, in seconds
+pub const DEFAULT_CHUNK_OVERLAP_SECS: u32 = 5;
+
+/// Longest overlap between chunks accepted in settings, in seconds
+pub const MAX_CHUNK_OVERLAP_SECS: u32 = 30;
+
+/// Length of the frames compared when looking for a quiet place to cut (100 ms)
+const CUT_FRAME_SAMPLES: usize = SAMPLE_RATE as usize / 10;
+
+/// Longest run of repeated words looked for where two chunk texts meet
+const MAX_OVERLAP_WORDS: usize = 60;
+
+/// Shorter runs of matching words are treated as coincidence, not overlap
+const MIN_OVERLAP_WORDS: usize = 2;
+
+/// Words at either side of a chunk boundary that may have been cut in half
+/// and therefore don't match their counterpart in the other chunk
+const MAX_CUT_WORDS: usize = 1;
+
+/// A window of the audio transcribed in one Whisper run
+#[derive(Debug, Clone, PartialEq)]
+pub struct Chunk {
+    /// Samples transcribed for this chunk, overlapping the neighbouring chunks
+    pub samples: Range<usize>,
+    /// Segments starting in this part of the audio (in milliseconds) are kept
+    /// from this chunk; the overlap with a neighbour is split in the middle
+    pub owned_ms: Range<u64>,
+}
+
+impl Chunk {
+    /// Start of the chunk in Whisper's centiseconds, to offset its timestamps
+    pub fn offset_centiseconds(&self) -> i64 {
+        (self.samples.start * 100 / SAMPLE_RATE as usize) as i64
+    }
+
+    /// Moves the chunk later in the audio, e.g. when chunks were planned for
+    /// audio with its leading silence trimmed
+    ///
+    /// # Arguments
+    /// * `samples` - Number of samples to move the chunk by
+    pub fn shifted(self, samples: usize) -> Self {
+        let ms = samples_to_ms(samples);
+        // The first chunk keeps owning everything before it, and the last everything after
+        let owned_start = match self.owned_ms.start {
+            0 => 0,
+            start => start + ms,
+        };
+        Self {
+            samples: self.samples.start + samples..self.samples.end + samples,
+            owned_ms: owned_start..self.owned_ms.end.saturating_add(ms),
+        }
+    }
+}
+
+/// Splits audio into overlapping chunks of at most `chunk_samples` samples
+///
+/// Each chunk ends at the quietest 100 ms within the last `overlap_samples`
+/// before its nominal end, so cuts fall into pauses between words where
+/// possible. The next chunk starts `overlap_samples` before that cut.
+///
+/// # Arguments
+/// * `samples` - 16kHz mono samples
+/// * `chunk_samples` - Length of a chunk, 0 to transcribe the audio in one piece
+/// * `overlap_samples` - Audio shared by consecutive chunks; capped below half a
+///   chunk so every chunk moves forward
+///
+/// # Returns
+/// The chunks in order, a single one if the audio fits into one chunk
+pub fn plan_chunks(samples: &[f32], chunk_samples: usize, overlap_samples: usize) -> Vec<Chunk> {
+    if chunk_samples == 0 || samples.len() <= chunk_samples {
+        return vec![Chunk {
+            samples: 0..samples.len(),
+            owned_ms: 0..u64::MAX,
+        }];
+    }
+
+    let overlap_samples = overlap_samples.min((chunk_samples - 1) / 2);
+    let mut ranges = Vec::new();
+    let mut start = 0;
+    loop {
+        let nominal_end = start + chunk_samples;
+        if nominal_end >= samples.len() {
+            ranges.push(start..samples.len());
+            break;
+        }
+        let end = quietest_cut(samples, nominal_end - overlap_samples..nominal_end);
+        ranges.push(start..end);
+        start = end - overlap_samples;
+    }
+
+    let boundaries: Vec<u64> = ranges
+        .windows(2)
+        .map(|pair| samples_to_ms((pair[0].end + pair[1].start) / 2))
+        .collect();
+    ranges
+        .into_iter()
+        .enumerate()
+        .map(|(index, samples)| {
+            let owned_start = if index == 0 { 0 } else { boundaries[index - 1] };
+            let owned_end = boundaries.get(index).copied().unwrap_or(u64::MAX);
+            Chunk {
+                samples,
+                owned_ms: owned_start..owned_end,
+            }
+        })
+        .collect()
+}
+
+/// Finds the middle of the quietest 100 ms frame within a range of samples
+///
+/// # Returns
+/// The sample to cut at, or the end of the range if it is shorter than a frame
+fn quietest_cut(samples: &[f32], range: Range<usize>) -> usize {
+    (range.start..=range.end.saturating_sub(CUT_FRAME_SAMPLES))
+        .step_by(CUT_FRAME_SAMPLES)
+        .map(|start| {
+            let rms = calculate_rms(&samples[start..start + CUT_FRAME_SAMPLES]);
+            (start + CUT_FRAME_SAMPLES / 2, rms)
+        })
+        .min_by(|(_, a), (_, b)| a.total_cmp(b))
+        .map(|(cut, _)| cut)
+        .unwrap_or(range.end)
+}
+
+/// Lowercases a word without its surrounding punctuation, so the same word
+/// matches whether or not Whisper ended a sentence with it
+pub(crate) fn normalize_word(word: &str) -> String {
+    word.trim_matches(|c: char| !c.is_alphanumeric())
+        .to_lowercase()
+}
+
+/// Appends the text of the next chunk, dropping the words both chunks
+/// transcribed from their shared audio
+///
+/// Looks for the longest run of words (ignoring case and punctuation) that
+/// ends `previous` and starts `next`. A word right at either edge may have
+/// been cut in half by the chunk boundary, so one unmatched word may follow
+/// the run in `previous` or precede it in `next`; such a fragment is dropped.
+/// The repeated words are taken from `previous` except for the last one, which
+/// comes from `next`: Whisper tends to end a chunk with a period and start one
+/// with a capital letter, wherever the sentence actually ends. If no run of at
+/// least two words is found, the texts are joined as they are, so nothing is lost.
+///
+/// # Arguments
+/// * `previous` - Text stitched together so far
+/// * `next` - Text of the next chunk
+///
+/// # Returns
+/// The combined text, with words separated by single spaces
+pub fn stitch_texts(previous: &str, next: &str) -> String {
+    let previous_words: Vec<&str> = previous.split_whitespace().collect();
+    let next_words: Vec<&str> = next.split_whitespace().collect();
+
+    let previous_tail = previous_words
+        .len()
+        .saturating_sub(MAX_OVERLAP_WORDS + MAX_CUT_WORDS);
+    let previous_normalized: Vec<String> = previous_words[previous_tail..]
+        .iter()
+        .map(|word| normalize_word(word))
+        .collect();
+    let next_head = next_words.len().min(MAX_OVERLAP_WORDS + MAX_CUT_WORDS);
+    let next_normalized: Vec<String> = next_words[..next_head]
+        .iter()
+        .map(|word| normalize_word(word))
+        .collect();
+
+    // (run length, cut words skipped, end of the run in previous, start of the run in next)
+    let mut best: Option<(usize, usize, usize, usize)> = None;
+    for skip_previous in 0..=MAX_CUT_WORDS.min(previous_normalized.len()) {
+        let previous_end = previous_normalized.len() - skip_previous;
+        for skip_next in 0..=MAX_CUT_WORDS.min(next_normalized.len()) {
+            let longest = previous_end
+                .min(next_normalized.len() - skip_next)
+                .min(MAX_OVERLAP_WORDS);
+            let run = (MIN_OVERLAP_WORDS..=longest).rev().find(|&length| {
+                previous_normalized[previous_end - length..previous_end]
+                    == next_normalized[skip_next..skip_next + length]
+            });
+            if let Some(length) = run {
+                let skipped = skip_previous + skip_next;
+                let better = match best {
+                    Some((best_length, best_skipped, _, _)) => {
+                        length > best_length || (length == best_length && skipped < best_skipped)
+                    }
+                    None => true,
+                };
+                if better {
+                    best = Some((length, skipped, previous_end, skip_next));
+                }
+            }
+        }
+    }
+
+    let (kept_previous, next_start) = match best {
+        Some((length, _, previous_end, skip_next)) => {
+            (previous_tail + previous_end - 1, skip_next + length - 1)
+        }
+        None => (previous_words.len(), 0),
+    };
+    previous_words[..kept_previous]
+        .iter()
+        .chain(&next_words[next_start..])
+        .copied()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SECOND: usize = SAMPLE_RATE as usize;
+
+    /// Audio at a constant level with quiet 100 ms dips starting at the given samples
+    fn audio_with_pauses(seconds: usize, pauses: &[usize]) -> Vec<f32> {
+        let mut samples = vec![0.5; seconds * SECOND];
+        for &pause in pauses {
+            samples[pause..pause + CUT_FRAME_SAMPLES].fill(0.0);
+        }
+        samples
+    }
+
+    #[test]
+    fn test_short_audio_is_a_single_chunk() {
+        let samples = vec![0.5; 10 * SECOND];
+
+        for chunk_samples in [0, 10 * SECOND, 60 * SECOND] {
+            let chunks = plan_chunks(&samples, chunk_samples, 5 * SECOND);
+            assert_eq!(chunks.len(), 1);
+            assert_eq!(chunks[0].samples, 0..samples.len());
+            assert_eq!(chunks[0].owned_ms, 0..u64::MAX);
+        }
+    }
+
+    #[test]
+    fn test_chunks_cover_audio_with_overlap() {
+        let samples = vec![0.5; 150 * SECOND];
+        let chunks = plan_chunks(&samples, 60 * SECOND, 5 * SECOND);
+
+        assert_eq!(chunks.first().unwrap().samples.start, 0);
+        assert_eq!(chunks.last().unwrap().samples.end, samples.len());
+        for pair in chunks.windows(2) {
+            assert_eq!(pair[0].samples.end - pair[1].samples.start, 5 * SECOND);
+            assert!(pair[0].samples.len() <= 60 * SECOND);
+            assert!(pair[0].samples.len() > 50 * SECOND);
+            // Ownership hands over in the middle of the overlap
+            assert_eq!(pair[0].owned_ms.end, pair[1].owned_ms.start);
+            assert!(pair[0].owned_ms.end > samples_to_ms(pair[1].samples.start));
+            assert!(pair[0].owned_ms.end < samples_to_ms(pair[0].samples.end));
+        }
+        assert_eq!(chunks.first().unwrap().owned_ms.start, 0);
+        assert_eq!(chunks.last().unwrap().owned_ms.end, u64::MAX);
+    }
+
+    #[test]
+    fn test_chunks_are_cut_in_pauses() {
+        // A pause 3 s before the nominal end of each of the first two chunks
+        let first_pause = 57 * SECOND;
+        let first_cut = first_pause + CUT_FRAME_SAMPLES / 2;
+        let second_pause = first_cut - 5 * SECOND + 57 * SECOND;
+        let samples = audio_with_pauses(150, &[first_pause, second_pause]);
+        let chunks = plan_chunks(&samples, 60 * SECOND, 5 * SECOND);
+
+        assert_eq!(chunks[0].samples.end, first_cut);
+        assert_eq!(chunks[1].samples.start, first_cut - 5 * SECOND);
+        assert_eq!(chunks[1].samples.end, second_pause + CUT_FRAME_SAMPLES / 2);
+    }
+
+    #[test]
+    fn test_overlap_of_half_a_chunk_still_advances() {
+        let samples = vec![0.5; 100 * SECOND];
+        let chunks = plan_chunks(&samples, 10 * SECOND, 10 * SECOND);
+
+        assert_eq!(chunks.last().unwrap().samples.end, samples.len());
+        for pair in chunks.windows(2) {
+            assert!(pair[1].samples.start > pair[0].samples.start);
+        }
+    }
+
+    #[test]
+    fn test_without_overlap_chunks_meet_exactly() {
+        let samples = vec![0.5; 25 * SECOND];
+        let chunks = plan_chunks(&samples, 10 * SECOND, 0);
+
+        let ranges: Vec<_> = chunks.iter().map(|chunk| chunk.samples.clone()).collect();
+        assert_eq!(
+            ranges,
+            vec![
+                0..10 * SECOND,
+                10 * SECOND..20 * SECOND,
+                20 * SECOND..25 * SECOND
+            ]
+        );
+        assert_eq!(chunks[1].offset_centiseconds(), 1000);
+    }
+
+    #[test]
+    fn test_shifted_chunks_keep_owning_the_edges() {
+        let samples = vec![0.5; 25 * SECOND];
+        let chunks: Vec<Chunk> = plan_chunks(&samples, 10 * SECOND, 0)
+            .into_iter()
+            .map(|chunk| chunk.shifted(2 * SECOND))
+            .collect();
+
+        assert_eq!(chunks[0].samples, 2 * SECOND..12 * SECOND);
+        assert_eq!(chunks[0].owned_ms, 0..12_000);
+        assert_eq!(chunks[1].owned_ms, 12_000..22_000);
+        assert_eq!(chunks[1].offset_centiseconds(), 1200);
+        assert_eq!(chunks[2].owned_ms, 22_000..u64::MAX);
+    }
+
+    #[test]
+    fn test_stitch_removes_repeated_words() {
+        assert_eq!(
+            stitch_texts(
+                "We should ship the release on Friday after",
+                "on Friday after the final review."
+            ),
+            "We should ship the release on Friday after the final review."
+        );
+    }
+
+    #[test]
+    fn test_stitch_ignores_case_and_punctuation() {
+        assert_eq!(
+            stitch_texts("Let's call it a day.", "it a day. Tomorrow we continue."),
+            "Let's call it a day. Tomorrow we continue."
+        );
+        assert_eq!(
+            stitch_texts("the build is green", "Is green, so merge it."),
+            "the build is green, so merge it."
+        );
+        // The period Whisper put at the end of the chunk is not kept
+        assert_eq!(
+            stitch_texts("we meet on Friday after.", "Friday after the review."),
+            "we meet on Friday after the review."
+        );
+    }
+
+    #[test]
+    fn test_stitch_drops_words_cut_at_the_boundary() {
+        // The previous chunk ends in a fragment of "review"
+        assert_eq!(
+            stitch_texts("after the final re", "the final review we ship."),
+            "after the final review we ship."
+        );
+        // The next chunk starts with the tail of "Friday"
+        assert_eq!(
+            stitch_texts("ship it on Friday after lunch", "day after lunch we rest."),
+            "ship it on Friday after lunch we rest."
+        );
+    }
+
+    #[test]
+    fn test_stitch_without_overlap_keeps_every_word() {
+        assert_eq!(
+            stitch_texts("Buy milk.", "Call mom."),
+            "Buy milk. Call mom."
+        );
+        // A single shared word is not enough to count as overlap
+        assert_eq!(
+            stitch_texts("I said no", "no way this works"),
+            "I said no no way this works"
+        );
+    }
+
+    #[test]
+    fn test_stitch_prefers_longest_overlap() {
+        assert_eq!(
+            stitch_texts("one two three one two", "one two three one two four"),
+            "one two three one two four"
+        );
+        assert_eq!(stitch_texts("a b a b", "a b a b c"), "a b a b c");
+    }
+
+    #[test]
+    fn test_stitch_with_empty_texts() {
+        assert_eq!(stitch_texts("", "Hello there."), "Hello there.");
+        assert_eq!(stitch_texts("Hello there.", ""), "Hello there.");
+        assert_eq!(stitch_texts("", ""), "");
+        assert_eq!(stitch_texts("  Hello   there. ", " "), "Hello there.");
+    }
+
+    #[test]
+    fn test_stitch_when_next_repeats_everything() {
+        assert_eq!(
+            stitch_texts("the final review", "the final review"),
+            "the final review"
+        );
+        assert_eq!(
+            stitch_texts("so the final review", "the final review"),
+            "so the final review"
+        );
+    }
+}
@@ -0,0 +1,166 @@
+use crate::whisper::result::Segment;
+
+/// Default no-speech probability above which a segment is dropped
+///
+/// Higher than Whisper's own 0.6, so only segments Whisper is quite sure
+/// contain no speech are removed.
+pub const DEFAULT_NO_SPEECH_THRESHOLD: f32 = 0.8;
+
+/// Phrases Whisper commonly makes up for silence, music or noise
+pub const DEFAULT_HALLUCINATION_BLOCKLIST: &[&str] = &[
+    "Thanks for watching!",
+    "Thank you for watching.",
+    "Please subscribe.",
+    "Subtitles by the Amara.org community",
+    "[BLANK_AUDIO]",
+    "[Music]",
+    "(music)",
+    "[Silence]",
+];
+
+/// Lowercases text and strips surrounding whitespace and trailing
+/// punctuation, so "Thanks for watching" matches "thanks for watching!"
+fn normalize(text: &str) -> String {
+    text.trim()
+        .trim_end_matches(['.', '!', '?', '…'])
+        .trim_end()
+        .to_lowercase()
+}
+
+/// Drops segments that are most likely not speech: ones Whisper itself
+/// rates as probably silent, and known hallucinated boilerplate
+#[derive(Debug, Clone)]
+pub struct SegmentFilter {
+    /// Segments with a higher no-speech probability are dropped
+    no_speech_threshold: f32,
+    /// Normalized phrases dropped when they make up a whole segment
+    blocklist: Vec<String>,
+}
+
+impl SegmentFilter {
+    /// Creates a filter
+    ///
+    /// # Arguments
+    /// * `no_speech_threshold` - No-speech probability (0.0 - 1.0) above which a segment is dropped
+    /// * `blocklist` - Phrases dropped when a segment consists of nothing else;
+    ///   matched ignoring case and trailing punctuation
+    pub fn new(no_speech_threshold: f32, blocklist: &[String]) -> Self {
+        Self {
+            no_speech_threshold,
+            blocklist: blocklist
+                .iter()
+                .map(|phrase| normalize(phrase))
+                .filter(|phrase| !phrase.is_empty())
+                .collect(),
+        }
+    }
+
+    /// Returns true if a segment should be kept
+    ///
+    /// # Arguments
+    /// * `text` - Segment text
+    /// * `no_speech_probability` - Whisper's probability that the segment has no speech
+    pub fn keep(&self, text: &str, no_speech_probability: f32) -> bool {
+        if no_speech_probability > self.no_speech_threshold {
+            log::info!(
+                "Dropping segment without speech ({:.2}): {}",
+                no_speech_probability,
+                text
+            );
+            return false;
+        }
+        if self.blocklist.contains(&normalize(text)) {
+            log::info!("Dropping hallucinated segment: {}", text);
+            return false;
+        }
+        true
+    }
+
+    /// Keeps the segments that pass the filter
+    ///
+    /// # Arguments
+    /// * `segments` - Segments with their no-speech probabilities
+    ///
+    /// # Returns
+    /// The kept segments, still paired with their no-speech probabilities
+    pub fn apply(&self, segments: Vec<(Segment, f32)>) -> Vec<(Segment, f32)> {
+        segments
+            .into_iter()
+            .filter(|(segment, no_speech_probability)| {
+                self.keep(&segment.text, *no_speech_probability)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn default_filter() -> SegmentFilter {
+        let blocklist: Vec<String> = DEFAULT_HALLUCINATION_BLOCKLIST
+            .iter()
+            .map(|phrase| phrase.to_string())
+            .collect();
+        SegmentFilter::new(DEFAULT_NO_SPEECH_THRESHOLD, &blocklist)
+    }
+
+    fn segments(texts: &[(&str, f32)]) -> Vec<(Segment, f32)> {
+        texts
+            .iter()
+            .enumerate()
+            .map(|(i, (text, no_speech))| {
+                let start = i as i64 * 100;
+                (Segment::from_whisper(start, start + 100, text), *no_speech)
+            })
+            .collect()
+    }
+
+    fn texts(segments: &[(Segment, f32)]) -> Vec<&str> {
+        segments
+            .iter()
+            .map(|(segment, _)| segment.text.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn test_drops_blocklisted_boilerplate() {
+        let kept = default_filter().apply(segments(&[
+            ("Buy milk.", 0.1),
+            (" Thanks for watching! ", 0.2),
+            ("[Music]", 0.3),
+            ("please subscribe", 0.1),
+        ]));
+
+        assert_eq!(texts(&kept), vec!["Buy milk."]);
+    }
+
+    #[test]
+    fn test_blocklisted_phrase_inside_speech_is_kept() {
+        let kept = default_filter().apply(segments(&[
+            ("Thanks for watching the kids tonight.", 0.1),
+            ("Please subscribe to the newsletter.", 0.1),
+        ]));
+
+        assert_eq!(kept.len(), 2);
+    }
+
+    #[test]
+    fn test_drops_segments_above_no_speech_threshold() {
+        let kept = default_filter().apply(segments(&[
+            ("Call mom.", 0.5),
+            ("you", 0.95),
+            ("Book flights.", DEFAULT_NO_SPEECH_THRESHOLD),
+        ]));
+
+        assert_eq!(texts(&kept), vec!["Call mom.", "Book flights."]);
+    }
+
+    #[test]
+    fn test_empty_blocklist_and_max_threshold_keep_everything() {
+        let filter = SegmentFilter::new(1.0, &[String::new()]);
+        let kept = filter.apply(segments(&[("Thanks for watching!", 1.0), ("[Music]", 0.0)]));
+
+        assert_eq!(kept.len(), 2);
+    }
+}
@@ -0,0 +1,127 @@
+use anyhow::{anyhow, Result};
+
+/// Language setting value that lets Whisper detect the spoken language
+pub const AUTO_DETECT_LANGUAGE: &str = "auto";
+
+/// Resolves a language setting into the value passed to `FullParams::set_language`
+///
+/// # Arguments
+/// * `code` - Language code from settings (e.g., "en", "de") or `"auto"`
+///
+/// # Returns
+/// * `Ok(Some(code))` with the normalized code for a language Whisper supports
+/// * `Ok(None)` for `"auto"` or an empty value, so Whisper auto-detects
+/// * `Err` if the code is not one of Whisper's supported languages
+pub fn resolve_language(code: &str) -> Result<Option<String>> {
+    let code = code.trim().to_lowercase();
+
+    if code.is_empty() || code == AUTO_DETECT_LANGUAGE {
+        return Ok(None);
+    }
+
+    // whisper_lang_id panics on interior NUL bytes, so reject them up front
+    if code.contains('\0') || whisper_rs::get_lang_id(&code).is_none() {
+        return Err(anyhow!(
+            "Unsupported language code '{}'. Use a Whisper language code such as \"en\" or \"de\", or \"{}\" to detect it automatically",
+            code,
+            AUTO_DETECT_LANGUAGE
+        ));
+    }
+
+    Ok(Some(code))
+}
+
+/// Converts a Whisper language id into its language code
+///
+/// # Arguments
+/// * `lang_id` - Language id as returned by Whisper's language detection
+///
+/// # Returns
+/// * `Some(code)` (e.g., "de") for a known id
+/// * `None` if the id is out of range
+pub fn language_code(lang_id: i32) -> Option<String> {
+    if lang_id < 0 {
+        return None;
+    }
+    whisper_rs::get_lang_str(lang_id).map(str::to_string)
+}
+
+/// Lists every language Whisper supports
+///
+/// # Returns
+/// `(code, name)` pairs ordered by language id, e.g. `("de", "German")`
+pub fn supported_languages() -> Vec<(String, String)> {
+    (0..=whisper_rs::get_lang_max_id())
+        .filter_map(|id| {
+            let code = whisper_rs::get_lang_str(id)?;
+            let name = whisper_rs::get_lang_str_full(id).unwrap_or(code);
+            Some((code.to_string(), capitalize(name)))
+        })
+        .collect()
+}
+
+/// Uppercases the first character of a language name ("german" -> "German")
+fn capitalize(name: &str) -> String {
+    let mut chars = name.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::settings::Settings;
+
+    #[test]
+    fn test_configured_language_is_used() {
+        let settings = Settings {
+            language: "de".to_string(),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            resolve_language(&settings.language).unwrap(),
+            Some("de".to_string())
+        );
+    }
+
+    #[test]
+    fn test_auto_detects_language() {
+        assert_eq!(resolve_language(AUTO_DETECT_LANGUAGE).unwrap(), None);
+        assert_eq!(resolve_language("").unwrap(), None);
+    }
+
+    #[test]
+    fn test_normalizes_code() {
+        assert_eq!(resolve_language(" FR ").unwrap(), Some("fr".to_string()));
+    }
+
+    #[test]
+    fn test_language_code_round_trip() {
+        let id = whisper_rs::get_lang_id("de").unwrap();
+        assert_eq!(language_code(id), Some("de".to_string()));
+        assert_eq!(language_code(-1), None);
+    }
+
+    #[test]
+    fn test_rejects_unknown_code() {
+        let err = resolve_language("klingon").unwrap_err();
+        assert!(err.to_string().contains("klingon"));
+        assert!(resolve_language("e\0n").is_err());
+    }
+
+    #[test]
+    fn test_supported_languages_resolve() {
+        let languages = supported_languages();
+
+        assert!(languages.contains(&("en".to_string(), "English".to_string())));
+        for (code, _) in &languages {
+            assert_eq!(
+                resolve_language(code).unwrap().as_deref(),
+                Some(code.as_str())
+            );
+        }
+    }
+}
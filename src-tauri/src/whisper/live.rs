@@ -0,0 +1,182 @@
+use crate::whisper::chunking::normalize_word;
+
+/// New audio recorded between two live transcription passes, in seconds
+pub const LIVE_INTERVAL_SECS: u32 = 5;
+
+/// Length of the most recent audio transcribed by a live pass, in seconds
+pub const LIVE_WINDOW_SECS: u32 = 30;
+
+/// Words at the end of the live text searched for the start of a new pass
+const MAX_SEARCH_WORDS: usize = 200;
+
+/// Shorter runs of matching words are treated as coincidence, not overlap
+const MIN_MATCH_WORDS: usize = 2;
+
+/// Words at the start of a pass that may have been cut in half by the window
+/// edge and therefore don't match the live text
+const MAX_CUT_WORDS: usize = 1;
+
+/// Merges the text of a new live pass into the live text shown so far
+///
+/// Every pass transcribes the most recent audio again, so its text repeats
+/// the end of the live text, usually in a better version since Whisper now
+/// hears what followed. The pass therefore replaces the live text from where
+/// its first words (ignoring case and punctuation, and possibly a word cut in
+/// half at the window edge) are found among the last words shown. A pass
+/// covering the recording from its start replaces the live text entirely; one
+/// whose start can't be found is appended, so no words are lost.
+///
+/// # Arguments
+/// * `live_text` - Live text shown so far
+/// * `pass_text` - Text of the new pass
+/// * `from_start` - Whether the pass covered the recording from its start
+///
+/// # Returns
+/// The new live text, with words separated by single spaces
+pub fn merge_live_text(live_text: &str, pass_text: &str, from_start: bool) -> String {
+    let pass_words: Vec<&str> = pass_text.split_whitespace().collect();
+    if from_start {
+        return pass_words.join(" ");
+    }
+
+    let live_words: Vec<&str> = live_text.split_whitespace().collect();
+    let search_start = live_words.len().saturating_sub(MAX_SEARCH_WORDS);
+    let live_normalized: Vec<String> = live_words[search_start..]
+        .iter()
+        .map(|word| normalize_word(word))
+        .collect();
+    let pass_normalized: Vec<String> = pass_words.iter().map(|word| normalize_word(word)).collect();
+
+    // (matching words, start in the live text, start in the pass)
+    let mut best: Option<(usize, usize, usize)> = None;
+    for skip in 0..=MAX_CUT_WORDS.min(pass_normalized.len()) {
+        for start in 0..live_normalized.len() {
+            let length = live_normalized[start..]
+                .iter()
+                .zip(&pass_normalized[skip..])
+                .take_while(|(live, pass)| live == pass)
+                .count();
+            // Later starts win ties: the pass covers the end of the recording
+            let better = match best {
+                Some((best_length, _, _)) => length >= best_length,
+                None => true,
+            };
+            if length >= MIN_MATCH_WORDS && better {
+                best = Some((length, start, skip));
+            }
+        }
+    }
+
+    let (kept_live, pass_start) = match best {
+        Some((_, start, skip)) => (search_start + start, skip),
+        None => (live_words.len(), 0),
+    };
+    live_words[..kept_live]
+        .iter()
+        .chain(&pass_words[pass_start..])
+        .copied()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pass_from_start_replaces_live_text() {
+        assert_eq!(merge_live_text("", "Hello", true), "Hello");
+        assert_eq!(
+            merge_live_text("Hello", "Hello there, how are you?", true),
+            "Hello there, how are you?"
+        );
+        // Whisper may revise earlier words once it hears more
+        assert_eq!(
+            merge_live_text("Hello their", "Hello there, friend.", true),
+            "Hello there, friend."
+        );
+    }
+
+    #[test]
+    fn test_sliding_pass_replaces_repeated_tail() {
+        assert_eq!(
+            merge_live_text(
+                "We should ship the release on Friday after.",
+                "release on Friday after the final review",
+                false
+            ),
+            "We should ship the release on Friday after the final review"
+        );
+    }
+
+    #[test]
+    fn test_sliding_pass_revises_the_last_words() {
+        // The previous pass misheard the end; the new one knows better
+        assert_eq!(
+            merge_live_text(
+                "the build is green so merge it",
+                "build is green, so merge it now.",
+                false
+            ),
+            "the build is green, so merge it now."
+        );
+        assert_eq!(
+            merge_live_text(
+                "call mom about the week",
+                "Mom about the weekend plans",
+                false
+            ),
+            "call Mom about the weekend plans"
+        );
+    }
+
+    #[test]
+    fn test_word_cut_at_window_edge_is_dropped() {
+        assert_eq!(
+            merge_live_text(
+                "ship it on Friday after lunch",
+                "day after lunch we rest",
+                false
+            ),
+            "ship it on Friday after lunch we rest"
+        );
+    }
+
+    #[test]
+    fn test_repeated_phrase_matches_latest_occurrence() {
+        assert_eq!(
+            merge_live_text("one two three one two three", "one two three four", false),
+            "one two three one two three four"
+        );
+    }
+
+    #[test]
+    fn test_unmatched_pass_is_appended() {
+        assert_eq!(
+            merge_live_text("Buy milk.", "Call mom.", false),
+            "Buy milk. Call mom."
+        );
+        // A single shared word is not enough to count as overlap
+        assert_eq!(
+            merge_live_text("I said no", "no way this works", false),
+            "I said no no way this works"
+        );
+    }
+
+    #[test]
+    fn test_empty_texts() {
+        assert_eq!(merge_live_text("", "Hello there.", false), "Hello there.");
+        assert_eq!(merge_live_text("Hello there.", "", false), "Hello there.");
+        assert_eq!(merge_live_text("Hello there.", "", true), "");
+        assert_eq!(merge_live_text("", "", false), "");
+    }
+
+    #[test]
+    fn test_repeated_identical_passes_are_stable() {
+        let mut live = String::new();
+        for _ in 0..3 {
+            live = merge_live_text(&live, "so the final review is done", false);
+        }
+        assert_eq!(live, "so the final review is done");
+    }
+}
@@ -4,5 +4,32 @@ pub mod context;
 /// Audio transcription using Whisper
 pub mod transcriber;
 
+/// Structured transcription results with segment timestamps
+pub mod result;
+
+/// SRT and WebVTT rendering of transcription segments
+pub mod subtitles;
+
+/// Language code validation for transcription
+pub mod language;
+
+/// Mapping of the sampling settings to Whisper's sampling strategy
+pub mod sampling;
+
 /// Model caching with automatic unloading
 pub mod cache;
+
+/// Filler-word removal and find/replace rules applied to transcribed text
+pub mod postprocess;
+
+/// Queue running one transcription at a time
+pub mod queue;
+
+/// Filtering of segments without speech and hallucinated boilerplate
+pub mod hallucination;
+
+/// Splitting long recordings into overlapping chunks and stitching their text
+pub mod chunking;
+
+/// Merging of live transcription passes shown while recording
+pub mod live;
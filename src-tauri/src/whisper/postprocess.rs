@@ -0,0 +1,306 @@
+use crate::commands::settings::{ReplacementRule, Settings};
+use anyhow::{bail, Context, Result};
+use regex::Regex;
+
+/// Filler words removed from English transcriptions, also used for languages
+/// without their own list since Whisper often spells hesitations this way
+const ENGLISH_FILLERS: &[&str] = &["um", "umm", "uh", "uhh", "uhm", "er", "erm", "hmm", "mm"];
+
+/// Filler words by Whisper language code
+const FILLERS: &[(&str, &[&str])] = &[
+    ("en", ENGLISH_FILLERS),
+    ("de", &["äh", "ähm", "öh", "öhm", "ehm", "hm", "hmm"]),
+    ("fr", &["euh", "heu", "hum", "hmm"]),
+    ("es", &["eh", "em", "ehm", "mmm"]),
+    ("it", &["eh", "ehm", "mmm"]),
+    ("nl", &["eh", "ehm", "uh", "uhm", "hm"]),
+];
+
+/// Filler words of a language, falling back to the English list
+pub fn filler_words(language: Option<&str>) -> &'static [&'static str] {
+    language
+        .and_then(|language| {
+            FILLERS
+                .iter()
+                .find(|(code, _)| *code == language)
+                .map(|(_, words)| *words)
+        })
+        .unwrap_or(ENGLISH_FILLERS)
+}
+
+/// How a replacement rule finds its matches
+#[derive(Debug)]
+enum Matcher {
+    /// Exact, case-sensitive text
+    Plain(String),
+    Regex(Regex),
+}
+
+/// A replacement rule ready to be applied
+#[derive(Debug)]
+pub struct CompiledRule {
+    matcher: Matcher,
+    replacement: String,
+}
+
+impl CompiledRule {
+    /// Compiles a rule from settings
+    ///
+    /// # Returns
+    /// * `Ok(CompiledRule)` if the rule can be applied
+    /// * `Err` if the pattern is empty or not a valid regular expression
+    pub fn new(rule: &ReplacementRule) -> Result<Self> {
+        if rule.pattern.is_empty() {
+            bail!("The pattern is empty");
+        }
+
+        let matcher = if rule.is_regex {
+            let regex = Regex::new(&rule.pattern)
+                .with_context(|| format!("'{}' is not a valid regular expression", rule.pattern))?;
+            Matcher::Regex(regex)
+        } else {
+            Matcher::Plain(rule.pattern.clone())
+        };
+
+        Ok(Self {
+            matcher,
+            replacement: rule.replacement.clone(),
+        })
+    }
+
+    /// Replaces every match in the text; regex replacements can use `$1` or `${name}`
+    fn apply(&self, text: &str) -> String {
+        match &self.matcher {
+            Matcher::Plain(pattern) => text.replace(pattern.as_str(), &self.replacement),
+            Matcher::Regex(regex) => regex
+                .replace_all(text, self.replacement.as_str())
+                .into_owned(),
+        }
+    }
+}
+
+/// Clean-up applied to the text of a finished transcription:
+/// filler-word removal, then the find/replace rules in order
+#[derive(Debug, Default)]
+pub struct TextPipeline {
+    /// Filler words to remove, `None` if removal is disabled
+    fillers: Option<&'static [&'static str]>,
+    /// Rules applied one after another, each to the output of the previous one
+    rules: Vec<CompiledRule>,
+}
+
+impl TextPipeline {
+    /// Builds the pipeline from settings
+    ///
+    /// Rules are validated when settings are saved, so an invalid rule can only
+    /// come from a hand-edited file; it is skipped with a warning.
+    ///
+    /// # Arguments
+    /// * `settings` - Settings with the filler-word option and replacement rules
+    /// * `language` - Language of the transcribed text, selecting the filler words
+    pub fn from_settings(settings: &Settings, language: Option<&str>) -> Self {
+        let rules = settings
+            .replacements
+            .iter()
+            .filter_map(|rule| match CompiledRule::new(rule) {
+                Ok(compiled) => Some(compiled),
+                Err(e) => {
+                    log::warn!("Skipping replacement rule: {:#}", e);
+                    None
+                }
+            })
+            .collect();
+
+        Self {
+            fillers: settings.remove_filler_words.then(|| filler_words(language)),
+            rules,
+        }
+    }
+
+    /// Returns true if the pipeline changes text at all
+    pub fn is_enabled(&self) -> bool {
+        self.fillers.is_some() || !self.rules.is_empty()
+    }
+
+    /// Runs the text through the pipeline
+    ///
+    /// # Returns
+    /// The cleaned-up text, or the text unchanged if the pipeline is disabled
+    pub fn apply(&self, text: &str) -> String {
+        let mut text = match self.fillers {
+            Some(fillers) => remove_filler_words(text, fillers),
+            None => text.to_string(),
+        };
+        for rule in &self.rules {
+            text = rule.apply(&text);
+        }
+        text
+    }
+}
+
+/// Lowercases a word without its surrounding punctuation, for filler matching
+fn normalize_word(word: &str) -> String {
+    word.trim_matches(|c: char| !c.is_alphanumeric())
+        .to_lowercase()
+}
+
+/// Uppercases the first letter of a word
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// Returns true if the word ends a sentence
+fn ends_sentence(word: &str) -> bool {
+    word.ends_with(['.', '!', '?'])
+}
+
+/// Removes filler words, matched case-insensitively on whole words
+///
+/// Whitespace is normalized to single spaces. A sentence that started with a
+/// filler keeps its capital letter, and a filler ending a sentence leaves its
+/// punctuation on the previous word.
+fn remove_filler_words(text: &str, fillers: &[&str]) -> String {
+    let mut kept: Vec<String> = Vec::new();
+    let mut sentence_start = true;
+    let mut capitalize_next = false;
+
+    for word in text.split_whitespace() {
+        if fillers.contains(&normalize_word(word).as_str()) {
+            if sentence_start && word.starts_with(char::is_uppercase) {
+                capitalize_next = true;
+            }
+            if ends_sentence(word) {
+                if let Some(previous) = kept.last_mut().filter(|previous| !ends_sentence(previous))
+                {
+                    previous.truncate(previous.trim_end_matches([',', ';', ':']).len());
+                    previous.extend(word.chars().rev().take_while(|c| ".!?".contains(*c)));
+                }
+                sentence_start = true;
+            }
+            continue;
+        }
+
+        let word = if capitalize_next {
+            capitalize(word)
+        } else {
+            word.to_string()
+        };
+        capitalize_next = false;
+        sentence_start = ends_sentence(&word);
+        kept.push(word);
+    }
+
+    kept.join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(pattern: &str, replacement: &str, is_regex: bool) -> ReplacementRule {
+        ReplacementRule {
+            pattern: pattern.to_string(),
+            replacement: replacement.to_string(),
+            is_regex,
+        }
+    }
+
+    fn pipeline(remove_filler_words: bool, rules: Vec<ReplacementRule>) -> TextPipeline {
+        let settings = Settings {
+            remove_filler_words,
+            replacements: rules,
+            ..Default::default()
+        };
+        TextPipeline::from_settings(&settings, Some("en"))
+    }
+
+    #[test]
+    fn test_disabled_pipeline_returns_text_unchanged() {
+        let text = "  Um, so   the rust ler build\nis green.";
+        let pipeline = pipeline(false, Vec::new());
+
+        assert!(!pipeline.is_enabled());
+        assert_eq!(pipeline.apply(text), text);
+    }
+
+    #[test]
+    fn test_removes_fillers_and_keeps_capitalization() {
+        let pipeline = pipeline(true, Vec::new());
+
+        assert_eq!(
+            pipeline.apply("Um, so we ship it. Uh, then we uh test it, erm."),
+            "So we ship it. Then we test it."
+        );
+        assert_eq!(
+            pipeline.apply("UM. Hmm? Umbrella summer"),
+            "Umbrella summer"
+        );
+    }
+
+    #[test]
+    fn test_filler_words_depend_on_language() {
+        assert!(filler_words(Some("de")).contains(&"ähm"));
+        assert!(!filler_words(Some("de")).contains(&"um"));
+        assert_eq!(filler_words(Some("ja")), ENGLISH_FILLERS);
+        assert_eq!(filler_words(None), ENGLISH_FILLERS);
+
+        let settings = Settings {
+            remove_filler_words: true,
+            ..Default::default()
+        };
+        let german = TextPipeline::from_settings(&settings, Some("de"));
+        assert_eq!(german.apply("Ähm, das ist äh gut."), "Das ist gut.");
+    }
+
+    #[test]
+    fn test_rules_apply_in_order_to_previous_output() {
+        let pipeline = pipeline(
+            false,
+            vec![
+                rule("rust ler", "Rustler", false),
+                rule("Rustler", "**Rustler**", false),
+            ],
+        );
+
+        assert_eq!(pipeline.apply("the rust ler app"), "the **Rustler** app");
+    }
+
+    #[test]
+    fn test_overlapping_matches_replace_leftmost_first() {
+        let pipeline = pipeline(false, vec![rule("aa", "b", false), rule("b+a", "X", true)]);
+
+        // "aaa" has overlapping matches of "aa"; only the leftmost one is replaced
+        assert_eq!(pipeline.apply("aaa"), "X");
+        assert_eq!(pipeline.apply("aaaa"), "bb");
+    }
+
+    #[test]
+    fn test_plain_rules_are_case_sensitive_regex_can_opt_out() {
+        let plain = pipeline(false, vec![rule("rustler", "Rustler", false)]);
+        assert_eq!(plain.apply("RUSTLER rustler"), "RUSTLER Rustler");
+
+        let insensitive = pipeline(false, vec![rule("(?i)rustler", "Rustler", true)]);
+        assert_eq!(insensitive.apply("RUSTLER rustler"), "Rustler Rustler");
+    }
+
+    #[test]
+    fn test_regex_replacement_uses_capture_groups() {
+        let pipeline = pipeline(false, vec![rule(r"ticket (\d+)", "[TICKET-$1]", true)]);
+
+        assert_eq!(pipeline.apply("see ticket 42"), "see [TICKET-42]");
+    }
+
+    #[test]
+    fn test_invalid_rules_fail_to_compile_and_are_skipped() {
+        assert!(CompiledRule::new(&rule("(unclosed", "", true)).is_err());
+        assert!(CompiledRule::new(&rule("", "x", false)).is_err());
+        assert!(CompiledRule::new(&rule("(unclosed", "", false)).is_ok());
+
+        let pipeline = pipeline(false, vec![rule("[", "", true), rule("teh", "the", false)]);
+        assert_eq!(pipeline.apply("teh end"), "the end");
+    }
+}
@@ -0,0 +1,275 @@
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use tokio::sync::{Semaphore, SemaphorePermit};
+
+/// First-come, first-served queue letting one transcription decode at a time
+///
+/// Two quick hotkey presses would otherwise start two Whisper runs that compete
+/// for the CPU (or GPU) and the model cache. Jobs wait for their turn in the
+/// order they arrived; a job that panics releases its turn while unwinding, so
+/// the queue can't get stuck.
+pub struct TranscriptionQueue {
+    /// Single turn, handed out in the order it was requested
+    turn: Semaphore,
+    /// Jobs running or waiting for their turn
+    jobs: AtomicUsize,
+    /// Bumped by `cancel_waiting`; jobs queued before the bump give up their turn
+    cancel_generation: AtomicU64,
+}
+
+impl Default for TranscriptionQueue {
+    fn default() -> Self {
+        Self {
+            turn: Semaphore::new(1),
+            jobs: AtomicUsize::new(0),
+            cancel_generation: AtomicU64::new(0),
+        }
+    }
+}
+
+/// Counts a job as running or waiting until dropped, including when a job
+/// stops waiting early
+struct QueuedJob<'a> {
+    queue: &'a TranscriptionQueue,
+}
+
+impl Drop for QueuedJob<'_> {
+    fn drop(&mut self) {
+        self.queue.jobs.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+/// A job's turn in the queue; the next job starts when it is dropped
+pub struct QueueTurn<'a> {
+    queue: &'a TranscriptionQueue,
+    /// Cancel generation when the job was queued
+    generation: u64,
+    _permit: SemaphorePermit<'a>,
+    /// Unset for background turns taken with `try_turn`, which aren't counted as jobs
+    _job: Option<QueuedJob<'a>>,
+}
+
+impl QueueTurn<'_> {
+    /// Returns true if `cancel_waiting` was called since the job was queued,
+    /// including after it got its turn
+    pub fn is_cancelled(&self) -> bool {
+        self.queue.cancel_generation.load(Ordering::SeqCst) != self.generation
+    }
+}
+
+impl TranscriptionQueue {
+    /// Waits until it is this job's turn
+    ///
+    /// # Arguments
+    /// * `on_queued` - Called with the number of jobs ahead if the job has to wait
+    ///
+    /// # Returns
+    /// * `Some(QueueTurn)` once every earlier job has finished
+    /// * `None` if waiting jobs were cancelled with `cancel_waiting` meanwhile
+    pub async fn wait_for_turn(&self, on_queued: impl FnOnce(usize)) -> Option<QueueTurn<'_>> {
+        let generation = self.cancel_generation.load(Ordering::SeqCst);
+        let ahead = self.jobs.fetch_add(1, Ordering::SeqCst);
+        let job = QueuedJob { queue: self };
+        if ahead > 0 {
+            log::info!("Transcription queued behind {} job(s)", ahead);
+            on_queued(ahead);
+        }
+
+        // The semaphore is never closed, so acquiring only fails in theory
+        let permit = self.turn.acquire().await.ok()?;
+        let turn = QueueTurn {
+            queue: self,
+            generation,
+            _permit: permit,
+            _job: Some(job),
+        };
+
+        if turn.is_cancelled() {
+            log::info!("Queued transcription was cancelled");
+            return None;
+        }
+        Some(turn)
+    }
+
+    /// Takes the turn without waiting, if no job is running or waiting
+    ///
+    /// For short background work, such as live transcription passes, that
+    /// must not decode alongside a transcription but would rather be skipped
+    /// than delay one. Such turns aren't counted as jobs, so they never show
+    /// up in the queue length.
+    ///
+    /// # Returns
+    /// The turn, or `None` if the queue is busy
+    pub fn try_turn(&self) -> Option<QueueTurn<'_>> {
+        if self.jobs.load(Ordering::SeqCst) > 0 {
+            return None;
+        }
+        let permit = self.turn.try_acquire().ok()?;
+        Some(QueueTurn {
+            queue: self,
+            generation: self.cancel_generation.load(Ordering::SeqCst),
+            _permit: permit,
+            _job: None,
+        })
+    }
+
+    /// Number of jobs waiting for their turn behind the running one
+    pub fn waiting(&self) -> usize {
+        self.jobs.load(Ordering::SeqCst).saturating_sub(1)
+    }
+
+    /// Cancels every job currently waiting for its turn; the running job is
+    /// cancelled separately through its cancellation flag
+    pub fn cancel_waiting(&self) {
+        self.cancel_generation.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use parking_lot::Mutex;
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    /// Submits a job that records when it starts and finishes, yielding in between
+    fn submit(
+        queue: &Arc<TranscriptionQueue>,
+        log: &Arc<Mutex<Vec<String>>>,
+        name: &'static str,
+    ) -> tokio::task::JoinHandle<bool> {
+        let queue = Arc::clone(queue);
+        let log = Arc::clone(log);
+        tokio::spawn(async move {
+            let Some(_turn) = queue
+                .wait_for_turn(|ahead| log.lock().push(format!("{} queued at {}", name, ahead)))
+                .await
+            else {
+                log.lock().push(format!("{} cancelled", name));
+                return false;
+            };
+            log.lock().push(format!("{} started", name));
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            log.lock().push(format!("{} finished", name));
+            true
+        })
+    }
+
+    /// Number of jobs running or waiting
+    fn job_count(queue: &TranscriptionQueue) -> usize {
+        queue.jobs.load(Ordering::SeqCst)
+    }
+
+    /// Waits until `count` jobs are running or queued
+    async fn wait_for_jobs(queue: &TranscriptionQueue, count: usize) {
+        while job_count(queue) < count {
+            tokio::task::yield_now().await;
+        }
+    }
+
+    #[tokio::test]
+    async fn test_three_jobs_run_one_at_a_time_in_order() {
+        let queue = Arc::new(TranscriptionQueue::default());
+        let log = Arc::new(Mutex::new(Vec::new()));
+
+        let mut jobs = Vec::new();
+        for (count, name) in [(1, "first"), (2, "second"), (3, "third")] {
+            jobs.push(submit(&queue, &log, name));
+            wait_for_jobs(&queue, count).await;
+        }
+        assert_eq!(queue.waiting(), 2);
+        for job in jobs {
+            assert!(job.await.unwrap());
+        }
+
+        assert_eq!(
+            *log.lock(),
+            vec![
+                "first started",
+                "second queued at 1",
+                "third queued at 2",
+                "first finished",
+                "second started",
+                "second finished",
+                "third started",
+                "third finished",
+            ]
+        );
+        assert_eq!(job_count(&queue), 0);
+        assert_eq!(queue.waiting(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_cancel_waiting_skips_queued_jobs_only() {
+        let queue = Arc::new(TranscriptionQueue::default());
+        let log = Arc::new(Mutex::new(Vec::new()));
+
+        let running = submit(&queue, &log, "running");
+        wait_for_jobs(&queue, 1).await;
+        let queued = submit(&queue, &log, "queued");
+        wait_for_jobs(&queue, 2).await;
+
+        queue.cancel_waiting();
+        assert!(running.await.unwrap());
+        assert!(!queued.await.unwrap());
+
+        // Jobs submitted after cancelling run normally
+        assert!(submit(&queue, &log, "later").await.unwrap());
+        assert!(log.lock().contains(&"queued cancelled".to_string()));
+        assert_eq!(job_count(&queue), 0);
+    }
+
+    #[tokio::test]
+    async fn test_cancel_after_turn_was_granted_is_seen() {
+        let queue = TranscriptionQueue::default();
+        let turn = queue.wait_for_turn(|_| {}).await.unwrap();
+        assert!(!turn.is_cancelled());
+
+        queue.cancel_waiting();
+        assert!(turn.is_cancelled());
+        drop(turn);
+
+        // Later jobs aren't affected
+        assert!(!queue.wait_for_turn(|_| {}).await.unwrap().is_cancelled());
+    }
+
+    #[tokio::test]
+    async fn test_background_turn_yields_to_jobs() {
+        let queue = Arc::new(TranscriptionQueue::default());
+        let log = Arc::new(Mutex::new(Vec::new()));
+
+        // Nothing running: the background work gets the turn, without counting as a job
+        let background = queue.try_turn();
+        assert!(background.is_some());
+        assert_eq!(job_count(&queue), 0);
+        assert!(queue.try_turn().is_none());
+
+        // A job waits for the background work to finish, but isn't reported as queued
+        let job = submit(&queue, &log, "job");
+        wait_for_jobs(&queue, 1).await;
+        assert!(queue.try_turn().is_none());
+        drop(background);
+        assert!(job.await.unwrap());
+        assert_eq!(*log.lock(), vec!["job started", "job finished"]);
+
+        assert!(queue.try_turn().is_some());
+    }
+
+    #[tokio::test]
+    async fn test_panicking_job_releases_its_turn() {
+        let queue = Arc::new(TranscriptionQueue::default());
+
+        let panicking = {
+            let queue = Arc::clone(&queue);
+            tokio::spawn(async move {
+                let _turn = queue.wait_for_turn(|_| {}).await;
+                panic!("whisper crashed");
+            })
+        };
+        assert!(panicking.await.is_err());
+
+        let turn = tokio::time::timeout(Duration::from_secs(5), queue.wait_for_turn(|_| {}))
+            .await
+            .expect("queue deadlocked after a panic");
+        assert!(turn.is_some());
+    }
+}
@@ -0,0 +1,334 @@
+use serde::{Deserialize, Serialize};
+
+/// Sample rate of the audio passed to Whisper
+const SAMPLE_RATE: u64 = 16000;
+
+/// A stretch of transcribed speech with its position in the audio
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Segment {
+    /// Start of the segment in milliseconds from the beginning of the audio
+    pub start_ms: u64,
+    /// End of the segment in milliseconds from the beginning of the audio
+    pub end_ms: u64,
+    /// Transcribed text, trimmed
+    pub text: String,
+    /// How sure Whisper was of the text (0.0 - 1.0), if token probabilities were available
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub confidence: Option<f32>,
+}
+
+impl Segment {
+    /// Builds a segment from Whisper's timestamps
+    ///
+    /// # Arguments
+    /// * `start` - Start timestamp in centiseconds, as reported by Whisper
+    /// * `end` - End timestamp in centiseconds, as reported by Whisper
+    /// * `text` - Segment text; surrounding whitespace is removed
+    pub fn from_whisper(start: i64, end: i64, text: &str) -> Self {
+        let start_ms = centiseconds_to_ms(start);
+        Self {
+            start_ms,
+            // Guard against inverted timestamps so consumers can rely on start <= end
+            end_ms: centiseconds_to_ms(end).max(start_ms),
+            text: text.trim().to_string(),
+            confidence: None,
+        }
+    }
+
+    /// Sets the confidence computed from the segment's token probabilities
+    pub fn with_confidence(mut self, confidence: Option<f32>) -> Self {
+        self.confidence = confidence;
+        self
+    }
+}
+
+/// Full result of a transcription
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TranscriptionResult {
+    /// Transcribed text of all segments
+    pub text: String,
+    /// Segments in the order they were spoken
+    pub segments: Vec<Segment>,
+    /// ID of the model that produced the transcription
+    pub model: String,
+    /// Duration of the transcribed audio in milliseconds
+    pub duration_ms: u64,
+    /// Configured language, or the one Whisper detected when set to auto
+    pub language: Option<String>,
+    /// Whether the speech was translated into English
+    pub translated: bool,
+    /// Confidence of the whole transcription (0.0 - 1.0), see `overall_confidence`
+    pub confidence: Option<f32>,
+    /// Number of segments in the transcription
+    #[serde(default)]
+    pub segment_count: u32,
+    /// Mean probability Whisper gave the kept segments of containing no speech
+    /// (0.0 - 1.0); high values hint at noise mistaken for speech
+    #[serde(default)]
+    pub mean_no_speech_probability: Option<f32>,
+}
+
+impl TranscriptionResult {
+    /// Builds a result from its segments, deriving the text from them
+    ///
+    /// # Arguments
+    /// * `segments` - Transcribed segments in spoken order
+    /// * `model` - ID of the model used
+    /// * `sample_count` - Number of 16kHz samples that were transcribed
+    /// * `language` - Language of the transcription, if known
+    /// * `translated` - Whether the speech was translated into English
+    pub fn new(
+        segments: Vec<Segment>,
+        model: String,
+        sample_count: usize,
+        language: Option<String>,
+        translated: bool,
+    ) -> Self {
+        Self {
+            text: join_segments(&segments),
+            confidence: overall_confidence(&segments),
+            segment_count: segments.len() as u32,
+            mean_no_speech_probability: None,
+            segments,
+            model,
+            duration_ms: samples_to_ms(sample_count),
+            language,
+            translated,
+        }
+    }
+
+    /// Sets the mean of the segments' no-speech probabilities
+    ///
+    /// # Arguments
+    /// * `probabilities` - No-speech probability of each segment, as reported by Whisper
+    pub fn with_no_speech_probabilities(mut self, probabilities: &[f32]) -> Self {
+        self.mean_no_speech_probability = mean(probabilities);
+        self
+    }
+}
+
+/// Converts a Whisper timestamp (centiseconds) into milliseconds, clamping negatives to 0
+pub fn centiseconds_to_ms(centiseconds: i64) -> u64 {
+    centiseconds.max(0) as u64 * 10
+}
+
+/// Converts a number of 16kHz samples into milliseconds
+pub fn samples_to_ms(sample_count: usize) -> u64 {
+    sample_count as u64 * 1000 / SAMPLE_RATE
+}
+
+/// Confidence of a segment from the probabilities of its text tokens
+///
+/// Uses the geometric mean of the probabilities, i.e. the exponential of the
+/// average log-probability, so a single very unlikely token pulls the score down
+/// more than an arithmetic mean would.
+///
+/// # Returns
+/// A score between 0.0 and 1.0, or `None` if there are no probabilities
+pub fn token_confidence(probabilities: &[f32]) -> Option<f32> {
+    if probabilities.is_empty() {
+        return None;
+    }
+    // Clamp so a zero probability doesn't turn the log-mean into -infinity
+    let mean_log_probability = probabilities
+        .iter()
+        .map(|&p| f64::from(p.clamp(1e-6, 1.0)).ln())
+        .sum::<f64>()
+        / probabilities.len() as f64;
+    Some(mean_log_probability.exp() as f32)
+}
+
+/// Confidence of a whole transcription: the mean of the segment confidences,
+/// weighted by segment duration so long segments count more than short ones
+///
+/// # Returns
+/// A score between 0.0 and 1.0, or `None` if no segment has a confidence
+pub fn overall_confidence(segments: &[Segment]) -> Option<f32> {
+    let scored: Vec<(f32, u64)> = segments
+        .iter()
+        .filter_map(|segment| {
+            let duration = segment.end_ms.saturating_sub(segment.start_ms);
+            segment.confidence.map(|confidence| (confidence, duration))
+        })
+        .collect();
+    if scored.is_empty() {
+        return None;
+    }
+
+    let total_duration: u64 = scored.iter().map(|(_, duration)| duration).sum();
+    if total_duration == 0 {
+        let sum: f32 = scored.iter().map(|(confidence, _)| confidence).sum();
+        return Some(sum / scored.len() as f32);
+    }
+    let weighted: f64 = scored
+        .iter()
+        .map(|&(confidence, duration)| f64::from(confidence) * duration as f64)
+        .sum();
+    Some((weighted / total_duration as f64) as f32)
+}
+
+/// Arithmetic mean of the values, or `None` if there are none
+fn mean(values: &[f32]) -> Option<f32> {
+    if values.is_empty() {
+        return None;
+    }
+    Some(values.iter().sum::<f32>() / values.len() as f32)
+}
+
+/// Joins segment texts into the transcription text, skipping empty segments
+pub fn join_segments(segments: &[Segment]) -> String {
+    segments
+        .iter()
+        .map(|segment| segment.text.as_str())
+        .filter(|text| !text.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn segment(start_ms: u64, end_ms: u64, text: &str) -> Segment {
+        Segment {
+            start_ms,
+            end_ms,
+            text: text.to_string(),
+            confidence: None,
+        }
+    }
+
+    fn assert_close(actual: Option<f32>, expected: f32) {
+        let actual = actual.expect("confidence");
+        assert!(
+            (actual - expected).abs() < 1e-4,
+            "{} != {}",
+            actual,
+            expected
+        );
+    }
+
+    #[test]
+    fn test_token_confidence_is_geometric_mean() {
+        assert_close(token_confidence(&[0.9, 0.9, 0.9]), 0.9);
+        // sqrt(0.8 * 0.2) = 0.4, below the arithmetic mean of 0.5
+        assert_close(token_confidence(&[0.8, 0.2]), 0.4);
+        assert_eq!(token_confidence(&[]), None);
+    }
+
+    #[test]
+    fn test_token_confidence_handles_zero_probability() {
+        let confidence = token_confidence(&[1.0, 0.0]).unwrap();
+        assert!(confidence.is_finite());
+        assert!(confidence > 0.0 && confidence < 0.01);
+    }
+
+    #[test]
+    fn test_overall_confidence_weights_by_duration() {
+        let segments = vec![
+            segment(0, 3000, "long").with_confidence(Some(0.9)),
+            segment(3000, 4000, "short").with_confidence(Some(0.5)),
+            segment(4000, 9000, "unscored"),
+        ];
+        // (0.9 * 3 + 0.5 * 1) / 4
+        assert_close(overall_confidence(&segments), 0.8);
+
+        let instant = vec![
+            segment(0, 0, "a").with_confidence(Some(0.2)),
+            segment(0, 0, "b").with_confidence(Some(0.6)),
+        ];
+        assert_close(overall_confidence(&instant), 0.4);
+
+        assert_eq!(overall_confidence(&[segment(0, 100, "x")]), None);
+    }
+
+    #[test]
+    fn test_segment_from_whisper_converts_timestamps() {
+        let segment = Segment::from_whisper(150, 420, " Hello there. ");
+
+        assert_eq!(segment, self::segment(1500, 4200, "Hello there."));
+    }
+
+    #[test]
+    fn test_segment_from_whisper_guards_bad_timestamps() {
+        assert_eq!(Segment::from_whisper(-5, 10, "a").start_ms, 0);
+        let inverted = Segment::from_whisper(300, 200, "b");
+        assert_eq!((inverted.start_ms, inverted.end_ms), (3000, 3000));
+    }
+
+    #[test]
+    fn test_join_segments_skips_empty_text() {
+        let segments = vec![
+            segment(0, 1000, "Buy milk."),
+            segment(1000, 1500, ""),
+            segment(1500, 3000, "Call mom."),
+        ];
+
+        assert_eq!(join_segments(&segments), "Buy milk. Call mom.");
+        assert_eq!(join_segments(&[]), "");
+    }
+
+    #[test]
+    fn test_samples_to_ms() {
+        assert_eq!(samples_to_ms(0), 0);
+        assert_eq!(samples_to_ms(16000), 1000);
+        assert_eq!(samples_to_ms(24000), 1500);
+    }
+
+    #[test]
+    fn test_result_serializes_camel_case() {
+        let result = TranscriptionResult::new(
+            vec![segment(0, 1200, "Hello.")],
+            "base".to_string(),
+            32000,
+            Some("en".to_string()),
+            false,
+        );
+
+        let json = serde_json::to_value(&result).unwrap();
+        assert_eq!(json["text"], "Hello.");
+        assert_eq!(json["durationMs"], 2000);
+        assert_eq!(json["segments"][0]["startMs"], 0);
+        assert_eq!(json["segments"][0]["endMs"], 1200);
+        assert_eq!(json["language"], "en");
+        assert_eq!(json["segmentCount"], 1);
+        assert!(json["meanNoSpeechProbability"].is_null());
+    }
+
+    #[test]
+    fn test_result_metadata() {
+        let result = TranscriptionResult::new(
+            vec![
+                segment(0, 1000, "Buy milk."),
+                segment(1000, 2500, "Call mom."),
+            ],
+            "small".to_string(),
+            48000,
+            None,
+            true,
+        )
+        .with_no_speech_probabilities(&[0.1, 0.3]);
+
+        assert_eq!(result.text, "Buy milk. Call mom.");
+        assert_eq!(result.duration_ms, 3000);
+        assert_eq!(result.model, "small");
+        assert_eq!(result.segment_count, 2);
+        assert_close(result.mean_no_speech_probability, 0.2);
+
+        let empty = TranscriptionResult::new(Vec::new(), "base".to_string(), 0, None, false)
+            .with_no_speech_probabilities(&[]);
+        assert_eq!(empty.segment_count, 0);
+        assert_eq!(empty.mean_no_speech_probability, None);
+    }
+
+    #[test]
+    fn test_result_without_metadata_deserializes() {
+        let json = r#"{"text":"Hi","segments":[],"model":"base","durationMs":500,"language":null,"translated":false,"confidence":null}"#;
+        let result: TranscriptionResult = serde_json::from_str(json).unwrap();
+
+        assert_eq!(result.segment_count, 0);
+        assert_eq!(result.mean_no_speech_probability, None);
+    }
+}
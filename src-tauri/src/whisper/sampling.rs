@@ -0,0 +1,60 @@
+use crate::commands::settings::SamplingMode;
+use whisper_rs::SamplingStrategy;
+
+/// Largest beam size accepted in settings; wider beams cost a lot of CPU for little gain
+pub const MAX_BEAM_SIZE: u32 = 8;
+
+/// Default beam size, matching whisper.cpp
+pub const DEFAULT_BEAM_SIZE: u32 = 5;
+
+/// Builds the Whisper sampling strategy for the configured mode
+///
+/// # Arguments
+/// * `mode` - Sampling mode from settings
+/// * `beam_size` - Beam width used for beam search, clamped to `1..=MAX_BEAM_SIZE`
+///
+/// # Returns
+/// Greedy sampling with a single candidate, or beam search with the given width
+pub fn sampling_strategy(mode: SamplingMode, beam_size: u32) -> SamplingStrategy {
+    match mode {
+        SamplingMode::Greedy => SamplingStrategy::Greedy { best_of: 1 },
+        SamplingMode::Beam => SamplingStrategy::BeamSearch {
+            beam_size: beam_size.clamp(1, MAX_BEAM_SIZE) as i32,
+            // Not implemented by whisper.cpp; -1.0 is its default
+            patience: -1.0,
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_greedy_mapping() {
+        assert!(matches!(
+            sampling_strategy(SamplingMode::Greedy, 5),
+            SamplingStrategy::Greedy { best_of: 1 }
+        ));
+    }
+
+    #[test]
+    fn test_beam_mapping() {
+        assert!(matches!(
+            sampling_strategy(SamplingMode::Beam, 3),
+            SamplingStrategy::BeamSearch { beam_size: 3, .. }
+        ));
+    }
+
+    #[test]
+    fn test_beam_size_is_clamped() {
+        assert!(matches!(
+            sampling_strategy(SamplingMode::Beam, 0),
+            SamplingStrategy::BeamSearch { beam_size: 1, .. }
+        ));
+        assert!(matches!(
+            sampling_strategy(SamplingMode::Beam, 100),
+            SamplingStrategy::BeamSearch { beam_size: 8, .. }
+        ));
+    }
+}
@@ -0,0 +1,202 @@
+use super::result::Segment;
+use serde::{Deserialize, Serialize};
+use std::fmt::Write;
+
+/// Subtitle file format
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SubtitleFormat {
+    /// SubRip (`.srt`)
+    Srt,
+    /// WebVTT (`.vtt`)
+    Vtt,
+}
+
+impl SubtitleFormat {
+    /// File extension for the format, without the dot
+    pub fn extension(self) -> &'static str {
+        match self {
+            SubtitleFormat::Srt => "srt",
+            SubtitleFormat::Vtt => "vtt",
+        }
+    }
+
+    /// Separator between seconds and milliseconds in cue timestamps
+    fn millis_separator(self) -> char {
+        match self {
+            SubtitleFormat::Srt => ',',
+            SubtitleFormat::Vtt => '.',
+        }
+    }
+}
+
+/// Formats a position in the audio as a cue timestamp
+///
+/// # Arguments
+/// * `ms` - Position in milliseconds
+/// * `format` - Subtitle format, which decides the milliseconds separator
+///
+/// # Returns
+/// `HH:MM:SS,mmm` for SRT and `HH:MM:SS.mmm` for VTT; hours grow past two digits if needed
+pub fn format_timestamp(ms: u64, format: SubtitleFormat) -> String {
+    let hours = ms / 3_600_000;
+    let minutes = ms / 60_000 % 60;
+    let seconds = ms / 1000 % 60;
+    let millis = ms % 1000;
+    format!(
+        "{:02}:{:02}:{:02}{}{:03}",
+        hours,
+        minutes,
+        seconds,
+        format.millis_separator(),
+        millis
+    )
+}
+
+/// Prepares segment text for use as a cue payload
+///
+/// Blank lines would end the cue early and `-->` would be read as a timing line,
+/// so both are removed. VTT additionally needs `&`, `<` and `>` escaped.
+fn cue_text(text: &str, format: SubtitleFormat) -> String {
+    let text = text
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n")
+        .replace("-->", "->");
+
+    match format {
+        SubtitleFormat::Srt => text,
+        SubtitleFormat::Vtt => text
+            .replace('&', "&amp;")
+            .replace('<', "&lt;")
+            .replace('>', "&gt;"),
+    }
+}
+
+/// Renders segments as a subtitle file
+///
+/// Segments without text are skipped, and cues are numbered from 1 in SRT.
+///
+/// # Arguments
+/// * `segments` - Segments in spoken order
+/// * `format` - Subtitle format to produce
+///
+/// # Returns
+/// The complete file contents
+pub fn render_subtitles(segments: &[Segment], format: SubtitleFormat) -> String {
+    let mut output = String::new();
+    if format == SubtitleFormat::Vtt {
+        output.push_str("WEBVTT\n\n");
+    }
+
+    let cues = segments
+        .iter()
+        .map(|segment| (segment, cue_text(&segment.text, format)))
+        .filter(|(_, text)| !text.is_empty());
+
+    for (index, (segment, text)) in cues.enumerate() {
+        if format == SubtitleFormat::Srt {
+            let _ = writeln!(output, "{}", index + 1);
+        }
+        let _ = writeln!(
+            output,
+            "{} --> {}",
+            format_timestamp(segment.start_ms, format),
+            format_timestamp(segment.end_ms.max(segment.start_ms), format)
+        );
+        let _ = writeln!(output, "{}\n", text);
+    }
+
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixture() -> Vec<Segment> {
+        vec![
+            Segment {
+                start_ms: 0,
+                end_ms: 1500,
+                text: "Hello there.".to_string(),
+                confidence: None,
+            },
+            Segment {
+                start_ms: 1500,
+                end_ms: 1600,
+                text: "  ".to_string(),
+                confidence: None,
+            },
+            Segment {
+                start_ms: 1600,
+                end_ms: 4_210,
+                text: "Fish & chips <3".to_string(),
+                confidence: None,
+            },
+        ]
+    }
+
+    #[test]
+    fn test_format_timestamp() {
+        assert_eq!(format_timestamp(0, SubtitleFormat::Srt), "00:00:00,000");
+        assert_eq!(
+            format_timestamp(3_723_045, SubtitleFormat::Srt),
+            "01:02:03,045"
+        );
+        assert_eq!(
+            format_timestamp(3_723_045, SubtitleFormat::Vtt),
+            "01:02:03.045"
+        );
+        assert_eq!(
+            format_timestamp(360_000_000, SubtitleFormat::Vtt),
+            "100:00:00.000"
+        );
+    }
+
+    #[test]
+    fn test_render_srt() {
+        let srt = render_subtitles(&fixture(), SubtitleFormat::Srt);
+
+        assert_eq!(
+            srt,
+            "1\n00:00:00,000 --> 00:00:01,500\nHello there.\n\n\
+             2\n00:00:01,600 --> 00:00:04,210\nFish & chips <3\n\n"
+        );
+    }
+
+    #[test]
+    fn test_render_vtt() {
+        let vtt = render_subtitles(&fixture(), SubtitleFormat::Vtt);
+
+        assert_eq!(
+            vtt,
+            "WEBVTT\n\n\
+             00:00:00.000 --> 00:00:01.500\nHello there.\n\n\
+             00:00:01.600 --> 00:00:04.210\nFish &amp; chips &lt;3\n\n"
+        );
+    }
+
+    #[test]
+    fn test_cue_text_cannot_break_cue() {
+        assert_eq!(
+            cue_text("first\r\n\r\nsecond --> third", SubtitleFormat::Srt),
+            "first\nsecond -> third"
+        );
+    }
+
+    #[test]
+    fn test_render_empty() {
+        assert_eq!(render_subtitles(&[], SubtitleFormat::Srt), "");
+        assert_eq!(render_subtitles(&[], SubtitleFormat::Vtt), "WEBVTT\n\n");
+    }
+
+    #[test]
+    fn test_format_deserializes_lowercase() {
+        let format: SubtitleFormat = serde_json::from_str("\"vtt\"").unwrap();
+        assert_eq!(format, SubtitleFormat::Vtt);
+        assert_eq!(format.extension(), "vtt");
+    }
+}
@@ -1,3 +1,4 @@
+use super::language::resolve_language;
 use anyhow::Result;
 use std::path::PathBuf;
 use whisper_rs::{FullParams, SamplingStrategy, WhisperContext, WhisperContextParameters};
@@ -36,20 +37,31 @@ impl Transcriber {
     ///
     /// # Arguments
     /// * `audio_data` - Audio samples as f32 values (16kHz, mono)
+    /// * `language` - Language code from settings, or `"auto"` to detect it
+    /// * `translate` - Whether to translate the speech into English
+    /// * `sampling` - Decoding strategy (see `whisper::sampling::sampling_strategy`)
     ///
     /// # Returns
     /// * `Ok(String)` with the transcribed text
-    /// * `Err` if transcription failed
-    pub fn transcribe(&self, audio_data: &[f32]) -> Result<String> {
+    /// * `Err` if the language is unsupported or transcription failed
+    pub fn transcribe(
+        &self,
+        audio_data: &[f32],
+        language: &str,
+        translate: bool,
+        sampling: SamplingStrategy,
+    ) -> Result<String> {
         log::info!("Transcribing {} audio samples", audio_data.len());
 
+        let language = resolve_language(language)?;
+
         // Create transcription parameters
-        let mut params = FullParams::new(SamplingStrategy::Greedy { best_of: 1 });
+        let mut params = FullParams::new(sampling);
 
         // Configure parameters
         params.set_n_threads(4);
-        params.set_translate(false);
-        params.set_language(Some("en"));
+        params.set_translate(translate);
+        params.set_language(language.as_deref());
         params.set_print_special(false);
         params.set_print_progress(false);
         params.set_print_realtime(false);
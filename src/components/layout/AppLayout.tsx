@@ -1,4 +1,5 @@
-import { useState } from "react";
+import { useEffect, useState } from "react";
+import { listen } from "@tauri-apps/api/event";
 import { AppHeader } from "./AppHeader";
 import { TabNavigation, type TabId } from "./TabNavigation";
 import { HistoryTab } from "@/components/tabs/HistoryTab";
@@ -18,6 +19,16 @@ export function AppLayout() {
   // Listen for hotkey events globally (so it works even before visiting Recording tab)
   useHotkeyListener();
 
+  // Switch to the settings tab when "Settings" is chosen from the tray menu
+  useEffect(() => {
+    const unlisten = listen("tray-open-settings", () =>
+      setActiveTab("settings"),
+    );
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, []);
+
   const renderTabContent = () => {
     switch (activeTab) {
       case "history":
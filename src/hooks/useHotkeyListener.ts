@@ -8,12 +8,15 @@ import {
   transcribeAudio,
   getSettings,
   pasteText,
+  getHistory,
+  pasteHistoryEntry,
+  cancelTranscription,
 } from "@/lib/tauri";
 
 /**
  * Global app listener hook.
  * Handles:
- * - Hotkey events (start/stop recording)
+ * - Hotkey events (start/stop recording, paste last, cancel transcription)
  * - Recording status updates from backend
  * - Processing status updates from backend
  * - Transcription completion events
@@ -24,7 +27,7 @@ import {
 export function useHotkeyListener() {
   const { isRecording, setRecording, setProcessing, setTranscription } = useAppStore();
 
-  const { addToHistory } = useHistoryStore();
+  const { loadHistory } = useHistoryStore();
 
   // Track recording state in a ref for the hotkey callback
   const isRecordingRef = useRef(isRecording);
@@ -47,9 +50,9 @@ export function useHotkeyListener() {
       const text = await transcribeAudio(audioPath, settings.model);
       console.log("Transcription result:", text);
 
-      // Save to history
+      // The backend saved it to history; refresh the list
       if (text && text.trim()) {
-        await addToHistory(text, undefined, settings.model);
+        await loadHistory();
       }
 
       // Auto-paste the transcribed text
@@ -117,12 +120,53 @@ export function useHotkeyListener() {
         }
       });
 
+      // Listen for the stop recording hotkey
+      const unlistenStop = await listen("hotkey-stop-recording", async () => {
+        if (!mounted) return;
+        console.log(
+          "Stop hotkey triggered! isRecording:",
+          isRecordingRef.current,
+        );
+        if (isRecordingRef.current) {
+          await handleStopAndTranscribe();
+        }
+      });
+
+      // Listen for the paste last transcription hotkey
+      const unlistenPasteLast = await listen("hotkey-paste-last", async () => {
+        if (!mounted) return;
+        try {
+          const { records } = await getHistory(0, 1);
+          if (records.length > 0) {
+            await pasteHistoryEntry(records[0].id);
+          }
+        } catch (error) {
+          console.error("Failed to paste last transcription:", error);
+        }
+      });
+
+      // Listen for the cancel transcription hotkey
+      const unlistenCancel = await listen(
+        "hotkey-cancel-transcription",
+        async () => {
+          if (!mounted) return;
+          try {
+            await cancelTranscription();
+          } catch (error) {
+            console.error("Failed to cancel transcription:", error);
+          }
+        },
+      );
+
       return () => {
         mounted = false;
         unlistenRecording();
         unlistenProcessing();
         unlistenTranscription();
         unlistenHotkey();
+        unlistenStop();
+        unlistenPasteLast();
+        unlistenCancel();
       };
     };
 
@@ -130,5 +174,5 @@ export function useHotkeyListener() {
     return () => {
       cleanup.then((fn) => fn());
     };
-  }, [setRecording, setProcessing, setTranscription, addToHistory]);
+  }, [setRecording, setProcessing, setTranscription, loadHistory]);
 }
@@ -18,7 +18,7 @@ import {
 export function useRecording() {
   const { isRecording, isProcessing, transcription, setRecording, setProcessing } = useAppStore();
 
-  const { addToHistory } = useHistoryStore();
+  const { loadHistory } = useHistoryStore();
 
   // Manual start recording
   const handleStartRecording = async () => {
@@ -47,9 +47,9 @@ export function useRecording() {
       // Skip blank audio results from Whisper
       const isBlankAudio = !text || !text.trim() || text.includes("[BLANK_AUDIO]");
 
-      // Save to history (skip blank audio)
+      // The backend saved it to history; refresh the list (skip blank audio)
       if (!isBlankAudio) {
-        await addToHistory(text, undefined, settings.model);
+        await loadHistory();
       }
 
       // Auto-paste the transcribed text (skip blank audio)
@@ -19,7 +19,7 @@ export type RecordingState = "idle" | "recording" | "processing" | "done";
 export function useRecordingState() {
   const [state, setState] = useState<RecordingState>("idle");
   const [transcription, setTranscription] = useState<string | null>(null);
-  const { addToHistory } = useHistoryStore();
+  const { loadHistory } = useHistoryStore();
 
   // Listen to backend events
   useEffect(() => {
@@ -106,9 +106,9 @@ export function useRecordingState() {
       // Skip blank audio results from Whisper
       const isBlankAudio = !text || !text.trim() || text.includes("[BLANK_AUDIO]");
 
-      // Save to history (skip blank audio)
+      // The backend saved it to history; refresh the list (skip blank audio)
       if (!isBlankAudio) {
-        await addToHistory(text, undefined, settings.model);
+        await loadHistory();
       }
 
       // Auto-paste the transcribed text (skip blank audio)
@@ -123,7 +123,7 @@ export function useRecordingState() {
       console.error("Failed to stop/transcribe:", error);
       setState("idle");
     }
-  }, [addToHistory]);
+  }, [loadHistory]);
 
   // Toggle recording
   const toggleRecording = useCallback(async () => {
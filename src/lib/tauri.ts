@@ -37,6 +37,12 @@ export interface TranscriptionRecord {
   model?: string;
 }
 
+export interface PaginatedHistory {
+  records: TranscriptionRecord[];
+  total: number;
+  hasMore: boolean;
+}
+
 // Commands
 export async function startRecording(): Promise<void> {
   return invoke("start_recording");
@@ -82,8 +88,11 @@ export async function resetWaylandHotkey(shortcut: string): Promise<string | nul
   return invoke("reset_wayland_hotkey", { shortcut });
 }
 
-export async function pasteText(text: string): Promise<void> {
-  return invoke("paste_text", { text });
+export async function pasteText(
+  text: string,
+  language?: string,
+): Promise<void> {
+  return invoke("paste_text", { text, language });
 }
 
 // Event listeners
@@ -126,8 +135,11 @@ export async function unregisterHotkeys(): Promise<void> {
 }
 
 // History commands
-export async function getHistory(): Promise<TranscriptionRecord[]> {
-  return invoke("get_history");
+export async function getHistory(
+  offset?: number,
+  limit?: number,
+): Promise<PaginatedHistory> {
+  return invoke("get_history", { offset, limit });
 }
 
 export async function addHistory(
@@ -146,5 +158,13 @@ export async function clearHistory(): Promise<void> {
   return invoke("clear_history");
 }
 
+export async function pasteHistoryEntry(id: string): Promise<void> {
+  return invoke("paste_history_entry", { id });
+}
+
+export async function cancelTranscription(): Promise<void> {
+  return invoke("cancel_transcription");
+}
+
 // App lifecycle commands (via @tauri-apps/plugin-process)
 export { relaunch as restartApp } from "@tauri-apps/plugin-process";
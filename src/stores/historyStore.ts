@@ -7,6 +7,9 @@ import {
   clearHistory as clearHistoryCommand,
 } from "@/lib/tauri";
 
+/** Number of most recent records kept in the store */
+const HISTORY_PAGE_SIZE = 100;
+
 interface HistoryState {
   records: TranscriptionRecord[];
   isLoading: boolean;
@@ -27,8 +30,8 @@ export const useHistoryStore = create<HistoryState>((set) => ({
   loadHistory: async () => {
     set({ isLoading: true, error: null });
     try {
-      const records = await getHistory();
-      set({ records, isLoading: false });
+      const page = await getHistory(0, HISTORY_PAGE_SIZE);
+      set({ records: page.records, isLoading: false });
     } catch (error) {
       set({ error: String(error), isLoading: false });
     }
@@ -38,7 +41,7 @@ export const useHistoryStore = create<HistoryState>((set) => ({
     try {
       const record = await addHistoryCommand(text, durationMs, model);
       set((state) => ({
-        records: [record, ...state.records].slice(0, 100),
+        records: [record, ...state.records].slice(0, HISTORY_PAGE_SIZE),
       }));
     } catch (error) {
       console.error("Failed to add to history:", error);